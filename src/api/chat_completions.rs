@@ -1,31 +1,31 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct ChatCompletionContentPartText {
     pub text: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct ImageUrl {
     pub url: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct ChatCompletionContentPartImage {
     pub image_url: ImageUrl,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct VideoUrl {
     pub url: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct ChatCompletionContentPartVideo {
     pub video_url: VideoUrl,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ChatCompletionContentPart {
     Text(ChatCompletionContentPartText),
@@ -33,37 +33,50 @@ pub enum ChatCompletionContentPart {
     VideoUrl(ChatCompletionContentPartVideo),
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(untagged)]
 pub enum ChatCompletionSystemMessageContent {
     Text(String),
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(untagged)]
 pub enum ChatCompletionUserMessageContent {
     Text(String),
     Array(Vec<ChatCompletionContentPart>),
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum ChatCompletionAssistantMessageContent {
+    Text(String),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct ChatCompletionSystemMessage {
     pub content: ChatCompletionSystemMessageContent,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct ChatCompletionUserMessage {
     pub content: ChatCompletionUserMessageContent,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ChatCompletionAssistantMessage {
+    pub content: ChatCompletionAssistantMessageContent,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(tag = "role", rename_all = "lowercase")]
 pub enum ChatCompletionMessage {
     System(ChatCompletionSystemMessage),
     User(ChatCompletionUserMessage),
+    Assistant(ChatCompletionAssistantMessage),
 }
 
-#[derive(Debug, Serialize, Clone)]
+/// Chat completion request body, as sent to `/v1/chat/completions`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct CreateChatCompletionRequest {
     pub messages: Vec<ChatCompletionMessage>,
     pub model: String,
@@ -79,20 +92,36 @@ pub struct CreateChatCompletionRequest {
     pub frequency_penalty: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub presence_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n: Option<u32>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ChatCompletionResponseMessage {
     pub content: Option<String>,
     pub reasoning_content: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ChatCompletionChoice {
     pub message: ChatCompletionResponseMessage,
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CompletionUsage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    pub total_tokens: u32,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CreateChatCompletionResponse {
     pub choices: Vec<ChatCompletionChoice>,
+    #[serde(default)]
+    pub usage: Option<CompletionUsage>,
+    /// Set when the response was served from the client's cache rather than the server.
+    #[serde(skip)]
+    pub cached: bool,
 }
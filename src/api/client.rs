@@ -1,12 +1,17 @@
 use std::collections::HashMap;
 
-use reqwest::Client;
+use reqwest::header::RETRY_AFTER;
+use reqwest::{Client, StatusCode};
 use serde::Serialize;
 use serde::de::DeserializeOwned;
 
 use super::chat_completions::*;
 use super::models::*;
 use crate::error::{ApiErrorDetail, ApiErrorResponse, PerceptronError};
+use crate::rate_limit::{self, RateLimiter};
+
+/// Times a request rejected with HTTP 429 and a `retry-after` header is sent again.
+const RATE_LIMITED_RETRIES: usize = 3;
 
 /// Low-level HTTP client for the Perceptron API.
 #[derive(Clone, Debug)]
@@ -15,6 +20,7 @@ pub struct ApiClient {
     pub base_url: String,
     pub api_key: Option<String>,
     pub headers: HashMap<String, String>,
+    pub rate_limiter: Option<RateLimiter>,
 }
 
 impl ApiClient {
@@ -24,6 +30,7 @@ impl ApiClient {
             base_url: "https://api.perceptron.inc".to_string(),
             api_key: None,
             headers: HashMap::new(),
+            rate_limiter: None,
         }
     }
 
@@ -42,19 +49,37 @@ impl ApiClient {
         &self,
         request: CreateChatCompletionRequest,
     ) -> Result<CreateChatCompletionResponse, PerceptronError> {
-        self.post("/v1/chat/completions", &request).await
+        let estimated = rate_limit::estimate_tokens(&request);
+        let response: CreateChatCompletionResponse = self.post("/v1/chat/completions", &request, estimated).await?;
+        if let (Some(limiter), Some(usage)) = (&self.rate_limiter, &response.usage) {
+            limiter.settle(estimated, usage.total_tokens);
+        }
+        Ok(response)
     }
 
     async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, PerceptronError> {
-        self.send(self.http.get(format!("{}{}", self.base_url, path))).await
+        self.send(self.http.get(format!("{}{}", self.base_url, path)), 0).await
     }
 
-    async fn post<T: DeserializeOwned>(&self, path: &str, body: &impl Serialize) -> Result<T, PerceptronError> {
-        self.send(self.http.post(format!("{}{}", self.base_url, path)).json(body))
+    async fn post<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &impl Serialize,
+        tokens: u32,
+    ) -> Result<T, PerceptronError> {
+        self.send(self.http.post(format!("{}{}", self.base_url, path)).json(body), tokens)
             .await
     }
 
-    async fn send<T: DeserializeOwned>(&self, mut req_builder: reqwest::RequestBuilder) -> Result<T, PerceptronError> {
+    /// Send a request costing an estimated `tokens`, waiting for the rate limiter first.
+    ///
+    /// With a rate limiter, a request rejected with HTTP 429 and a `retry-after` header is sent
+    /// again once the limiter has waited out the delay.
+    async fn send<T: DeserializeOwned>(
+        &self,
+        mut req_builder: reqwest::RequestBuilder,
+        tokens: u32,
+    ) -> Result<T, PerceptronError> {
         if let Some(key) = &self.api_key {
             req_builder = req_builder.bearer_auth(key);
         }
@@ -63,10 +88,34 @@ impl ApiClient {
             req_builder = req_builder.header(name, value);
         }
 
-        let response = req_builder
-            .send()
-            .await
-            .map_err(|e| PerceptronError::RequestFailed(e.to_string()))?;
+        let mut retries = 0;
+        let response = loop {
+            let retry = req_builder.try_clone();
+            if let Some(limiter) = &self.rate_limiter {
+                limiter.acquire(tokens).await;
+            }
+            let response = req_builder
+                .send()
+                .await
+                .map_err(|e| PerceptronError::RequestFailed(e.to_string()))?;
+            let Some(limiter) = &self.rate_limiter else {
+                break response;
+            };
+            limiter.update(response.headers());
+            match retry {
+                Some(retry)
+                    if response.status() == StatusCode::TOO_MANY_REQUESTS
+                        && response.headers().contains_key(RETRY_AFTER)
+                        && retries < RATE_LIMITED_RETRIES =>
+                {
+                    // The rejected request used no tokens.
+                    limiter.settle(tokens, 0);
+                    retries += 1;
+                    req_builder = retry;
+                }
+                _ => break response,
+            }
+        };
 
         if !response.status().is_success() {
             return Err(Self::error_from_response(response).await);
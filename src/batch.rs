@@ -0,0 +1,266 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tokio::sync::watch;
+use tokio::task::JoinSet;
+
+use crate::client::{Perceptron, PerceptronClient};
+use crate::error::PerceptronError;
+use crate::layout::OcrLayoutResponse;
+use crate::types::*;
+
+/// Requests run at once unless [`Batch::concurrency`] is set.
+const DEFAULT_CONCURRENCY: usize = 8;
+
+/// A request that can run as part of a [`Batch`].
+pub trait BatchTask: Send + 'static {
+    /// Result of the task.
+    type Output: Send + 'static;
+
+    /// Run the task on `client`.
+    fn run(self, client: &PerceptronClient) -> impl Future<Output = Result<Self::Output, PerceptronError>> + Send;
+}
+
+macro_rules! batch_tasks {
+    ($($request:ty => $method:ident -> $output:ty),* $(,)?) => {
+        $(
+            impl BatchTask for $request {
+                type Output = $output;
+
+                fn run(self, client: &PerceptronClient) -> impl Future<Output = Result<$output, PerceptronError>> + Send {
+                    client.$method(self)
+                }
+            }
+        )*
+    };
+}
+
+batch_tasks! {
+    QuestionRequest => question -> PointingResponse,
+    AnalyzeRequest => analyze -> PointingResponse,
+    CaptionRequest => caption -> PointingResponse,
+    OcrRequest => ocr -> TextResponse,
+    DetectRequest => detect -> PointingResponse,
+    ClassifyRequest => classify -> ClassifyResponse,
+    CountRequest => count -> CountResponse,
+    GroundRequest => ground -> GroundResponse,
+    CompareRequest => compare -> CompareResponse,
+    DocumentRequest => document -> DocumentResponse,
+    VideoSummaryRequest => summarize_video -> VideoSummaryResponse,
+    LocateRequest => locate -> LocateResponse,
+    TagRequest => tag -> TagResponse,
+    ChatRequest => chat -> ChatResponse,
+}
+
+/// Run [`Perceptron::ocr_layout`] in a batch, instead of plain [`Perceptron::ocr`].
+#[derive(Debug, Clone)]
+pub struct OcrLayoutTask(pub OcrRequest);
+
+impl BatchTask for OcrLayoutTask {
+    type Output = OcrLayoutResponse;
+
+    fn run(self, client: &PerceptronClient) -> impl Future<Output = Result<OcrLayoutResponse, PerceptronError>> + Send {
+        client.ocr_layout(self.0)
+    }
+}
+
+/// Progress of a running batch, reported after each request finishes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchProgress {
+    /// Position of the finished request in the batch.
+    pub index: usize,
+    /// Requests finished so far, including failures.
+    pub completed: usize,
+    /// Requests that returned an error so far.
+    pub failed: usize,
+    /// Requests in the batch.
+    pub total: usize,
+}
+
+/// Cancels a running batch. Clones cancel the same batch.
+///
+/// Requests not yet started are skipped and requests in flight are dropped; both
+/// finish with [`PerceptronError::Cancelled`].
+#[derive(Debug, Clone)]
+pub struct CancelToken {
+    sender: Arc<watch::Sender<bool>>,
+    parent: Option<Arc<CancelToken>>,
+}
+
+impl Default for CancelToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CancelToken {
+    /// Create a token that has not been cancelled.
+    pub fn new() -> Self {
+        Self {
+            sender: Arc::new(watch::Sender::new(false)),
+            parent: None,
+        }
+    }
+
+    /// A token cancelled along with this one, which can also be cancelled on its own
+    /// without affecting this one.
+    pub(crate) fn child(&self) -> Self {
+        Self {
+            sender: Arc::new(watch::Sender::new(false)),
+            parent: Some(Arc::new(self.clone())),
+        }
+    }
+
+    /// Cancel every batch using this token.
+    pub fn cancel(&self) {
+        self.sender.send_replace(true);
+    }
+
+    /// Whether [`Self::cancel`] has been called, on this token or the one it was made from.
+    pub fn is_cancelled(&self) -> bool {
+        *self.sender.borrow() || self.parent.as_ref().is_some_and(|parent| parent.is_cancelled())
+    }
+
+    async fn cancelled(&self) {
+        // The sender lives in `self`, so waiting can only end by cancellation.
+        let own = async {
+            let _ = self.sender.subscribe().wait_for(|cancelled| *cancelled).await;
+        };
+        match &self.parent {
+            Some(parent) => {
+                tokio::select! {
+                    _ = own => {}
+                    _ = Box::pin(parent.cancelled()) => {}
+                }
+            }
+            None => own.await,
+        }
+    }
+}
+
+/// Runs many requests with bounded concurrency. Created with [`PerceptronClient::batch`].
+pub struct Batch<R: BatchTask> {
+    client: PerceptronClient,
+    requests: Vec<R>,
+    concurrency: usize,
+    progress: Option<Box<dyn FnMut(BatchProgress) + Send>>,
+    cancel: CancelToken,
+}
+
+impl<R: BatchTask> Batch<R> {
+    pub(crate) fn new(client: PerceptronClient, requests: Vec<R>) -> Self {
+        Self {
+            client,
+            requests,
+            concurrency: DEFAULT_CONCURRENCY,
+            progress: None,
+            cancel: CancelToken::new(),
+        }
+    }
+
+    /// Maximum number of requests in flight at once. Defaults to 8.
+    pub fn concurrency(mut self, limit: usize) -> Self {
+        self.concurrency = limit.max(1);
+        self
+    }
+
+    /// Call `callback` each time a request finishes.
+    pub fn on_progress(mut self, callback: impl FnMut(BatchProgress) + Send + 'static) -> Self {
+        self.progress = Some(Box::new(callback));
+        self
+    }
+
+    /// Stop the batch when `token` is cancelled.
+    pub fn cancel_token(mut self, token: CancelToken) -> Self {
+        self.cancel = token;
+        self
+    }
+
+    /// Run every request and return their results in input order.
+    ///
+    /// A failed request does not stop the others; its error takes its place in the results.
+    pub async fn run(self) -> Vec<Result<R::Output, PerceptronError>> {
+        let mut results: Vec<Option<Result<R::Output, PerceptronError>>> =
+            (0..self.requests.len()).map(|_| None).collect();
+        self.for_each(|index, result| results[index] = Some(result)).await;
+        results
+            .into_iter()
+            .map(|result| result.unwrap_or(Err(PerceptronError::Cancelled)))
+            .collect()
+    }
+
+    /// Run every request, passing each result to `callback` with its input position as soon
+    /// as it finishes. Requests skipped or dropped by cancellation are not passed on.
+    pub async fn for_each(self, mut callback: impl FnMut(usize, Result<R::Output, PerceptronError>)) {
+        let Self {
+            client,
+            requests,
+            concurrency,
+            mut progress,
+            cancel,
+        } = self;
+        let mut pending = requests.into_iter().enumerate();
+        let mut tasks = JoinSet::new();
+        let mut indices = HashMap::new();
+        let mut report = BatchProgress {
+            index: 0,
+            completed: 0,
+            failed: 0,
+            total: pending.len(),
+        };
+
+        loop {
+            while tasks.len() < concurrency && !cancel.is_cancelled() {
+                let Some((index, request)) = pending.next() else {
+                    break;
+                };
+                let client = client.clone();
+                let handle = tasks.spawn(async move { request.run(&client).await });
+                indices.insert(handle.id(), index);
+            }
+            let joined = tokio::select! {
+                joined = tasks.join_next_with_id() => joined,
+                _ = cancel.cancelled() => {
+                    tasks.abort_all();
+                    break;
+                }
+            };
+            let Some(joined) = joined else {
+                break;
+            };
+            let (id, result) = match joined {
+                Ok((id, result)) => (id, result),
+                Err(e) => (e.id(), Err(PerceptronError::RequestFailed(e.to_string()))),
+            };
+            let Some(index) = indices.remove(&id) else {
+                continue;
+            };
+            report.index = index;
+            report.completed += 1;
+            report.failed += usize::from(result.is_err());
+            callback(index, result);
+            if let Some(progress) = progress.as_mut() {
+                progress(report);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn child_tokens_follow_their_parent_only() {
+        let parent = CancelToken::new();
+        let child = parent.child();
+        child.cancel();
+        assert!(child.is_cancelled());
+        assert!(!parent.is_cancelled());
+
+        let child = parent.child();
+        parent.cancel();
+        assert!(child.is_cancelled());
+        child.cancelled().await;
+    }
+}
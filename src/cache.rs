@@ -0,0 +1,350 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::api::chat_completions::{CreateChatCompletionRequest, CreateChatCompletionResponse};
+
+/// Storage for cached chat completion responses, keyed by request hash.
+///
+/// Implementations should treat failures as misses: a cache that cannot be read or written
+/// only costs a request. The client calls the cache on Tokio's blocking thread pool, so
+/// implementations may do blocking I/O.
+pub trait Cache: Send + Sync {
+    /// The value stored under `key`, unless it is missing or expired.
+    fn get(&self, key: &str) -> Option<String>;
+
+    /// Store `value` under `key`, expiring after `ttl` if set.
+    fn put(&self, key: &str, value: String, ttl: Option<Duration>);
+}
+
+/// In-memory cache that evicts the least recently used entry once full.
+/// Clones share the same entries.
+#[derive(Debug, Clone)]
+pub struct MemoryCache {
+    state: Arc<Mutex<MemoryState>>,
+}
+
+#[derive(Debug, Default)]
+struct MemoryState {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<String, MemoryEntry>,
+    recency: BTreeMap<u64, String>,
+}
+
+#[derive(Debug)]
+struct MemoryEntry {
+    value: String,
+    expires: Option<Instant>,
+    used: u64,
+}
+
+impl MemoryState {
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.recency.remove(&entry.used);
+        }
+    }
+}
+
+impl MemoryCache {
+    /// Create a cache holding at most `capacity` responses.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(MemoryState {
+                capacity: capacity.max(1),
+                ..MemoryState::default()
+            })),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MemoryState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Number of responses currently stored, including expired ones not yet evicted.
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    /// Whether the cache holds no responses.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Cache for MemoryCache {
+    fn get(&self, key: &str) -> Option<String> {
+        let mut state = self.lock();
+        let state = &mut *state;
+        let entry = state.entries.get_mut(key)?;
+        if entry.expires.is_some_and(|expires| expires <= Instant::now()) {
+            state.remove(key);
+            return None;
+        }
+        state.tick += 1;
+        state.recency.remove(&entry.used);
+        entry.used = state.tick;
+        state.recency.insert(entry.used, key.to_string());
+        Some(entry.value.clone())
+    }
+
+    fn put(&self, key: &str, value: String, ttl: Option<Duration>) {
+        let mut state = self.lock();
+        state.remove(key);
+        while state.entries.len() >= state.capacity {
+            let Some((_, oldest)) = state.recency.pop_first() else {
+                break;
+            };
+            state.entries.remove(&oldest);
+        }
+        state.tick += 1;
+        let used = state.tick;
+        state.recency.insert(used, key.to_string());
+        state.entries.insert(
+            key.to_string(),
+            MemoryEntry {
+                value,
+                expires: ttl.map(|ttl| Instant::now() + ttl),
+                used,
+            },
+        );
+    }
+}
+
+/// Distinguishes the temporary files of concurrent writes.
+static WRITE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// On-disk cache storing one JSON file per response in a directory.
+///
+/// Expired entries are removed when read; call [`DiskCache::purge_expired`] to remove the
+/// rest, e.g. on startup.
+#[derive(Debug, Clone)]
+pub struct DiskCache {
+    dir: PathBuf,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DiskEntry {
+    /// Expiry as seconds since the Unix epoch.
+    expires: Option<f64>,
+    value: String,
+}
+
+fn unix_now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64()
+}
+
+impl DiskCache {
+    /// Store responses in `dir`, which is created when the first response is stored.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.json"))
+    }
+
+    /// Remove expired and unreadable entries, and temporary files left by interrupted writes.
+    /// Returns the number of files removed.
+    pub fn purge_expired(&self) -> std::io::Result<usize> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e),
+        };
+        let now = unix_now();
+        let mut removed = 0;
+        for entry in entries {
+            let path = entry?.path();
+            let stale = match path.extension().and_then(|ext| ext.to_str()) {
+                Some("json") => fs::read_to_string(&path)
+                    .ok()
+                    .and_then(|json| serde_json::from_str::<DiskEntry>(&json).ok())
+                    .is_none_or(|entry| entry.expires.is_some_and(|expires| expires <= now)),
+                // A write in progress may still rename its file, so leave recent ones alone.
+                Some("tmp") => fs::metadata(&path)
+                    .and_then(|meta| meta.modified())
+                    .is_ok_and(|modified| modified.elapsed().is_ok_and(|age| age > Duration::from_secs(3600))),
+                _ => false,
+            };
+            if stale && fs::remove_file(&path).is_ok() {
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+}
+
+impl Cache for DiskCache {
+    fn get(&self, key: &str) -> Option<String> {
+        let path = self.path(key);
+        let entry: DiskEntry = serde_json::from_str(&fs::read_to_string(&path).ok()?).ok()?;
+        if entry.expires.is_some_and(|expires| expires <= unix_now()) {
+            let _ = fs::remove_file(path);
+            return None;
+        }
+        Some(entry.value)
+    }
+
+    fn put(&self, key: &str, value: String, ttl: Option<Duration>) {
+        let entry = DiskEntry {
+            expires: ttl.map(|ttl| unix_now() + ttl.as_secs_f64()),
+            value,
+        };
+        let Ok(json) = serde_json::to_string(&entry) else {
+            return;
+        };
+        // Write then rename, so readers never see a partial entry. Each write has its own
+        // temporary file, so concurrent writes of the same key cannot interleave.
+        let path = self.path(key);
+        let partial = self.dir.join(format!(
+            "{key}.{}-{}.tmp",
+            std::process::id(),
+            WRITE_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        if fs::create_dir_all(&self.dir).is_ok() && fs::write(&partial, json).is_ok() {
+            let _ = fs::rename(&partial, &path);
+        }
+    }
+}
+
+/// The client's cache backend and when to use it.
+#[derive(Clone, Default)]
+pub(crate) struct CacheLayer {
+    pub(crate) backend: Option<Arc<dyn Cache>>,
+    pub(crate) ttl: Option<Duration>,
+    pub(crate) force: bool,
+    pub(crate) bypass: bool,
+}
+
+impl fmt::Debug for CacheLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CacheLayer")
+            .field("enabled", &self.backend.is_some())
+            .field("ttl", &self.ttl)
+            .field("force", &self.force)
+            .field("bypass", &self.bypass)
+            .finish()
+    }
+}
+
+impl CacheLayer {
+    /// The cache key for `request`, unless the request should not be cached. Sampled requests
+    /// (temperature above 0) are only cached when forced. A request without a temperature
+    /// counts as deterministic and is cached.
+    pub(crate) fn key(&self, base_url: &str, request: &CreateChatCompletionRequest) -> Option<String> {
+        self.backend.as_ref()?;
+        if !self.force && request.temperature.is_some_and(|t| t > 0.0) {
+            return None;
+        }
+        let body = serde_json::to_string(request).ok()?;
+        Some(request_hash(&[base_url.as_bytes(), b"\n", body.as_bytes()]))
+    }
+
+    /// The cached response for `key`, unless bypassed.
+    pub(crate) async fn get(&self, key: &str) -> Option<CreateChatCompletionResponse> {
+        if self.bypass {
+            return None;
+        }
+        let backend = Arc::clone(self.backend.as_ref()?);
+        let key = key.to_string();
+        let value = tokio::task::spawn_blocking(move || backend.get(&key)).await.ok()??;
+        let response: CreateChatCompletionResponse = serde_json::from_str(&value).ok()?;
+        Some(CreateChatCompletionResponse {
+            cached: true,
+            ..response
+        })
+    }
+
+    pub(crate) async fn put(&self, key: &str, response: &CreateChatCompletionResponse) {
+        if let (Some(backend), Ok(value)) = (&self.backend, serde_json::to_string(response)) {
+            let (backend, key, ttl) = (Arc::clone(backend), key.to_string(), self.ttl);
+            let _ = tokio::task::spawn_blocking(move || backend.put(&key, value, ttl)).await;
+        }
+    }
+}
+
+/// 128-bit FNV-1a hash as hex. Stable across platforms and releases, so disk caches survive
+/// upgrades.
+fn request_hash(parts: &[&[u8]]) -> String {
+    const OFFSET: u128 = 0x6c62272e07bb014262b821756295c58d;
+    const PRIME: u128 = 0x0000000001000000000000000000013b;
+    let hash = parts
+        .iter()
+        .flat_map(|part| part.iter())
+        .fold(OFFSET, |hash, &byte| (hash ^ u128::from(byte)).wrapping_mul(PRIME));
+    format!("{hash:032x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_cache_evicts_least_recently_used() {
+        let cache = MemoryCache::new(2);
+        cache.put("a", "1".to_string(), None);
+        cache.put("b", "2".to_string(), None);
+        assert_eq!(cache.get("a").as_deref(), Some("1"));
+        cache.put("c", "3".to_string(), None);
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.get("a").as_deref(), Some("1"));
+        assert_eq!(cache.get("c").as_deref(), Some("3"));
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn memory_cache_expires_entries() {
+        let cache = MemoryCache::new(4);
+        cache.put("a", "1".to_string(), Some(Duration::ZERO));
+        cache.put("b", "2".to_string(), Some(Duration::from_secs(60)));
+        assert_eq!(cache.get("a"), None);
+        assert_eq!(cache.get("b").as_deref(), Some("2"));
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn disk_cache_purges_expired_entries() {
+        let dir = std::env::temp_dir().join(format!("perceptron-cache-purge-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let cache = DiskCache::new(&dir);
+        assert_eq!(cache.purge_expired().unwrap(), 0);
+
+        std::thread::scope(|scope| {
+            for i in 0..4 {
+                let cache = &cache;
+                scope.spawn(move || cache.put("same", i.to_string(), None));
+            }
+        });
+        cache.put("old", "1".to_string(), Some(Duration::ZERO));
+        cache.put("kept", "2".to_string(), Some(Duration::from_secs(60)));
+        fs::write(dir.join("broken.json"), "{").unwrap();
+
+        assert!(cache.get("same").is_some());
+        assert_eq!(cache.purge_expired().unwrap(), 2);
+        let mut files: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        files.sort();
+        assert_eq!(files, ["kept.json", "same.json"]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn hash_is_stable() {
+        assert_eq!(request_hash(&[b""]), "6c62272e07bb014262b821756295c58d");
+        assert_ne!(request_hash(&[b"ab"]), request_hash(&[b"ba"]));
+        assert_eq!(request_hash(&[b"a", b"b"]), request_hash(&[b"ab"]));
+    }
+}
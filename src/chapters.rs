@@ -0,0 +1,193 @@
+use serde::{Deserialize, Serialize};
+
+use crate::parsing;
+use crate::pointing::ClipTimestamp;
+use crate::types::OutputFormat;
+
+/// A chapter of a summarized video.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Chapter {
+    /// Short chapter title.
+    pub title: String,
+    /// Time span covered by the chapter. A [`ClipTimestamp::Moment`] is a final chapter
+    /// whose end is unknown because the video duration was not given.
+    pub range: ClipTimestamp,
+    /// What happens in the chapter, if the model described it.
+    pub description: Option<String>,
+    /// Whether the range was trimmed or clamped from what the model returned.
+    pub adjusted: bool,
+}
+
+/// The prose before the first chapter tag, without annotation tags.
+pub(crate) fn summary(text: &str) -> Option<String> {
+    let prose: Vec<&str> = text.lines().take_while(|line| !line.contains("<clip")).collect();
+    Some(parsing::strip_tags(&prose.join("\n"))).filter(|s| !s.is_empty())
+}
+
+/// Read one chapter per `<clip>` line, with the text after the tag as its description.
+fn parse_chapters(text: &str) -> Vec<(String, ClipTimestamp, Option<String>)> {
+    text.lines()
+        .filter_map(|line| {
+            let clip = parsing::extract(line, Some(&OutputFormat::Clip))?
+                .clips
+                .into_iter()
+                .next()?;
+            let description = parsing::strip_tags(line);
+            let description = description.trim_start_matches([':', '-', '–', '—', ' ']);
+            let title = clip.mention.unwrap_or_default();
+            Some((
+                title.trim().to_string(),
+                clip.timestamp,
+                Some(description.to_string()).filter(|d| !d.is_empty()),
+            ))
+        })
+        .collect()
+}
+
+/// Build ordered, non-overlapping chapters from a summary answer.
+///
+/// Chapters are sorted by start time, and of chapters starting at the same time only the
+/// first is kept. A chapter given as a single moment runs until the next chapter starts or
+/// the end of the video; a final moment stays a moment when the duration is unknown.
+/// Overlapping chapters are trimmed so each ends where the next begins, and with a known
+/// `duration` ranges are clamped to the video; chapters starting after the end, or left
+/// with no length, are dropped.
+pub(crate) fn build_chapters(text: &str, duration: Option<f32>) -> Vec<Chapter> {
+    let mut parsed: Vec<(String, f32, Option<f32>, Option<String>)> = parse_chapters(text)
+        .into_iter()
+        .map(|(title, timestamp, description)| match timestamp {
+            ClipTimestamp::Moment(t) => (title, t, None, description),
+            ClipTimestamp::Range { start, end } => (title, start.min(end), Some(start.max(end)), description),
+        })
+        .collect();
+    parsed.sort_by(|a, b| a.1.total_cmp(&b.1));
+    parsed.retain(|(_, start, end, _)| end.is_none_or(|end| end > *start));
+    parsed.dedup_by(|later, earlier| later.1 == earlier.1);
+
+    let starts: Vec<f32> = parsed.iter().map(|c| c.1).collect();
+    let mut chapters = Vec::new();
+    for (i, (title, start, end, description)) in parsed.into_iter().enumerate() {
+        let next_start = starts.get(i + 1).copied();
+        if duration.is_some_and(|d| start >= d) {
+            continue;
+        }
+        let limit = duration.unwrap_or(f32::INFINITY);
+        let clamped_start = start.max(0.0);
+        let mut adjusted = clamped_start != start;
+        let end = match end {
+            Some(end) => {
+                let bounded = next_start.map_or(end, |next| end.min(next)).min(limit);
+                adjusted |= bounded != end;
+                Some(bounded)
+            }
+            None => next_start.or(duration).map(|end| end.min(limit)),
+        };
+        if end.is_some_and(|end| end <= clamped_start) {
+            continue;
+        }
+        chapters.push(Chapter {
+            title: if title.is_empty() {
+                format!("Chapter {}", chapters.len() + 1)
+            } else {
+                title
+            },
+            range: match end {
+                Some(end) => ClipTimestamp::Range {
+                    start: clamped_start,
+                    end,
+                },
+                None => ClipTimestamp::Moment(clamped_start),
+            },
+            description,
+            adjusted,
+        });
+    }
+    chapters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranges(chapters: &[Chapter]) -> Vec<(f32, f32, bool)> {
+        chapters
+            .iter()
+            .map(|c| match c.range {
+                ClipTimestamp::Range { start, end } => (start, end, c.adjusted),
+                ClipTimestamp::Moment(t) => panic!("expected a range, got a moment at {t}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parses_summary_and_chapters() {
+        let text = r#"A cooking show where the host bakes bread.
+<clip mention="Intro" t="0 12"/> The host introduces the recipe.
+<clip mention="Kneading" t="12 60"/>: Mixing and kneading the dough.
+<clip mention="Baking" t="60 95"/>"#;
+        assert_eq!(
+            summary(text).as_deref(),
+            Some("A cooking show where the host bakes bread.")
+        );
+        let chapters = build_chapters(text, None);
+        assert_eq!(chapters.len(), 3);
+        assert_eq!(chapters[0].title, "Intro");
+        assert_eq!(
+            chapters[0].description.as_deref(),
+            Some("The host introduces the recipe.")
+        );
+        assert_eq!(
+            chapters[1].description.as_deref(),
+            Some("Mixing and kneading the dough.")
+        );
+        assert_eq!(chapters[2].description, None);
+        assert_eq!(
+            ranges(&chapters),
+            [(0.0, 12.0, false), (12.0, 60.0, false), (60.0, 95.0, false)]
+        );
+    }
+
+    #[test]
+    fn orders_trims_and_clamps() {
+        let text = r#"<clip mention="Middle" t="30 50"/>
+<clip mention="Start" t="0 40"/>
+<clip mention="End" t="50 130"/>
+<clip mention="Credits" t="125 140"/>"#;
+        let chapters = build_chapters(text, Some(120.0));
+        let titles: Vec<_> = chapters.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(titles, ["Start", "Middle", "End"]);
+        assert_eq!(
+            ranges(&chapters),
+            [(0.0, 30.0, true), (30.0, 50.0, false), (50.0, 120.0, true)]
+        );
+    }
+
+    #[test]
+    fn moments_run_until_next_chapter() {
+        let text = "<clip mention=\"A\" t=0/>\n<clip t=20/>\n<clip mention=\"C\" t=45/>";
+        assert_eq!(
+            ranges(&build_chapters(text, Some(60.0))),
+            [(0.0, 20.0, false), (20.0, 45.0, false), (45.0, 60.0, false)]
+        );
+        let chapters = build_chapters(text, None);
+        assert_eq!(chapters[1].title, "Chapter 2");
+        assert_eq!(chapters[2].range, ClipTimestamp::Moment(45.0));
+    }
+
+    #[test]
+    fn drops_chapters_without_length() {
+        let text = r#"<clip mention="Intro" t=0/>
+<clip mention="Recipe" t=20/>
+<clip mention="Ingredients" t=20/>
+<clip mention="Pause" t="30 30"/>
+<clip mention="Baking" t=45/>"#;
+        let chapters = build_chapters(text, Some(60.0));
+        let titles: Vec<_> = chapters.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(titles, ["Intro", "Recipe", "Baking"]);
+        assert_eq!(
+            ranges(&chapters),
+            [(0.0, 20.0, false), (20.0, 45.0, false), (45.0, 60.0, false)]
+        );
+    }
+}
@@ -0,0 +1,291 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Label reported for answers that do not match any candidate label.
+pub const UNKNOWN_LABEL: &str = "unknown";
+
+/// Minimum normalized edit similarity for a paraphrased answer to match a label.
+const MIN_SIMILARITY: f32 = 0.8;
+
+// A dash only separates the score when surrounded by spaces, so labels like "covid-19" stay whole.
+static SCORE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(.*?)(?:\s*[:=(]|\s+[-–]\s)\s*(\d+(?:\.\d+)?)\s*(%?)\s*\)?\s*$")
+        .expect("regex creation should never fail here")
+});
+static LIST_MARKER_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*(?:[-*•]|\d+[.)])\s*").expect("regex creation should never fail here"));
+
+/// A candidate label with the model's confidence.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct LabelScore {
+    /// One of the requested labels, exactly as given, or [`UNKNOWN_LABEL`].
+    pub label: String,
+    /// Confidence between 0 and 1. Labels listed without a confidence score 1.
+    pub score: f32,
+}
+
+/// Lowercase, drop punctuation and collapse whitespace.
+fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Drop a trailing plural `s` from every word.
+fn singular(text: &str) -> String {
+    text.split(' ')
+        .map(|w| {
+            if w.len() > 3 {
+                w.strip_suffix('s').unwrap_or(w)
+            } else {
+                w
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+fn similarity(a: &str, b: &str) -> f32 {
+    let len = a.chars().count().max(b.chars().count());
+    if len == 0 {
+        return 1.0;
+    }
+    1.0 - levenshtein(a, b) as f32 / len as f32
+}
+
+/// Whether `needle` appears in `haystack` on word boundaries.
+fn contains_words(haystack: &str, needle: &str) -> bool {
+    format!(" {haystack} ").contains(&format!(" {needle} "))
+}
+
+/// Whether `label` would be confused with [`UNKNOWN_LABEL`] in classification results.
+pub(crate) fn is_reserved_label(label: &str) -> bool {
+    normalize(label) == UNKNOWN_LABEL
+}
+
+/// Normalized, singular form used to compare labels.
+pub(crate) fn label_key(text: &str) -> String {
+    singular(&normalize(text))
+}
+
+/// Map an answer to the label it names, ignoring only case, punctuation and plurals.
+pub(crate) fn match_exact<'a>(answer: &str, labels: &'a [String]) -> Option<&'a str> {
+    let answer = label_key(answer);
+    if answer.is_empty() {
+        return None;
+    }
+    labels
+        .iter()
+        .find(|label| label_key(label) == answer)
+        .map(String::as_str)
+}
+
+/// Map a free-form answer to the closest candidate label.
+///
+/// Tries, in order: an exact match after normalization and singularization, the longest
+/// label contained in the answer, then the most similar label by edit distance.
+pub(crate) fn match_label<'a>(answer: &str, labels: &'a [String]) -> Option<&'a str> {
+    let answer = label_key(answer);
+    if answer.is_empty() {
+        return None;
+    }
+    let candidates: Vec<(&str, String)> = labels
+        .iter()
+        .map(|label| (label.as_str(), label_key(label)))
+        .filter(|(_, normalized)| !normalized.is_empty())
+        .collect();
+
+    if let Some((label, _)) = candidates.iter().find(|(_, normalized)| *normalized == answer) {
+        return Some(label);
+    }
+    if let Some((label, _)) = candidates
+        .iter()
+        .filter(|(_, normalized)| contains_words(&answer, normalized))
+        .max_by_key(|(_, normalized)| normalized.len())
+    {
+        return Some(label);
+    }
+    candidates
+        .iter()
+        .map(|(label, normalized)| (*label, similarity(&answer, normalized)))
+        .filter(|(_, score)| *score >= MIN_SIMILARITY)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(label, _)| label)
+}
+
+/// Parse one `label: confidence` line into the label text and an optional confidence.
+fn parse_line(line: &str) -> (&str, Option<f32>) {
+    let line = LIST_MARKER_REGEX
+        .find(line)
+        .map_or(line, |marker| &line[marker.end()..])
+        .trim();
+    match SCORE_REGEX.captures(line) {
+        Some(caps) => {
+            let label = caps.get(1).map_or("", |m| m.as_str());
+            let value: f32 = caps[2].parse().unwrap_or(0.0);
+            let score = if caps[3].is_empty() && value <= 1.0 {
+                value
+            } else {
+                value / 100.0
+            };
+            (
+                label.trim_matches(|c: char| c == '`' || c == '*' || c.is_whitespace()),
+                Some(score.clamp(0.0, 1.0)),
+            )
+        }
+        None => (line, None),
+    }
+}
+
+/// Rank the labels mentioned in a classification answer.
+///
+/// Each non-empty line is matched against `labels`. Lines that carry a confidence but match
+/// no label are collected under [`UNKNOWN_LABEL`], as is the whole answer when nothing
+/// matches. Duplicate labels keep their highest score.
+pub(crate) fn rank_labels(text: &str, labels: &[String]) -> Vec<LabelScore> {
+    let mut scores: Vec<LabelScore> = Vec::new();
+    let mut unknown: Option<f32> = None;
+    for line in text.lines().filter(|l| !l.trim().is_empty()) {
+        let (answer, score) = parse_line(line);
+        match (match_label(answer, labels), score) {
+            (Some(label), score) => add_score(&mut scores, label, score.unwrap_or(1.0)),
+            (None, Some(score)) => unknown = Some(unknown.unwrap_or(0.0).max(score)),
+            (None, None) => {}
+        }
+    }
+    if scores.is_empty() && !text.trim().is_empty() {
+        unknown.get_or_insert(1.0);
+    }
+    if let Some(score) = unknown {
+        add_score(&mut scores, UNKNOWN_LABEL, score);
+    }
+
+    scores.sort_by(|a, b| b.score.total_cmp(&a.score));
+    scores
+}
+
+/// Record `score` for `label`, keeping the highest score when the label repeats.
+fn add_score(scores: &mut Vec<LabelScore>, label: &str, score: f32) {
+    match scores.iter_mut().find(|s| s.label == label) {
+        Some(existing) => existing.score = existing.score.max(score),
+        None => scores.push(LabelScore {
+            label: label.to_string(),
+            score,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels() -> Vec<String> {
+        ["cat", "dog", "golden retriever", "Traffic Light"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn matches_paraphrases() {
+        let labels = labels();
+        assert_eq!(match_label("Cats", &labels), Some("cat"));
+        assert_eq!(match_label("traffic-light", &labels), Some("Traffic Light"));
+        assert_eq!(
+            match_label("It looks like a golden retriever dog.", &labels),
+            Some("golden retriever")
+        );
+        assert_eq!(match_label("golden retreiver", &labels), Some("golden retriever"));
+        assert_eq!(match_label("hamster", &labels), None);
+        assert_eq!(match_label("concatenate", &labels), None);
+    }
+
+    #[test]
+    fn exact_matches_ignore_only_case_punctuation_and_plurals() {
+        let labels = labels();
+        assert_eq!(match_exact("Cats", &labels), Some("cat"));
+        assert_eq!(match_exact("traffic-lights", &labels), Some("Traffic Light"));
+        assert_eq!(match_exact("a golden retriever", &labels), None);
+        assert_eq!(match_exact("golden retreiver", &labels), None);
+    }
+
+    #[test]
+    fn parses_confidences() {
+        assert_eq!(parse_line("1. cat: 0.9"), ("cat", Some(0.9)));
+        assert_eq!(parse_line("- **dog** (85%)"), ("dog", Some(0.85)));
+        assert_eq!(parse_line("`cat` - 70"), ("cat", Some(0.7)));
+        assert_eq!(parse_line("just a cat"), ("just a cat", None));
+    }
+
+    #[test]
+    fn keeps_hyphenated_digits_in_labels() {
+        assert_eq!(parse_line("covid-19"), ("covid-19", None));
+        assert_eq!(parse_line("F-16: 0.8"), ("F-16", Some(0.8)));
+        assert_eq!(parse_line("- F-16 (90%)"), ("F-16", Some(0.9)));
+        assert_eq!(parse_line("covid-19 - 0.3"), ("covid-19", Some(0.3)));
+
+        let labels = vec!["covid-19".to_string(), "F-16".to_string()];
+        let ranked = rank_labels("F-16\ncovid-19: 0.4", &labels);
+        assert_eq!(ranked[0].label, "F-16");
+        assert_eq!(ranked[0].score, 1.0);
+        assert_eq!(ranked[1].label, "covid-19");
+    }
+
+    #[test]
+    fn reserves_unknown_label() {
+        assert!(is_reserved_label("Unknown"));
+        assert!(is_reserved_label(" unknown. "));
+        assert!(!is_reserved_label("unknown soldier"));
+    }
+
+    #[test]
+    fn ranks_and_buckets_unknown() {
+        let ranked = rank_labels("dog: 0.2\nCats: 0.7\nhamster: 0.1\ncat: 0.5", &labels());
+        assert_eq!(
+            ranked,
+            vec![
+                LabelScore {
+                    label: "cat".to_string(),
+                    score: 0.7
+                },
+                LabelScore {
+                    label: "dog".to_string(),
+                    score: 0.2
+                },
+                LabelScore {
+                    label: UNKNOWN_LABEL.to_string(),
+                    score: 0.1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn unmatched_answer_is_unknown() {
+        let ranked = rank_labels("I cannot tell.", &labels());
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].label, UNKNOWN_LABEL);
+        assert_eq!(ranked[0].score, 1.0);
+        assert!(rank_labels("", &labels()).is_empty());
+    }
+}
@@ -1,24 +1,57 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
 use reqwest::Client;
+use tokio::task::JoinSet;
 
 use crate::api::ApiClient;
 use crate::api::chat_completions::*;
+use crate::batch::{Batch, BatchTask};
+use crate::cache::{Cache, CacheLayer};
+use crate::chapters;
+use crate::classification;
+use crate::comparison;
+use crate::counting;
+use crate::document::{self, Table};
+use crate::ensemble::{self, Ensemble};
 use crate::error::PerceptronError;
+use crate::events;
+use crate::jsonl::JsonlBatch;
+use crate::layout::{self, OcrLayoutResponse};
 use crate::media::Media;
 use crate::models::Model;
 use crate::parsing;
-use crate::prompting;
+use crate::pointing::{Pointing, Region};
+use crate::prompting::{PromptProfile, PromptRegistry};
+use crate::rate_limit::RateLimiter;
+use crate::tagging;
 use crate::types::*;
 
 /// Client for the Perceptron SDK.
 #[derive(Clone, Debug)]
 pub struct PerceptronClient {
     api: ApiClient,
+    prompts: PromptRegistry,
+    recorded: Option<Arc<Mutex<Vec<CreateChatCompletionRequest>>>>,
+    cache: CacheLayer,
+}
+
+impl Default for PerceptronClient {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl PerceptronClient {
     /// Create a new client with default settings.
     pub fn new() -> Self {
-        Self { api: ApiClient::new() }
+        Self {
+            api: ApiClient::new(),
+            prompts: PromptRegistry::default(),
+            recorded: None,
+            cache: CacheLayer::default(),
+        }
     }
 
     /// Set the base URL for the model. Defaults to `https://api.perceptron.inc`.
@@ -45,17 +78,142 @@ impl PerceptronClient {
         self
     }
 
+    /// Limit requests and tokens per minute, waiting for capacity instead of failing with 429s.
+    /// Pass clones of the same limiter to clients sharing an API key.
+    pub fn rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        self.api.rate_limiter = Some(limiter);
+        self
+    }
+
+    /// Cache responses in `cache`, keyed by a hash of the chat completion request and base URL.
+    ///
+    /// Requests sampled with a temperature above 0 are not cached unless [`Self::force_cache`]
+    /// is set. Requests without a temperature are cached, as if the server default were 0;
+    /// set a temperature on requests that rely on a sampling server default. Cached responses
+    /// have `cached` set.
+    pub fn cache(mut self, cache: impl Cache + 'static) -> Self {
+        self.cache.backend = Some(Arc::new(cache));
+        self
+    }
+
+    /// Expire cached responses after `ttl`. By default they do not expire.
+    pub fn cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache.ttl = Some(ttl);
+        self
+    }
+
+    /// Cache responses even for requests sampled with a temperature above 0.
+    pub fn force_cache(mut self, force: bool) -> Self {
+        self.cache.force = force;
+        self
+    }
+
+    /// Skip cache lookups, e.g. on a clone used to refresh results. Fresh responses are
+    /// still stored.
+    pub fn bypass_cache(mut self, bypass: bool) -> Self {
+        self.cache.bypass = bypass;
+        self
+    }
+
+    /// Use the given prompt templates for `question`, `caption`, `ocr` and `detect` on every model,
+    /// replacing any registered profiles.
+    pub fn prompt_profile(mut self, profile: PromptProfile) -> Self {
+        self.prompts = PromptRegistry::new(profile);
+        self
+    }
+
+    /// Register prompt templates for model ids matching `pattern` (e.g. `my-isaac-ft-*`).
+    /// Later registrations take precedence over earlier ones.
+    pub fn register_prompt_profile(mut self, pattern: impl Into<String>, profile: PromptProfile) -> Self {
+        self.prompts = self.prompts.register(pattern, profile);
+        self
+    }
+
+    /// Replace the prompt registry used to select templates by model id.
+    /// Defaults to [`PromptProfile::ISAAC`] for every model.
+    pub fn prompt_registry(mut self, registry: PromptRegistry) -> Self {
+        self.prompts = registry;
+        self
+    }
+
+    /// Record chat completion requests instead of sending them.
+    ///
+    /// Task methods return empty responses, and the requests can be read back with
+    /// [`Self::recorded_requests`]. Clones of the client share the same record.
+    /// Model listing is unaffected.
+    pub fn dry_run(mut self) -> Self {
+        self.recorded = Some(Arc::default());
+        self
+    }
+
+    /// Requests recorded in dry-run mode, oldest first. Empty when dry-run mode is off.
+    pub fn recorded_requests(&self) -> Vec<CreateChatCompletionRequest> {
+        self.recorded
+            .as_ref()
+            .map(|recorded| recorded.lock().unwrap_or_else(|e| e.into_inner()).clone())
+            .unwrap_or_default()
+    }
+
+    /// Build the chat completion request a task method would send, without sending it.
+    pub fn preview(&self, request: impl IntoWireRequest) -> CreateChatCompletionRequest {
+        request.into_wire_request(&self.prompts)
+    }
+
+    /// Run many requests of the same task with bounded concurrency, e.g.
+    /// `client.batch(requests).concurrency(32).run().await`. Results come back in input order.
+    pub fn batch<R: BatchTask>(&self, requests: impl IntoIterator<Item = R>) -> Batch<R> {
+        Batch::new(self.clone(), requests.into_iter().collect())
+    }
+
+    /// Run a JSONL file of [`BatchLine`](crate::BatchLine)s and write a JSONL file of results
+    /// keyed by line id, resuming from `output` if an earlier run was interrupted.
+    pub fn run_jsonl(&self, input: impl Into<PathBuf>, output: impl Into<PathBuf>) -> JsonlBatch {
+        JsonlBatch::new(self.clone(), input.into(), output.into())
+    }
+
+    async fn chat_completions(
+        &self,
+        wire_request: CreateChatCompletionRequest,
+    ) -> Result<CreateChatCompletionResponse, PerceptronError> {
+        match &self.recorded {
+            Some(recorded) => {
+                recorded.lock().unwrap_or_else(|e| e.into_inner()).push(wire_request);
+                Ok(CreateChatCompletionResponse {
+                    choices: Vec::new(),
+                    usage: None,
+                    cached: false,
+                })
+            }
+            None => {
+                let key = self.cache.key(&self.api.base_url, &wire_request);
+                if let Some(key) = &key
+                    && let Some(response) = self.cache.get(key).await
+                {
+                    return Ok(response);
+                }
+                let response = self.api.chat_completions(wire_request).await?;
+                if let Some(key) = key {
+                    self.cache.put(&key, &response).await;
+                }
+                Ok(response)
+            }
+        }
+    }
+
     async fn send(&self, wire_request: CreateChatCompletionRequest) -> Result<TextResponse, PerceptronError> {
-        let completion = self.api.chat_completions(wire_request).await?;
+        let completion = self.chat_completions(wire_request).await?;
+        let cached = completion.cached;
 
         let response = match completion.choices.into_iter().next() {
             Some(choice) => TextResponse {
                 content: choice.message.content,
                 reasoning: choice.message.reasoning_content,
+                cached,
             },
             None => TextResponse {
                 content: None,
                 reasoning: None,
+                cached,
             },
         };
 
@@ -67,7 +225,8 @@ impl PerceptronClient {
         wire_request: CreateChatCompletionRequest,
         output_format: Option<&OutputFormat>,
     ) -> Result<PointingResponse, PerceptronError> {
-        let completion = self.api.chat_completions(wire_request).await?;
+        let completion = self.chat_completions(wire_request).await?;
+        let cached = completion.cached;
 
         let response = match completion.choices.into_iter().next() {
             Some(choice) => {
@@ -79,21 +238,97 @@ impl PerceptronClient {
                 PointingResponse {
                     content: choice.message.content,
                     reasoning: choice.message.reasoning_content,
+                    cached,
                     pointing,
+                    ensemble: None,
                 }
             }
             None => PointingResponse {
                 content: None,
                 reasoning: None,
+                cached,
                 pointing: None,
+                ensemble: None,
             },
         };
 
         Ok(response)
     }
+
+    /// Draw `ensemble.samples` responses for the same request, concurrently.
+    async fn sample(
+        &self,
+        wire_request: CreateChatCompletionRequest,
+        ensemble: &Ensemble,
+    ) -> Result<Vec<TextResponse>, PerceptronError> {
+        let samples = ensemble.samples.max(1) as usize;
+        let mut responses = Vec::with_capacity(samples);
+        if ensemble.use_choices.unwrap_or(false) {
+            let completion = self
+                .chat_completions(CreateChatCompletionRequest {
+                    n: Some(samples as u32),
+                    ..wire_request.clone()
+                })
+                .await?;
+            let cached = completion.cached;
+            responses.extend(completion.choices.into_iter().take(samples).map(|choice| TextResponse {
+                content: choice.message.content,
+                reasoning: choice.message.reasoning_content,
+                cached,
+            }));
+        }
+
+        let mut tasks = JoinSet::new();
+        for index in responses.len()..samples {
+            // Identical requests would all hit the same cache entry, so samples skip the cache.
+            let mut client = self.clone();
+            client.cache.backend = None;
+            let wire_request = wire_request.clone();
+            tasks.spawn(async move { (index, client.send(wire_request).await) });
+        }
+        let mut rest = Vec::new();
+        while let Some(joined) = tasks.join_next().await {
+            let (index, response) = joined.map_err(|e| PerceptronError::RequestFailed(e.to_string()))?;
+            rest.push((index, response?));
+        }
+        rest.sort_by_key(|(index, _)| *index);
+        responses.extend(rest.into_iter().map(|(_, response)| response));
+        Ok(responses)
+    }
+
+    /// Like [`Self::send_and_extract`], but combining several samples when `ensemble` is set.
+    async fn send_and_extract_ensemble(
+        &self,
+        wire_request: CreateChatCompletionRequest,
+        output_format: Option<&OutputFormat>,
+        ensemble: Option<&Ensemble>,
+    ) -> Result<PointingResponse, PerceptronError> {
+        match ensemble {
+            Some(ensemble) => {
+                let samples = self.sample(wire_request, ensemble).await?;
+                Ok(ensemble::aggregate(samples, output_format, ensemble.threshold()))
+            }
+            None => self.send_and_extract(wire_request, output_format).await,
+        }
+    }
 }
 
 /// Trait for analyzing visual media with a Perceptron AI model.
+///
+/// Every task sends its messages in the same order:
+///
+/// 1. A `<hint>` system message, when the output format or reasoning setting calls for one.
+/// 2. The instruction system message: the request's `system_prompt` if set, otherwise the
+///    prompt profile default for the task (if any).
+/// 3. Task constraints: detection exclusions and size hints, then the output language
+///    instruction when `output_language` is set.
+/// 4. The request's `extra_instructions` system message, if set.
+/// 5. For each `few_shot` example, a user message (media, then prompt) and an assistant
+///    message with the expected answer.
+/// 6. The user message: the media followed by the task text (if any).
+///
+/// Tasks added after `detect` have default implementations, so implementing the trait only
+/// requires the original methods. Unless overridden, they fail with [`PerceptronError::Unsupported`].
 pub trait Perceptron {
     /// List all available models.
     fn models(&self) -> impl Future<Output = Result<Vec<Model>, PerceptronError>> + Send;
@@ -122,8 +357,119 @@ pub trait Perceptron {
     /// Extract text using OCR.
     fn ocr(&self, request: OcrRequest) -> impl Future<Output = Result<TextResponse, PerceptronError>> + Send;
 
+    /// Extract text with line and word bounding boxes.
+    ///
+    /// Runs OCR in [`OcrMode::Layout`] regardless of the request's mode and parses the answer into
+    /// blocks, lines and words. The default implementation does this on top of [`Perceptron::ocr`].
+    fn ocr_layout(
+        &self,
+        request: OcrRequest,
+    ) -> impl Future<Output = Result<OcrLayoutResponse, PerceptronError>> + Send {
+        let response = self.ocr(request.mode(OcrMode::Layout));
+        async move {
+            let response = response.await?;
+            let layout = response.content.as_deref().and_then(layout::extract);
+            Ok(OcrLayoutResponse {
+                content: response.content,
+                reasoning: response.reasoning,
+                cached: response.cached,
+                layout,
+            })
+        }
+    }
+
     /// Detect and segment objects.
     fn detect(&self, request: DetectRequest) -> impl Future<Output = Result<PointingResponse, PerceptronError>> + Send;
+
+    /// Classify media into one or more of the given labels.
+    ///
+    /// The model's answer is matched back to the canonical labels, tolerating case, plurals,
+    /// punctuation and small spelling differences; anything else is reported as
+    /// [`UNKNOWN_LABEL`](crate::UNKNOWN_LABEL). A request with a label equal to
+    /// `UNKNOWN_LABEL` fails with [`PerceptronError::InvalidRequest`] without being sent.
+    fn classify(
+        &self,
+        request: ClassifyRequest,
+    ) -> impl Future<Output = Result<ClassifyResponse, PerceptronError>> + Send {
+        unsupported("classify", request)
+    }
+
+    /// Count instances of each class, pointing to every instance as evidence.
+    ///
+    /// Counts come from the extracted points. Each class also reports the total the model
+    /// stated in its answer and whether it disagrees with the number of points.
+    fn count(&self, request: CountRequest) -> impl Future<Output = Result<CountResponse, PerceptronError>> + Send {
+        unsupported("count", request)
+    }
+
+    /// Find the region described by a natural-language expression.
+    ///
+    /// The model is asked for exactly one region unless [`GroundRequest::all_matches`] is set.
+    fn ground(&self, request: GroundRequest) -> impl Future<Output = Result<GroundResponse, PerceptronError>> + Send {
+        unsupported("ground", request)
+    }
+
+    /// Compare two images and locate what was added, removed or changed.
+    ///
+    /// Both images are sent in one user message, labeled before and after, and every
+    /// returned region records which image its coordinates refer to.
+    fn compare(
+        &self,
+        request: CompareRequest,
+    ) -> impl Future<Output = Result<CompareResponse, PerceptronError>> + Send {
+        unsupported("compare", request)
+    }
+
+    /// Extract key/value fields, and optionally tables, from a document image.
+    ///
+    /// Values are typed from their text and carry the box the model placed on them.
+    fn document(
+        &self,
+        request: DocumentRequest,
+    ) -> impl Future<Output = Result<DocumentResponse, PerceptronError>> + Send {
+        unsupported("document", request)
+    }
+
+    /// Summarize a video and split it into chapters.
+    ///
+    /// Chapters come back sorted and non-overlapping, clamped to the duration when it is set.
+    fn summarize_video(
+        &self,
+        request: VideoSummaryRequest,
+    ) -> impl Future<Output = Result<VideoSummaryResponse, PerceptronError>> + Send {
+        unsupported("summarize_video", request)
+    }
+
+    /// Find every time range in a video where an event happens.
+    ///
+    /// Hits closer than the request's merge gap are merged into one.
+    fn locate(&self, request: LocateRequest) -> impl Future<Output = Result<LocateResponse, PerceptronError>> + Send {
+        unsupported("locate", request)
+    }
+
+    /// Tag media with labels from a controlled vocabulary.
+    ///
+    /// Returned tags are mapped onto the taxonomy, with ancestors of applied tags added;
+    /// tags outside the taxonomy are reported separately.
+    fn tag(&self, request: TagRequest) -> impl Future<Output = Result<TagResponse, PerceptronError>> + Send {
+        unsupported("tag", request)
+    }
+
+    /// Send a conversation as-is, bypassing the task prompts.
+    ///
+    /// Unlike the task methods, no prompt profile is applied; only the `<hint>` system message
+    /// is added when the request sets an output format or reasoning.
+    fn chat(&self, request: ChatRequest) -> impl Future<Output = Result<ChatResponse, PerceptronError>> + Send {
+        unsupported("chat", request)
+    }
+}
+
+/// Default for task methods an implementation does not support.
+fn unsupported<T: Send, R>(
+    method: &'static str,
+    _request: R,
+) -> impl Future<Output = Result<T, PerceptronError>> + Send {
+    std::future::ready(Err(PerceptronError::Unsupported(method)))
 }
 
 impl Perceptron for PerceptronClient {
@@ -138,121 +484,679 @@ impl Perceptron for PerceptronClient {
     }
 
     async fn question(&self, request: QuestionRequest) -> Result<PointingResponse, PerceptronError> {
-        let output_format = request.output_format.as_ref();
-        let profile = &prompting::ISAAC;
-        let mut system_prompts: Vec<String> = system_hint(output_format, request.reasoning).into_iter().collect();
-        if let Some(system) = profile.question.resolve_system(output_format, &request.media) {
-            system_prompts.push(system.to_string());
-        }
-        let desc = RequestDescriptor {
-            media: request.media,
-            system_prompts,
-            user_text: Some(request.question),
-            model: request.model,
-            max_tokens: request.max_tokens,
-            temperature: request.temperature,
-            top_p: request.top_p,
-            top_k: request.top_k,
-            frequency_penalty: request.frequency_penalty,
-            presence_penalty: request.presence_penalty,
-        };
-        self.send_and_extract(build_wire_request(desc), output_format).await
+        let output_format = request.output_format.clone();
+        let ensemble = request.ensemble.clone();
+        let wire_request = request.into_wire_request(&self.prompts);
+        self.send_and_extract_ensemble(wire_request, output_format.as_ref(), ensemble.as_ref())
+            .await
     }
 
     async fn analyze(&self, request: AnalyzeRequest) -> Result<PointingResponse, PerceptronError> {
-        let output_format = request.output_format.as_ref();
-        let desc = RequestDescriptor {
-            media: request.media,
-            system_prompts: system_hint(output_format, request.reasoning).into_iter().collect(),
-            user_text: Some(request.message),
-            model: request.model,
-            max_tokens: request.max_tokens,
-            temperature: request.temperature,
-            top_p: request.top_p,
-            top_k: request.top_k,
-            frequency_penalty: request.frequency_penalty,
-            presence_penalty: request.presence_penalty,
-        };
-        self.send_and_extract(build_wire_request(desc), output_format).await
+        let output_format = request.output_format.clone();
+        let wire_request = request.into_wire_request(&self.prompts);
+        self.send_and_extract(wire_request, output_format.as_ref()).await
     }
 
     async fn caption(&self, request: CaptionRequest) -> Result<PointingResponse, PerceptronError> {
-        let output_format = request.output_format.unwrap_or(OutputFormat::Box);
-        let profile = &prompting::ISAAC;
-        let mut system_prompts: Vec<String> = system_hint(Some(&output_format), request.reasoning)
-            .into_iter()
-            .collect();
-        if let Some(system) = profile.caption.resolve_system(&request.media) {
-            system_prompts.push(system.to_string());
-        }
-        let user_text = Some(profile.caption.resolve_user(&request.style, &request.media).to_string());
-        let desc = RequestDescriptor {
-            media: request.media,
-            system_prompts,
-            user_text,
-            model: request.model,
-            max_tokens: request.max_tokens,
-            temperature: request.temperature,
-            top_p: request.top_p,
-            top_k: request.top_k,
-            frequency_penalty: request.frequency_penalty,
-            presence_penalty: request.presence_penalty,
+        let output_format = caption_output_format(&request);
+        let length = caption_length(&request);
+        let length_policy = request.length_policy.unwrap_or_default();
+        let wire_request = request.into_wire_request(&self.prompts);
+
+        let Some(length) = length else {
+            return self.send_and_extract(wire_request, output_format.as_ref()).await;
         };
-        self.send_and_extract(build_wire_request(desc), Some(&output_format))
-            .await
+        let retries = match length_policy {
+            LengthPolicy::Truncate => 0,
+            LengthPolicy::Retry { attempts } => attempts,
+        };
+        let mut response = self
+            .send_and_extract(wire_request.clone(), output_format.as_ref())
+            .await?;
+        // A cached out-of-range answer would be returned again, so retries skip the lookup; the
+        // retried answer replaces it in the cache.
+        let mut retry_client = self.clone();
+        retry_client.cache.bypass = true;
+        for _ in 0..retries {
+            if response
+                .content
+                .as_deref()
+                .is_none_or(|c| length.contains(&parsing::strip_tags(c)))
+            {
+                break;
+            }
+            response = retry_client
+                .send_and_extract(wire_request.clone(), output_format.as_ref())
+                .await?;
+        }
+        // The length applies to the prose, so `content` is returned without annotation tags;
+        // `pointing` still reflects the full answer.
+        if let Some(content) = &response.content {
+            let prose = parsing::strip_tags(content);
+            response.content = Some(if length.measure(&prose) > length.max {
+                length.truncate(&prose)
+            } else {
+                prose
+            });
+        }
+        Ok(response)
     }
 
     async fn ocr(&self, request: OcrRequest) -> Result<TextResponse, PerceptronError> {
-        let profile = &prompting::ISAAC;
-        let mut system_prompts: Vec<String> = system_hint(None, request.reasoning).into_iter().collect();
-        if let Some(system) = profile.ocr.resolve_system() {
-            system_prompts.push(system.to_string());
+        self.send(request.into_wire_request(&self.prompts)).await
+    }
+
+    async fn detect(&self, request: DetectRequest) -> Result<PointingResponse, PerceptronError> {
+        let strict_classes = request
+            .strict_classes
+            .unwrap_or(false)
+            .then(|| (request.classes.clone(), request.excluded_classes.clone()));
+        let ensemble = request.ensemble.clone();
+        let wire_request = request.into_wire_request(&self.prompts);
+        let mut response = self
+            .send_and_extract_ensemble(wire_request, Some(&OutputFormat::Box), ensemble.as_ref())
+            .await?;
+        if let Some((classes, excluded_classes)) = strict_classes {
+            response.pointing = retain_classes(response.pointing, classes.as_deref(), &excluded_classes);
+        }
+        Ok(response)
+    }
+
+    async fn classify(&self, request: ClassifyRequest) -> Result<ClassifyResponse, PerceptronError> {
+        if let Some(label) = request.labels.iter().find(|l| classification::is_reserved_label(l)) {
+            return Err(PerceptronError::InvalidRequest(format!(
+                "label {label:?} is reserved for answers matching no label"
+            )));
         }
-        let user_text = request
+        let labels = request.labels.clone();
+        let response = self.send(request.into_wire_request(&self.prompts)).await?;
+        let labels = response
+            .content
+            .as_deref()
+            .map(|content| classification::rank_labels(content, &labels))
+            .unwrap_or_default();
+        Ok(ClassifyResponse {
+            content: response.content,
+            reasoning: response.reasoning,
+            cached: response.cached,
+            labels,
+        })
+    }
+
+    async fn count(&self, request: CountRequest) -> Result<CountResponse, PerceptronError> {
+        let classes = request.classes.clone();
+        let ensemble = request.ensemble.clone();
+        let response = self
+            .send_and_extract_ensemble(
+                request.into_wire_request(&self.prompts),
+                Some(&OutputFormat::Point),
+                ensemble.as_ref(),
+            )
+            .await?;
+        let counts = counting::count_classes(
+            response.content.as_deref().unwrap_or_default(),
+            response.pointing.as_ref(),
+            &classes,
+        );
+        Ok(CountResponse {
+            content: response.content,
+            reasoning: response.reasoning,
+            cached: response.cached,
+            counts,
+            ensemble: response.ensemble,
+        })
+    }
+
+    async fn ground(&self, request: GroundRequest) -> Result<GroundResponse, PerceptronError> {
+        let output_format = request.output_format();
+        let response = self
+            .send_and_extract(request.into_wire_request(&self.prompts), Some(&output_format))
+            .await?;
+        let matches: Vec<Region> = match response.pointing {
+            Some(pointing) => match output_format {
+                OutputFormat::Polygon => pointing.polygons.into_iter().map(Region::Polygon).collect(),
+                _ => pointing.boxes.into_iter().map(Region::Box).collect(),
+            },
+            None => Vec::new(),
+        };
+        Ok(GroundResponse {
+            content: response.content,
+            reasoning: response.reasoning,
+            cached: response.cached,
+            region: matches.first().cloned(),
+            matches,
+        })
+    }
+
+    async fn compare(&self, request: CompareRequest) -> Result<CompareResponse, PerceptronError> {
+        let response = self
+            .send_and_extract(request.into_wire_request(&self.prompts), Some(&OutputFormat::Box))
+            .await?;
+        Ok(CompareResponse {
+            summary: response.content.as_deref().and_then(comparison::summary),
+            changes: comparison::extract_changes(response.pointing.as_ref()),
+            content: response.content,
+            reasoning: response.reasoning,
+            cached: response.cached,
+        })
+    }
+
+    async fn document(&self, request: DocumentRequest) -> Result<DocumentResponse, PerceptronError> {
+        let fields = request.fields.clone();
+        let response = self
+            .send_and_extract(request.into_wire_request(&self.prompts), Some(&OutputFormat::Box))
+            .await?;
+        let content = response.content.as_deref().unwrap_or_default();
+        Ok(DocumentResponse {
+            fields: document::extract_fields(content, response.pointing.as_ref(), &fields),
+            tables: Table::parse_all(content),
+            content: response.content,
+            reasoning: response.reasoning,
+            cached: response.cached,
+        })
+    }
+
+    async fn summarize_video(&self, request: VideoSummaryRequest) -> Result<VideoSummaryResponse, PerceptronError> {
+        let duration = request.duration;
+        let response = self
+            .send_and_extract(request.into_wire_request(&self.prompts), Some(&OutputFormat::Clip))
+            .await?;
+        let content = response.content.as_deref().unwrap_or_default();
+        Ok(VideoSummaryResponse {
+            summary: chapters::summary(content),
+            chapters: chapters::build_chapters(content, duration),
+            content: response.content,
+            reasoning: response.reasoning,
+            cached: response.cached,
+        })
+    }
+
+    async fn locate(&self, request: LocateRequest) -> Result<LocateResponse, PerceptronError> {
+        let key_frames = request.key_frames;
+        let merge_gap = request.merge_gap.unwrap_or(events::DEFAULT_MERGE_GAP);
+        let response = self.send(request.into_wire_request(&self.prompts)).await?;
+        let hits = response
+            .content
+            .as_deref()
+            .map(|content| events::extract_hits(content, key_frames, merge_gap))
+            .unwrap_or_default();
+        Ok(LocateResponse {
+            content: response.content,
+            reasoning: response.reasoning,
+            cached: response.cached,
+            hits,
+        })
+    }
+
+    async fn tag(&self, request: TagRequest) -> Result<TagResponse, PerceptronError> {
+        let taxonomy = request.taxonomy.clone();
+        let response = self
+            .send_and_extract(request.into_wire_request(&self.prompts), Some(&OutputFormat::Box))
+            .await?;
+        let (tags, rejected) = tagging::assign_tags(
+            response.content.as_deref().unwrap_or_default(),
+            response.pointing.as_ref(),
+            &taxonomy,
+        );
+        Ok(TagResponse {
+            content: response.content,
+            reasoning: response.reasoning,
+            cached: response.cached,
+            tags,
+            rejected,
+        })
+    }
+
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse, PerceptronError> {
+        let output_format = request.output_format.clone();
+        let completion = self.chat_completions(request.into_wire_request(&self.prompts)).await?;
+        let cached = completion.cached;
+        let usage = completion.usage.map(|usage| Usage {
+            prompt_tokens: usage.prompt_tokens,
+            completion_tokens: usage.completion_tokens,
+            total_tokens: usage.total_tokens,
+        });
+        let response = match completion.choices.into_iter().next() {
+            Some(choice) => ChatResponse {
+                pointing: output_format.as_ref().and_then(|format| {
+                    choice
+                        .message
+                        .content
+                        .as_deref()
+                        .and_then(|text| parsing::extract(text, Some(format)))
+                }),
+                content: choice.message.content,
+                reasoning: choice.message.reasoning_content,
+                cached,
+                finish_reason: choice.finish_reason,
+                usage,
+            },
+            None => ChatResponse {
+                content: None,
+                reasoning: None,
+                cached,
+                pointing: None,
+                finish_reason: None,
+                usage,
+            },
+        };
+        Ok(response)
+    }
+}
+
+/// A task request that can be turned into the chat completion request sent on the wire.
+///
+/// Implemented for every task request type; see [`PerceptronClient::preview`].
+pub trait IntoWireRequest {
+    /// Build the chat completion request, resolving prompt templates from `prompts`.
+    fn into_wire_request(self, prompts: &PromptRegistry) -> CreateChatCompletionRequest;
+}
+
+impl IntoWireRequest for QuestionRequest {
+    fn into_wire_request(self, prompts: &PromptRegistry) -> CreateChatCompletionRequest {
+        let output_format = self.output_format.as_ref();
+        let profile = prompts.resolve(&self.model);
+        let system_prompts = system_prompts(
+            system_hint(output_format, self.reasoning),
+            profile.question.resolve_system(output_format, &self.media),
+            self.system_prompt,
+            self.output_language.map(|language| profile.language.resolve(&language)),
+            self.extra_instructions,
+        );
+        build_wire_request(RequestDescriptor {
+            system_prompts,
+            few_shot: self.few_shot,
+            user_content: media_with_text(self.media, Some(self.question)),
+            model: self.model,
+            max_tokens: self.max_tokens,
+            temperature: self.temperature,
+            top_p: self.top_p,
+            top_k: self.top_k,
+            frequency_penalty: self.frequency_penalty,
+            presence_penalty: self.presence_penalty,
+        })
+    }
+}
+
+impl IntoWireRequest for AnalyzeRequest {
+    fn into_wire_request(self, _prompts: &PromptRegistry) -> CreateChatCompletionRequest {
+        build_wire_request(RequestDescriptor {
+            system_prompts: system_prompts(
+                system_hint(self.output_format.as_ref(), self.reasoning),
+                None,
+                self.system_prompt,
+                None,
+                self.extra_instructions,
+            ),
+            few_shot: self.few_shot,
+            user_content: media_with_text(self.media, Some(self.message)),
+            model: self.model,
+            max_tokens: self.max_tokens,
+            temperature: self.temperature,
+            top_p: self.top_p,
+            top_k: self.top_k,
+            frequency_penalty: self.frequency_penalty,
+            presence_penalty: self.presence_penalty,
+        })
+    }
+}
+
+impl IntoWireRequest for CaptionRequest {
+    fn into_wire_request(self, prompts: &PromptRegistry) -> CreateChatCompletionRequest {
+        let output_format = caption_output_format(&self);
+        let length = caption_length(&self);
+        let profile = prompts.resolve(&self.model);
+        let system_prompts = system_prompts(
+            system_hint(output_format.as_ref(), self.reasoning),
+            profile.caption.resolve_system(&self.media),
+            self.system_prompt,
+            self.output_language.map(|language| profile.language.resolve(&language)),
+            self.extra_instructions,
+        );
+        let mut user_text = profile.caption.resolve_user(&self.style, &self.media).to_string();
+        if let Some(length) = &length {
+            user_text = format!("{user_text} {}", profile.caption.resolve_length(length));
+        }
+        build_wire_request(RequestDescriptor {
+            system_prompts,
+            few_shot: self.few_shot,
+            user_content: media_with_text(self.media, Some(user_text)),
+            model: self.model,
+            max_tokens: self.max_tokens,
+            temperature: self.temperature,
+            top_p: self.top_p,
+            top_k: self.top_k,
+            frequency_penalty: self.frequency_penalty,
+            presence_penalty: self.presence_penalty,
+        })
+    }
+}
+
+impl IntoWireRequest for OcrRequest {
+    fn into_wire_request(self, prompts: &PromptRegistry) -> CreateChatCompletionRequest {
+        let profile = prompts.resolve(&self.model);
+        let output_format = (self.mode == OcrMode::Layout).then_some(OutputFormat::Box);
+        let system_prompts = system_prompts(
+            system_hint(output_format.as_ref(), self.reasoning),
+            profile.ocr.resolve_system(),
+            self.system_prompt,
+            None,
+            self.extra_instructions,
+        );
+        let user_text = self
             .prompt
-            .or_else(|| profile.ocr.resolve_user(&request.mode).map(str::to_string));
-        let desc = RequestDescriptor {
-            media: request.image.into(),
+            .or_else(|| profile.ocr.resolve_user(&self.mode).map(str::to_string));
+        build_wire_request(RequestDescriptor {
             system_prompts,
-            user_text,
-            model: request.model,
-            max_tokens: request.max_tokens,
-            temperature: request.temperature,
-            top_p: request.top_p,
-            top_k: request.top_k,
-            frequency_penalty: request.frequency_penalty,
-            presence_penalty: request.presence_penalty,
-        };
-        self.send(build_wire_request(desc)).await
+            few_shot: self.few_shot,
+            user_content: media_with_text(self.image.into(), user_text),
+            model: self.model,
+            max_tokens: self.max_tokens,
+            temperature: self.temperature,
+            top_p: self.top_p,
+            top_k: self.top_k,
+            frequency_penalty: self.frequency_penalty,
+            presence_penalty: self.presence_penalty,
+        })
     }
+}
 
-    async fn detect(&self, request: DetectRequest) -> Result<PointingResponse, PerceptronError> {
-        let profile = &prompting::ISAAC;
-        let mut system_prompts: Vec<String> = system_hint(Some(&OutputFormat::Box), request.reasoning)
-            .into_iter()
-            .collect();
-        system_prompts.push(
+impl IntoWireRequest for DetectRequest {
+    fn into_wire_request(self, prompts: &PromptRegistry) -> CreateChatCompletionRequest {
+        let profile = prompts.resolve(&self.model);
+        let constraints =
             profile
                 .detect
-                .resolve_system(request.classes.as_deref(), &request.media),
+                .resolve_constraints(&self.excluded_classes, self.max_instances_per_class, self.min_size);
+        let language = self.output_language.map(|language| profile.language.resolve(&language));
+        let system_prompts = system_prompts(
+            system_hint(Some(&OutputFormat::Box), self.reasoning),
+            Some(
+                &profile
+                    .detect
+                    .resolve_system(self.classes.as_deref(), &self.class_descriptions, &self.media),
+            ),
+            self.system_prompt,
+            constraints.into_iter().chain(language),
+            self.extra_instructions,
         );
-        let desc = RequestDescriptor {
-            media: request.media,
+        build_wire_request(RequestDescriptor {
             system_prompts,
-            user_text: None,
-            model: request.model,
-            max_tokens: request.max_tokens,
-            temperature: request.temperature,
-            top_p: request.top_p,
-            top_k: request.top_k,
-            frequency_penalty: request.frequency_penalty,
-            presence_penalty: request.presence_penalty,
-        };
-        self.send_and_extract(build_wire_request(desc), Some(&OutputFormat::Box))
-            .await
+            few_shot: self.few_shot,
+            user_content: media_with_text(self.media, None),
+            model: self.model,
+            max_tokens: self.max_tokens,
+            temperature: self.temperature,
+            top_p: self.top_p,
+            top_k: self.top_k,
+            frequency_penalty: self.frequency_penalty,
+            presence_penalty: self.presence_penalty,
+        })
     }
 }
 
+impl IntoWireRequest for ClassifyRequest {
+    fn into_wire_request(self, prompts: &PromptRegistry) -> CreateChatCompletionRequest {
+        let profile = prompts.resolve(&self.model);
+        let user_text = profile
+            .classify
+            .resolve_user(&self.labels, self.multi_label.unwrap_or(false), &self.media);
+        build_wire_request(RequestDescriptor {
+            system_prompts: system_prompts(
+                system_hint(None, self.reasoning),
+                None,
+                self.system_prompt,
+                None,
+                self.extra_instructions,
+            ),
+            few_shot: self.few_shot,
+            user_content: media_with_text(self.media, Some(user_text)),
+            model: self.model,
+            max_tokens: self.max_tokens,
+            temperature: self.temperature,
+            top_p: self.top_p,
+            top_k: self.top_k,
+            frequency_penalty: self.frequency_penalty,
+            presence_penalty: self.presence_penalty,
+        })
+    }
+}
+
+impl IntoWireRequest for CountRequest {
+    fn into_wire_request(self, prompts: &PromptRegistry) -> CreateChatCompletionRequest {
+        let profile = prompts.resolve(&self.model);
+        let user_text = profile.count.resolve_user(&self.classes, &self.media);
+        build_wire_request(RequestDescriptor {
+            system_prompts: system_prompts(
+                system_hint(Some(&OutputFormat::Point), self.reasoning),
+                None,
+                self.system_prompt,
+                None,
+                self.extra_instructions,
+            ),
+            few_shot: self.few_shot,
+            user_content: media_with_text(self.media, Some(user_text)),
+            model: self.model,
+            max_tokens: self.max_tokens,
+            temperature: self.temperature,
+            top_p: self.top_p,
+            top_k: self.top_k,
+            frequency_penalty: self.frequency_penalty,
+            presence_penalty: self.presence_penalty,
+        })
+    }
+}
+
+impl IntoWireRequest for GroundRequest {
+    fn into_wire_request(self, prompts: &PromptRegistry) -> CreateChatCompletionRequest {
+        let output_format = self.output_format();
+        let profile = prompts.resolve(&self.model);
+        let system_prompts = system_prompts(
+            system_hint(Some(&output_format), self.reasoning),
+            profile.question.resolve_system(Some(&output_format), &self.media),
+            self.system_prompt,
+            None,
+            self.extra_instructions,
+        );
+        let user_text = profile
+            .ground
+            .resolve_user(&self.expression, self.all_matches.unwrap_or(false), &self.media);
+        build_wire_request(RequestDescriptor {
+            system_prompts,
+            few_shot: self.few_shot,
+            user_content: media_with_text(self.media, Some(user_text)),
+            model: self.model,
+            max_tokens: self.max_tokens,
+            temperature: self.temperature,
+            top_p: self.top_p,
+            top_k: self.top_k,
+            frequency_penalty: self.frequency_penalty,
+            presence_penalty: self.presence_penalty,
+        })
+    }
+}
+
+impl IntoWireRequest for CompareRequest {
+    fn into_wire_request(self, prompts: &PromptRegistry) -> CreateChatCompletionRequest {
+        let template = &prompts.resolve(&self.model).compare;
+        build_wire_request(RequestDescriptor {
+            system_prompts: system_prompts(
+                system_hint(Some(&OutputFormat::Box), self.reasoning),
+                None,
+                self.system_prompt,
+                None,
+                self.extra_instructions,
+            ),
+            few_shot: self.few_shot,
+            user_content: vec![
+                ChatContentPart::Text(template.before_label.to_string()),
+                self.before.into(),
+                ChatContentPart::Text(template.after_label.to_string()),
+                self.after.into(),
+                ChatContentPart::Text(template.instruction.to_string()),
+            ],
+            model: self.model,
+            max_tokens: self.max_tokens,
+            temperature: self.temperature,
+            top_p: self.top_p,
+            top_k: self.top_k,
+            frequency_penalty: self.frequency_penalty,
+            presence_penalty: self.presence_penalty,
+        })
+    }
+}
+
+impl IntoWireRequest for DocumentRequest {
+    fn into_wire_request(self, prompts: &PromptRegistry) -> CreateChatCompletionRequest {
+        let profile = prompts.resolve(&self.model);
+        let user_text = profile
+            .document
+            .resolve_user(&self.fields, self.tables.unwrap_or(false));
+        build_wire_request(RequestDescriptor {
+            system_prompts: system_prompts(
+                system_hint(Some(&OutputFormat::Box), self.reasoning),
+                None,
+                self.system_prompt,
+                None,
+                self.extra_instructions,
+            ),
+            few_shot: self.few_shot,
+            user_content: media_with_text(self.image.into(), Some(user_text)),
+            model: self.model,
+            max_tokens: self.max_tokens,
+            temperature: self.temperature,
+            top_p: self.top_p,
+            top_k: self.top_k,
+            frequency_penalty: self.frequency_penalty,
+            presence_penalty: self.presence_penalty,
+        })
+    }
+}
+
+impl IntoWireRequest for VideoSummaryRequest {
+    fn into_wire_request(self, prompts: &PromptRegistry) -> CreateChatCompletionRequest {
+        let user_text = prompts.resolve(&self.model).video_summary.resolve_user(self.duration);
+        build_wire_request(RequestDescriptor {
+            system_prompts: system_prompts(
+                system_hint(Some(&OutputFormat::Clip), self.reasoning),
+                None,
+                self.system_prompt,
+                None,
+                self.extra_instructions,
+            ),
+            few_shot: self.few_shot,
+            user_content: media_with_text(self.video.into(), Some(user_text)),
+            model: self.model,
+            max_tokens: self.max_tokens,
+            temperature: self.temperature,
+            top_p: self.top_p,
+            top_k: self.top_k,
+            frequency_penalty: self.frequency_penalty,
+            presence_penalty: self.presence_penalty,
+        })
+    }
+}
+
+impl IntoWireRequest for LocateRequest {
+    fn into_wire_request(self, prompts: &PromptRegistry) -> CreateChatCompletionRequest {
+        let user_text = prompts
+            .resolve(&self.model)
+            .locate
+            .resolve_user(&self.query, self.key_frames);
+        build_wire_request(RequestDescriptor {
+            system_prompts: system_prompts(
+                system_hint(Some(&OutputFormat::Clip), self.reasoning),
+                None,
+                self.system_prompt,
+                None,
+                self.extra_instructions,
+            ),
+            few_shot: self.few_shot,
+            user_content: media_with_text(self.video.into(), Some(user_text)),
+            model: self.model,
+            max_tokens: self.max_tokens,
+            temperature: self.temperature,
+            top_p: self.top_p,
+            top_k: self.top_k,
+            frequency_penalty: self.frequency_penalty,
+            presence_penalty: self.presence_penalty,
+        })
+    }
+}
+
+impl IntoWireRequest for TagRequest {
+    fn into_wire_request(self, prompts: &PromptRegistry) -> CreateChatCompletionRequest {
+        let user_text = prompts
+            .resolve(&self.model)
+            .tag
+            .resolve_user(&self.taxonomy.paths(), &self.media);
+        build_wire_request(RequestDescriptor {
+            system_prompts: system_prompts(
+                system_hint(Some(&OutputFormat::Box), self.reasoning),
+                None,
+                self.system_prompt,
+                None,
+                self.extra_instructions,
+            ),
+            few_shot: self.few_shot,
+            user_content: media_with_text(self.media, Some(user_text)),
+            model: self.model,
+            max_tokens: self.max_tokens,
+            temperature: self.temperature,
+            top_p: self.top_p,
+            top_k: self.top_k,
+            frequency_penalty: self.frequency_penalty,
+            presence_penalty: self.presence_penalty,
+        })
+    }
+}
+
+impl IntoWireRequest for ChatRequest {
+    fn into_wire_request(self, _prompts: &PromptRegistry) -> CreateChatCompletionRequest {
+        let hint = system_hint(self.output_format.as_ref(), self.reasoning).map(ChatMessage::System);
+        let messages = hint
+            .into_iter()
+            .chain(self.messages)
+            .map(|message| match message {
+                ChatMessage::System(text) => ChatCompletionMessage::System(ChatCompletionSystemMessage {
+                    content: ChatCompletionSystemMessageContent::Text(text),
+                }),
+                ChatMessage::User(parts) => user_message(parts),
+                ChatMessage::Assistant(text) => ChatCompletionMessage::Assistant(ChatCompletionAssistantMessage {
+                    content: ChatCompletionAssistantMessageContent::Text(text),
+                }),
+            })
+            .collect();
+        CreateChatCompletionRequest {
+            messages,
+            model: self.model,
+            max_completion_tokens: self.max_tokens,
+            temperature: self.temperature,
+            top_p: self.top_p,
+            top_k: self.top_k,
+            frequency_penalty: self.frequency_penalty,
+            presence_penalty: self.presence_penalty,
+            n: None,
+        }
+    }
+}
+
+/// Output format for a caption: none for text-only captions, otherwise boxes unless overridden.
+fn caption_output_format(request: &CaptionRequest) -> Option<OutputFormat> {
+    match request.text_only {
+        Some(true) => None,
+        _ => Some(request.output_format.clone().unwrap_or(OutputFormat::Box)),
+    }
+}
+
+/// Length limit for a caption, defaulting alt text to under 125 characters.
+fn caption_length(request: &CaptionRequest) -> Option<CaptionLength> {
+    request.length.clone().or_else(|| match request.style {
+        CaptionStyle::AltText => Some(CaptionLength::characters(0, 124)),
+        _ => None,
+    })
+}
+
 /// Generate the hint tag for the system prompt based on output format and reasoning.
 fn system_hint(output_format: Option<&OutputFormat>, enable_reasoning: Option<bool>) -> Option<String> {
     let mut components = Vec::new();
@@ -276,10 +1180,46 @@ fn system_hint(output_format: Option<&OutputFormat>, enable_reasoning: Option<bo
     }
 }
 
+/// Assemble the system messages in wire order: the `<hint>` tag, then the instruction
+/// (the request's `system_prompt` if set, otherwise the profile default), then task
+/// constraints such as the output language, then any extra instructions.
+fn system_prompts(
+    hint: Option<String>,
+    default_instruction: Option<&str>,
+    system_prompt: Option<String>,
+    constraints: impl IntoIterator<Item = String>,
+    extra_instructions: Option<String>,
+) -> Vec<String> {
+    let instruction = system_prompt.or_else(|| default_instruction.map(str::to_string));
+    hint.into_iter()
+        .chain(instruction)
+        .chain(constraints)
+        .chain(extra_instructions)
+        .collect()
+}
+
+/// Drop boxes whose mention is not in `allowed` or is in `excluded`, comparing case-insensitively.
+fn retain_classes(pointing: Option<Pointing>, allowed: Option<&[String]>, excluded: &[String]) -> Option<Pointing> {
+    let normalize = |s: &str| s.trim().to_lowercase();
+    let allowed: Option<Vec<String>> = allowed.map(|a| a.iter().map(|c| normalize(c)).collect());
+    let excluded: Vec<String> = excluded.iter().map(|c| normalize(c)).collect();
+    let mut pointing = pointing?;
+    pointing.boxes.retain(|b| {
+        let mention = b.mention.as_deref().map(normalize);
+        let is_allowed = match (&allowed, &mention) {
+            (Some(allowed), Some(mention)) => allowed.contains(mention),
+            (Some(_), None) => false,
+            (None, _) => true,
+        };
+        is_allowed && !mention.is_some_and(|m| excluded.contains(&m))
+    });
+    (pointing != Pointing::default()).then_some(pointing)
+}
+
 struct RequestDescriptor {
-    media: Media,
     system_prompts: Vec<String>,
-    user_text: Option<String>,
+    few_shot: Vec<FewShotExample>,
+    user_content: Vec<ChatContentPart>,
     model: String,
     max_tokens: Option<u32>,
     temperature: Option<f32>,
@@ -298,23 +1238,15 @@ fn build_wire_request(desc: RequestDescriptor) -> CreateChatCompletionRequest {
         }));
     }
 
-    let media_part = match desc.media {
-        Media::Image(image) => ChatCompletionContentPart::ImageUrl(ChatCompletionContentPartImage {
-            image_url: ImageUrl { url: image.to_url() },
-        }),
-        Media::Video(video) => ChatCompletionContentPart::VideoUrl(ChatCompletionContentPartVideo {
-            video_url: VideoUrl { url: video.to_url() },
-        }),
-    };
-    let mut user_parts = vec![media_part];
-
-    if let Some(text) = desc.user_text {
-        user_parts.push(ChatCompletionContentPart::Text(ChatCompletionContentPartText { text }));
+    for example in desc.few_shot {
+        let text = (!example.prompt.is_empty()).then_some(example.prompt);
+        messages.push(user_message(media_with_text(example.media, text)));
+        messages.push(ChatCompletionMessage::Assistant(ChatCompletionAssistantMessage {
+            content: ChatCompletionAssistantMessageContent::Text(example.answer.to_text()),
+        }));
     }
 
-    messages.push(ChatCompletionMessage::User(ChatCompletionUserMessage {
-        content: ChatCompletionUserMessageContent::Array(user_parts),
-    }));
+    messages.push(user_message(desc.user_content));
 
     CreateChatCompletionRequest {
         messages,
@@ -325,5 +1257,31 @@ fn build_wire_request(desc: RequestDescriptor) -> CreateChatCompletionRequest {
         top_k: desc.top_k,
         frequency_penalty: desc.frequency_penalty,
         presence_penalty: desc.presence_penalty,
+        n: None,
+    }
+}
+
+fn content_part(part: ChatContentPart) -> ChatCompletionContentPart {
+    match part {
+        ChatContentPart::Text(text) => ChatCompletionContentPart::Text(ChatCompletionContentPartText { text }),
+        ChatContentPart::Image(image) => ChatCompletionContentPart::ImageUrl(ChatCompletionContentPartImage {
+            image_url: ImageUrl { url: image.to_url() },
+        }),
+        ChatContentPart::Video(video) => ChatCompletionContentPart::VideoUrl(ChatCompletionContentPartVideo {
+            video_url: VideoUrl { url: video.to_url() },
+        }),
     }
 }
+
+/// User content with the media first, followed by the text (if any).
+fn media_with_text(media: Media, text: Option<String>) -> Vec<ChatContentPart> {
+    let mut parts = vec![ChatContentPart::from(media)];
+    parts.extend(text.map(ChatContentPart::Text));
+    parts
+}
+
+fn user_message(content: Vec<ChatContentPart>) -> ChatCompletionMessage {
+    ChatCompletionMessage::User(ChatCompletionUserMessage {
+        content: ChatCompletionUserMessageContent::Array(content.into_iter().map(content_part).collect()),
+    })
+}
@@ -0,0 +1,134 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::parsing;
+use crate::pointing::{BoundingBox, Pointing};
+
+static CHANGE_MENTION_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^\s*(added|removed|changed)\s*(?:\(\s*(before|after)\s*\))?\s*(?::\s*(.*?))?\s*$")
+        .expect("regex creation should never fail here")
+});
+
+/// Kind of change between the two compared images.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum ChangeKind {
+    /// Present only in the after image.
+    Added,
+    /// Present only in the before image.
+    Removed,
+    /// Present in both images but different.
+    Changed,
+}
+
+/// Which of the two compared images a region belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum ComparedImage {
+    /// The first (before) image.
+    Before,
+    /// The second (after) image.
+    After,
+}
+
+/// A changed region, located in one of the two compared images.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Change {
+    /// Kind of change.
+    pub kind: ChangeKind,
+    /// Image the box coordinates refer to.
+    pub image: ComparedImage,
+    /// What changed (e.g. `"red car"`), if the model named it.
+    pub label: Option<String>,
+    /// Region of the change in [`Self::image`]. The box mention is cleared.
+    pub bbox: BoundingBox,
+}
+
+/// Read change boxes tagged `added: x`, `removed: x` or `changed (before|after): x`.
+///
+/// Added regions belong to the after image and removed regions to the before image.
+/// Changed regions use the image named in parentheses, defaulting to the after image.
+/// Boxes whose mention does not name a change kind are skipped.
+pub(crate) fn extract_changes(pointing: Option<&Pointing>) -> Vec<Change> {
+    let boxes = pointing.map(|p| p.boxes.as_slice()).unwrap_or_default();
+    boxes
+        .iter()
+        .filter_map(|b| {
+            let caps = CHANGE_MENTION_REGEX.captures(b.mention.as_deref()?)?;
+            let kind = match caps[1].to_lowercase().as_str() {
+                "added" => ChangeKind::Added,
+                "removed" => ChangeKind::Removed,
+                _ => ChangeKind::Changed,
+            };
+            let image = match (caps.get(2).map(|m| m.as_str().to_lowercase()), kind) {
+                (Some(side), _) if side == "before" => ComparedImage::Before,
+                (Some(_), _) => ComparedImage::After,
+                (None, ChangeKind::Removed) => ComparedImage::Before,
+                (None, _) => ComparedImage::After,
+            };
+            let label = caps.get(3).map(|m| m.as_str().to_string()).filter(|l| !l.is_empty());
+            Some(Change {
+                kind,
+                image,
+                label,
+                bbox: BoundingBox {
+                    mention: None,
+                    ..b.clone()
+                },
+            })
+        })
+        .collect()
+}
+
+/// The prose of a comparison answer, without annotation tags.
+pub(crate) fn summary(text: &str) -> Option<String> {
+    Some(parsing::strip_tags(text)).filter(|s| !s.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::OutputFormat;
+
+    #[test]
+    fn attributes_changes_to_images() {
+        let text = r#"A car left and the door was painted.
+<point_box mention="removed: red car"> (10,10) (50,40) </point_box>
+<point_box mention="added: bicycle"> (60,10) (80,40) </point_box>
+<point_box mention="Changed (before): door"> (1,1) (5,9) </point_box>
+<point_box mention="changed (after): door"> (2,1) (6,9) </point_box>
+<point_box mention="tree"> (0,0) (1,1) </point_box>
+<point_box mention="changed"> (3,3) (4,4) </point_box>"#;
+        let pointing = parsing::extract(text, Some(&OutputFormat::Box));
+        let changes = extract_changes(pointing.as_ref());
+        let summary: Vec<_> = changes
+            .iter()
+            .map(|c| (c.kind, c.image, c.label.as_deref(), c.bbox.x1))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (ChangeKind::Removed, ComparedImage::Before, Some("red car"), 10),
+                (ChangeKind::Added, ComparedImage::After, Some("bicycle"), 60),
+                (ChangeKind::Changed, ComparedImage::Before, Some("door"), 1),
+                (ChangeKind::Changed, ComparedImage::After, Some("door"), 2),
+                (ChangeKind::Changed, ComparedImage::After, None, 3),
+            ]
+        );
+        assert_eq!(changes[0].bbox.mention, None);
+    }
+
+    #[test]
+    fn summary_strips_tags() {
+        assert_eq!(
+            summary(r#"A car left. <point_box mention="removed: car"> (1,1) (2,2) </point_box>"#),
+            Some("A car left.".to_string())
+        );
+        assert_eq!(summary("<point_box> (1,1) (2,2) </point_box>"), None);
+    }
+}
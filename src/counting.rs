@@ -0,0 +1,198 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::classification::match_exact;
+use crate::parsing;
+use crate::pointing::{Point, Pointing};
+
+static LABELED_COUNT_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(.+?)\s*[:=]\s*(\w+)\b").expect("regex creation should never fail here"));
+static INLINE_COUNT_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    let numbers = NUMBER_WORDS.join("|");
+    Regex::new(&format!(r"(?i)\b(\d+|{numbers})\s+(\w+(?:[ -]\w+)?)")).expect("regex creation should never fail here")
+});
+
+const NUMBER_WORDS: [&str; 21] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+    "twenty",
+];
+
+/// Count of one class, backed by the points the model placed on each instance.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ClassCount {
+    /// The requested class, exactly as given.
+    pub class: String,
+    /// Number of points attributed to the class.
+    pub count: usize,
+    /// One point per counted instance.
+    pub points: Vec<Point>,
+    /// Total the model stated in its prose, if any.
+    pub stated_count: Option<u32>,
+    /// Whether the stated total differs from the number of points.
+    pub mismatch: bool,
+}
+
+/// Parse a count written as digits or as an English word up to twenty.
+fn parse_number(word: &str) -> Option<u32> {
+    word.parse().ok().or_else(|| {
+        let word = word.to_lowercase();
+        NUMBER_WORDS.iter().position(|w| *w == word).map(|n| n as u32)
+    })
+}
+
+/// Find the totals the model stated per class, from `class: 3` lines or phrases like
+/// `3 cars` / `three cars`. The first total found for a class wins.
+///
+/// Labels must name a class exactly, ignoring only case, punctuation and plurals, so a
+/// sentence like `There are 3 cars: two red` states 3 cars, not 2.
+fn stated_counts(text: &str, classes: &[String]) -> Vec<Option<u32>> {
+    let mut stated = vec![None; classes.len()];
+    let mut record = |label: &str, number: &str| {
+        let (Some(n), Some(class)) = (parse_number(number), match_exact(label, classes)) else {
+            return false;
+        };
+        let slot = &mut stated[classes.iter().position(|c| c == class).unwrap_or_default()];
+        slot.get_or_insert(n);
+        true
+    };
+    for line in text.lines().map(parsing::strip_tags) {
+        let line = line.trim_start_matches(['-', '*', ' ']);
+        if let Some(caps) = LABELED_COUNT_REGEX.captures(line)
+            && record(&caps[1], &caps[2])
+        {
+            continue;
+        }
+        for caps in INLINE_COUNT_REGEX.captures_iter(line) {
+            // The phrase may run into the next word (`4 cars and`), so retry with its first word.
+            if !record(&caps[2], &caps[1])
+                && let Some((word, _)) = caps[2].split_once(' ')
+            {
+                record(word, &caps[1]);
+            }
+        }
+    }
+    stated
+}
+
+/// Attribute points to classes and compare with the totals stated in `text`.
+///
+/// Points are matched to classes by mention, ignoring only case, punctuation and plurals.
+/// With a single class, unlabeled points count towards it; otherwise points that match no
+/// class are left unattributed.
+pub(crate) fn count_classes(text: &str, pointing: Option<&Pointing>, classes: &[String]) -> Vec<ClassCount> {
+    let stated = stated_counts(text, classes);
+    let mut counts: Vec<ClassCount> = classes
+        .iter()
+        .zip(stated)
+        .map(|(class, stated_count)| ClassCount {
+            class: class.clone(),
+            count: 0,
+            points: Vec::new(),
+            stated_count,
+            mismatch: false,
+        })
+        .collect();
+
+    for point in pointing.map(|p| p.points.as_slice()).unwrap_or_default() {
+        let class = match point.mention.as_deref() {
+            Some(mention) => match_exact(mention, classes),
+            None if classes.len() == 1 => Some(classes[0].as_str()),
+            None => None,
+        };
+        if let Some(entry) = class.and_then(|class| counts.iter_mut().find(|c| c.class == class)) {
+            entry.points.push(point.clone());
+        }
+    }
+    for entry in &mut counts {
+        entry.count = entry.points.len();
+        entry.mismatch = entry.stated_count.is_some_and(|n| n as usize != entry.count);
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::OutputFormat;
+
+    fn classes(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn stated_totals() {
+        let classes = classes(&["car", "traffic light"]);
+        assert_eq!(
+            stated_counts("car: 3\n- Traffic lights: two", &classes),
+            vec![Some(3), Some(2)]
+        );
+        assert_eq!(
+            stated_counts("I can see 4 cars and one traffic light.", &classes),
+            vec![Some(4), Some(1)]
+        );
+        assert_eq!(stated_counts("Nothing here.", &classes), vec![None, None]);
+        assert_eq!(
+            stated_counts("There are 3 cars: two red and one blue.", &classes),
+            vec![Some(3), None]
+        );
+    }
+
+    #[test]
+    fn counts_points_and_flags_mismatch() {
+        let text = r#"<point mention="car"> (1,1) </point> <point mention="cars"> (2,2) </point>
+<point mention="person"> (3,3) </point> <point mention="bus"> (4,4) </point>
+There are 3 cars and 1 person."#;
+        let pointing = parsing::extract(text, Some(&OutputFormat::Point));
+        let counts = count_classes(text, pointing.as_ref(), &classes(&["car", "person"]));
+
+        assert_eq!(counts[0].count, 2);
+        assert_eq!(counts[0].stated_count, Some(3));
+        assert!(counts[0].mismatch);
+        assert_eq!(counts[1].count, 1);
+        assert_eq!(counts[1].points[0].x, 3);
+        assert!(!counts[1].mismatch);
+    }
+
+    #[test]
+    fn only_exact_mentions_count() {
+        let text = r#"<point mention="car seat"> (1,1) </point> <point mention="cats"> (2,2) </point>
+<point mention="Cars"> (3,3) </point> <point mention="cat"> (4,4) </point>"#;
+        let pointing = parsing::extract(text, Some(&OutputFormat::Point));
+        let counts = count_classes(text, pointing.as_ref(), &classes(&["car", "cat"]));
+
+        assert_eq!(counts[0].points.iter().map(|p| p.x).collect::<Vec<_>>(), [3]);
+        assert_eq!(counts[1].points.iter().map(|p| p.x).collect::<Vec<_>>(), [2, 4]);
+    }
+
+    #[test]
+    fn unlabeled_points_count_for_single_class() {
+        let text = "<point> (1,1) </point> <point> (2,2) </point>";
+        let pointing = parsing::extract(text, Some(&OutputFormat::Point));
+        let counts = count_classes(text, pointing.as_ref(), &classes(&["apple"]));
+        assert_eq!(counts[0].count, 2);
+        assert_eq!(counts[0].stated_count, None);
+        assert!(!counts[0].mismatch);
+    }
+}
@@ -0,0 +1,542 @@
+use std::borrow::Cow;
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::classification::match_label;
+use crate::parsing;
+use crate::pointing::{BoundingBox, Pointing};
+
+const REGEX_EXPECT: &str = "regex creation should never fail here";
+
+static HTML_TABLE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<table\b[^>]*>(.*?)</table>").expect(REGEX_EXPECT));
+static HTML_ROW_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<tr\b[^>]*>(.*?)</tr>").expect(REGEX_EXPECT));
+static HTML_CELL_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<(th|td)\b([^>]*)>(.*?)</(?:th|td)>").expect(REGEX_EXPECT));
+static HTML_TAG_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]*>").expect(REGEX_EXPECT));
+static SPAN_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?i)\b(colspan|rowspan)\s*=\s*["']?(\d+)"#).expect(REGEX_EXPECT));
+static NUMBER_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^([-+])?\s*[$€£¥]?\s*(\d{1,3}(?:,\d{3})+|\d+)(\.\d+)?\s*$").expect(REGEX_EXPECT));
+static DATE_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(\d{4})-(\d{2})-(\d{2})$").expect(REGEX_EXPECT));
+
+/// Answers that mean the field is not present in the document.
+const MISSING_VALUES: [&str; 6] = ["null", "none", "n/a", "na", "-", "not present"];
+
+/// Value of an extracted document field, typed from its text.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum FieldValue {
+    /// Free text.
+    Text(String),
+    /// A number, with currency symbols and thousands separators removed. Digit strings with
+    /// a leading zero or more precision than an `f64` holds (IDs, ZIP codes, account
+    /// numbers) stay [`FieldValue::Text`].
+    Number(f64),
+    /// A yes/no or checkbox value.
+    Boolean(bool),
+    /// An ISO 8601 date (`YYYY-MM-DD`).
+    Date(String),
+}
+
+impl FieldValue {
+    /// Type a value from its text, or `None` if the text says the field is missing.
+    fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let lower = text.to_lowercase();
+        if text.is_empty() || MISSING_VALUES.contains(&lower.as_str()) {
+            return None;
+        }
+        match lower.as_str() {
+            "yes" | "true" | "checked" => return Some(Self::Boolean(true)),
+            "no" | "false" | "unchecked" => return Some(Self::Boolean(false)),
+            _ => {}
+        }
+        if DATE_REGEX.captures(text).is_some_and(|caps| is_calendar_date(&caps)) {
+            return Some(Self::Date(text.to_string()));
+        }
+        if let Some(number) = NUMBER_REGEX.captures(text).and_then(|caps| exact_number(&caps)) {
+            return Some(Self::Number(number));
+        }
+        Some(Self::Text(text.to_string()))
+    }
+}
+
+/// Whether the year, month and day matched by [`DATE_REGEX`] name a day of the calendar.
+fn is_calendar_date(caps: &regex::Captures<'_>) -> bool {
+    let (Ok(year), Ok(month), Ok(day)) = (caps[1].parse::<u32>(), caps[2].parse::<u32>(), caps[3].parse::<u32>())
+    else {
+        return false;
+    };
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return false,
+    };
+    (1..=days).contains(&day)
+}
+
+/// The number matched by [`NUMBER_REGEX`], if it has no leading zero and `f64` holds it exactly.
+fn exact_number(caps: &regex::Captures<'_>) -> Option<f64> {
+    let integer = caps[2].replace(',', "");
+    if integer.len() > 1 && integer.starts_with('0') {
+        return None;
+    }
+    let sign = caps.get(1).map_or("", |m| m.as_str());
+    let fraction = caps
+        .get(3)
+        .map_or("", |m| m.as_str().trim_end_matches('0').trim_end_matches('.'));
+    let number: f64 = format!("{sign}{integer}{fraction}").parse().ok()?;
+    // `f64` formats without an exponent, so any lost digit shows up as a difference.
+    let canonical = format!("{}{integer}{fraction}", if sign == "-" { "-" } else { "" });
+    (number.to_string() == canonical).then_some(number)
+}
+
+/// A key/value pair read from a document.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DocumentField {
+    /// The requested field name, exactly as given, or the label printed on the document.
+    pub key: String,
+    /// Typed value, or `None` if the field was not found.
+    pub value: Option<FieldValue>,
+    /// Value as written by the model.
+    pub text: Option<String>,
+    /// Where the value appears in the image, if the model marked it.
+    pub bbox: Option<BoundingBox>,
+}
+
+/// A table cell.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Cell {
+    /// Cell text with markup removed.
+    pub text: String,
+    /// Number of columns the cell spans.
+    pub colspan: u32,
+    /// Number of rows the cell spans.
+    pub rowspan: u32,
+}
+
+impl Cell {
+    fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            colspan: 1,
+            rowspan: 1,
+        }
+    }
+}
+
+/// A table parsed from Markdown or HTML output.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Table {
+    /// Column headers, empty if the table has no header row.
+    pub headers: Vec<String>,
+    /// Body rows. Cells covered by a span from another cell are omitted.
+    pub rows: Vec<Vec<Cell>>,
+}
+
+impl Table {
+    /// Parse every table in OCR output produced with
+    /// [`OcrMode::Html`](crate::OcrMode::Html) or [`OcrMode::Markdown`](crate::OcrMode::Markdown).
+    ///
+    /// HTML tables are returned first, followed by Markdown tables, each in document order.
+    pub fn parse_all(markup: &str) -> Vec<Table> {
+        let mut tables: Vec<Table> = HTML_TABLE_REGEX
+            .captures_iter(markup)
+            .filter_map(|caps| parse_html_table(&caps[1]))
+            .collect();
+        tables.extend(parse_markdown_tables(&HTML_TABLE_REGEX.replace_all(markup, "\n")));
+        tables
+    }
+
+    /// Export as CSV. Spanned cells are written once and the positions they cover are left
+    /// empty, so every record keeps its columns aligned.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::new();
+        if !self.headers.is_empty() {
+            push_record(&mut out, self.headers.iter().map(String::as_str));
+        }
+        // Rows still covered by a rowspan, per column.
+        let mut covered: Vec<u32> = Vec::new();
+        for row in &self.rows {
+            let mut record = Vec::new();
+            let mut cells = row.iter();
+            let mut column = 0;
+            loop {
+                if let Some(rows) = covered.get_mut(column).filter(|rows| **rows > 0) {
+                    *rows -= 1;
+                    record.push("");
+                    column += 1;
+                    continue;
+                }
+                let Some(cell) = cells.next() else {
+                    break;
+                };
+                for span in 0..cell.colspan.max(1) {
+                    record.push(if span == 0 { cell.text.as_str() } else { "" });
+                    if covered.len() <= column {
+                        covered.resize(column + 1, 0);
+                    }
+                    covered[column] = cell.rowspan.max(1) - 1;
+                    column += 1;
+                }
+            }
+            push_record(&mut out, record);
+        }
+        out
+    }
+}
+
+fn csv_field(text: &str) -> Cow<'_, str> {
+    if text.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", text.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+fn push_record<'a>(out: &mut String, fields: impl IntoIterator<Item = &'a str>) {
+    let fields: Vec<_> = fields.into_iter().map(csv_field).collect();
+    out.push_str(&fields.join(","));
+    out.push('\n');
+}
+
+/// Text of an HTML cell: line breaks become spaces, tags are dropped and entities decoded.
+fn html_text(html: &str) -> String {
+    let text = HTML_TAG_REGEX.replace_all(html, " ");
+    let text = text
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn parse_html_table(body: &str) -> Option<Table> {
+    let mut rows: Vec<(bool, Vec<Cell>)> = HTML_ROW_REGEX
+        .captures_iter(body)
+        .map(|row| {
+            let mut all_headers = true;
+            let cells = HTML_CELL_REGEX
+                .captures_iter(&row[1])
+                .map(|cell| {
+                    all_headers &= cell[1].eq_ignore_ascii_case("th");
+                    let mut parsed = Cell::new(html_text(&cell[3]));
+                    for span in SPAN_REGEX.captures_iter(&cell[2]) {
+                        let value = span[2].parse().unwrap_or(1).max(1);
+                        if span[1].eq_ignore_ascii_case("colspan") {
+                            parsed.colspan = value;
+                        } else {
+                            parsed.rowspan = value;
+                        }
+                    }
+                    parsed
+                })
+                .collect::<Vec<_>>();
+            (all_headers && !cells.is_empty(), cells)
+        })
+        .filter(|(_, cells)| !cells.is_empty())
+        .collect();
+    if rows.is_empty() {
+        return None;
+    }
+    let headers = if rows[0].0 {
+        rows.remove(0)
+            .1
+            .into_iter()
+            .flat_map(|cell| std::iter::once(cell.text).chain((1..cell.colspan).map(|_| String::new())))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    Some(Table {
+        headers,
+        rows: rows.into_iter().map(|(_, cells)| cells).collect(),
+    })
+}
+
+/// Split a Markdown table row on unescaped pipes.
+fn markdown_cells(line: &str) -> Vec<String> {
+    let line = line.trim();
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let line = line.strip_suffix('|').filter(|l| !l.ends_with('\\')).unwrap_or(line);
+    let mut cells = Vec::new();
+    let mut current = String::new();
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'|') => current.push(chars.next().unwrap_or('|')),
+            '|' => cells.push(std::mem::take(&mut current).trim().to_string()),
+            _ => current.push(c),
+        }
+    }
+    cells.push(current.trim().to_string());
+    cells
+}
+
+fn is_markdown_separator(line: &str) -> bool {
+    line.contains('-') && line.chars().all(|c| matches!(c, '|' | '-' | ':' | ' ' | '\t'))
+}
+
+/// Read pipe tables. Rows may leave out the edge pipes, but then the table must start with a
+/// header row and separator row.
+fn parse_markdown_tables(text: &str) -> Vec<Table> {
+    let mut tables = Vec::new();
+    let mut block: Vec<&str> = Vec::new();
+    let lines: Vec<&str> = text.lines().chain(std::iter::once("")).collect();
+    for (i, &line) in lines.iter().enumerate() {
+        let starts_table = line.trim_start().starts_with('|')
+            || (line.contains('|')
+                && lines
+                    .get(i + 1)
+                    .is_some_and(|next| next.contains('|') && is_markdown_separator(next)));
+        if starts_table || (!block.is_empty() && line.contains('|')) {
+            block.push(line);
+            continue;
+        }
+        if block.is_empty() {
+            continue;
+        }
+        let mut table = Table::default();
+        let mut rows = &block[..];
+        if rows.len() > 1 && is_markdown_separator(rows[1]) {
+            table.headers = markdown_cells(rows[0]);
+            rows = &rows[2..];
+        }
+        table.rows = rows
+            .iter()
+            .map(|row| markdown_cells(row).into_iter().map(Cell::new).collect())
+            .collect();
+        tables.push(table);
+        block.clear();
+    }
+    tables
+}
+
+/// Strip emphasis around a key or value.
+fn trim_markup(text: &str) -> &str {
+    text.trim_matches(|c: char| matches!(c, '*' | '`' | '_') || c.is_whitespace())
+}
+
+/// Read `key: value` lines. Table rows, markup and lines without a value are skipped.
+fn key_values(text: &str) -> Vec<(String, String)> {
+    text.lines()
+        .map(parsing::strip_tags)
+        .filter(|line| !line.starts_with(['|', '<']) && !is_markdown_separator(line))
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            let key = trim_markup(key.trim_start_matches(['-', '*', '•', ' ']));
+            let value = trim_markup(value);
+            (!key.is_empty() && !value.is_empty()).then(|| (key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Read the requested fields from a document answer.
+///
+/// With requested `fields`, the result has one entry per field in request order, matching
+/// the model's keys fuzzily; fields the model omitted or marked `null` have no value.
+/// Without requested fields, every `key: value` line is returned. Each field takes the
+/// first box whose mention matches its key.
+pub(crate) fn extract_fields(text: &str, pointing: Option<&Pointing>, fields: &[String]) -> Vec<DocumentField> {
+    let mut result: Vec<DocumentField> = fields
+        .iter()
+        .map(|key| DocumentField {
+            key: key.clone(),
+            value: None,
+            text: None,
+            bbox: None,
+        })
+        .collect();
+    for (key, value) in key_values(text) {
+        let index = if fields.is_empty() {
+            match result.iter().position(|f| f.key.eq_ignore_ascii_case(&key)) {
+                Some(index) => index,
+                None => {
+                    result.push(DocumentField {
+                        key,
+                        value: None,
+                        text: None,
+                        bbox: None,
+                    });
+                    result.len() - 1
+                }
+            }
+        } else {
+            let Some(field) = match_label(&key, fields) else {
+                continue;
+            };
+            result.iter().position(|f| f.key == field).unwrap_or_default()
+        };
+        let entry = &mut result[index];
+        if entry.text.is_none() && entry.value.is_none() {
+            entry.value = FieldValue::parse(&value);
+            entry.text = entry.value.is_some().then_some(value);
+        }
+    }
+
+    let keys: Vec<String> = result.iter().map(|f| f.key.clone()).collect();
+    for b in pointing.map(|p| p.boxes.as_slice()).unwrap_or_default() {
+        let Some(key) = b.mention.as_deref().and_then(|mention| match_label(mention, &keys)) else {
+            continue;
+        };
+        if let Some(entry) = result.iter_mut().find(|f| f.key == key && f.bbox.is_none()) {
+            entry.bbox = Some(BoundingBox {
+                mention: None,
+                ..b.clone()
+            });
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::OutputFormat;
+
+    #[test]
+    fn types_values() {
+        assert_eq!(FieldValue::parse("$1,234.50"), Some(FieldValue::Number(1234.5)));
+        assert_eq!(FieldValue::parse("-42"), Some(FieldValue::Number(-42.0)));
+        assert_eq!(
+            FieldValue::parse("2024-03-01"),
+            Some(FieldValue::Date("2024-03-01".to_string()))
+        );
+        assert_eq!(FieldValue::parse("Yes"), Some(FieldValue::Boolean(true)));
+        assert_eq!(
+            FieldValue::parse("ACME Corp."),
+            Some(FieldValue::Text("ACME Corp.".to_string()))
+        );
+        assert_eq!(
+            FieldValue::parse("12 Main St, 4"),
+            Some(FieldValue::Text("12 Main St, 4".to_string()))
+        );
+        assert_eq!(FieldValue::parse("null"), None);
+    }
+
+    #[test]
+    fn rejects_impossible_dates() {
+        assert_eq!(
+            FieldValue::parse("2024-02-29"),
+            Some(FieldValue::Date("2024-02-29".to_string()))
+        );
+        for text in ["2024-13-45", "2023-02-29", "2024-04-31", "2024-00-10"] {
+            assert_eq!(
+                FieldValue::parse(text),
+                Some(FieldValue::Text(text.to_string())),
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn keeps_lossy_numbers_as_text() {
+        assert_eq!(FieldValue::parse("0.5"), Some(FieldValue::Number(0.5)));
+        assert_eq!(FieldValue::parse("1.10"), Some(FieldValue::Number(1.1)));
+        for text in ["00123", "02134", "12345678901234567890", "0.12345678901234567891"] {
+            assert_eq!(
+                FieldValue::parse(text),
+                Some(FieldValue::Text(text.to_string())),
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn extracts_requested_fields() {
+        let text = r#"**Vendor**: ACME Corp. <point_box mention="vendor"> (10,10) (90,20) </point_box>
+- Invoice date: 2024-03-01
+Total amount: $1,234.50 <point_box mention="total"> (300,400) (380,420) </point_box>
+Tax id: null"#;
+        let pointing = parsing::extract(text, Some(&OutputFormat::Box));
+        let fields: Vec<String> = ["vendor", "date", "total", "tax id", "due date"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let result = extract_fields(text, pointing.as_ref(), &fields);
+
+        let keys: Vec<_> = result.iter().map(|f| f.key.as_str()).collect();
+        assert_eq!(keys, ["vendor", "date", "total", "tax id", "due date"]);
+        assert_eq!(result[0].value, Some(FieldValue::Text("ACME Corp.".to_string())));
+        assert_eq!(
+            result[0].bbox.as_ref().map(|b| (b.x1, b.mention.clone())),
+            Some((10, None))
+        );
+        assert_eq!(result[1].value, Some(FieldValue::Date("2024-03-01".to_string())));
+        assert_eq!(result[1].bbox, None);
+        assert_eq!(result[2].value, Some(FieldValue::Number(1234.5)));
+        assert_eq!(result[2].text.as_deref(), Some("$1,234.50"));
+        assert_eq!(result[3].value, None);
+        assert_eq!(result[3].text, None);
+        assert_eq!(result[4].value, None);
+    }
+
+    #[test]
+    fn extracts_all_fields_without_a_list() {
+        let result = extract_fields(
+            "Name: Jane Doe
+Signed: yes
+Name: John",
+            None,
+            &[],
+        );
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].text.as_deref(), Some("Jane Doe"));
+        assert_eq!(result[1].key, "Signed");
+        assert_eq!(result[1].value, Some(FieldValue::Boolean(true)));
+    }
+
+    #[test]
+    fn parses_markdown_tables() {
+        let text = "Items:\n\n| Item | Qty | Price |\n|:-----|----:|------:|\n| Widget | 2 | 3.50 |\n| A \\| B | 1 | 1.00 |\n\nThanks";
+        let tables = Table::parse_all(text);
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].headers, ["Item", "Qty", "Price"]);
+        assert_eq!(tables[0].rows.len(), 2);
+        assert_eq!(tables[0].rows[1][0].text, "A | B");
+        assert_eq!(tables[0].to_csv(), "Item,Qty,Price\nWidget,2,3.50\nA | B,1,1.00\n");
+    }
+
+    #[test]
+    fn parses_tables_without_edge_pipes() {
+        let text = "Total: 5.50\n\nItem | Qty\n--- | ---:\nWidget | 2\nGadget | 1\n\nA | B is not a table";
+        let tables = Table::parse_all(text);
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].headers, ["Item", "Qty"]);
+        assert_eq!(tables[0].to_csv(), "Item,Qty\nWidget,2\nGadget,1\n");
+
+        let result = extract_fields(text, None, &[]);
+        let keys: Vec<_> = result.iter().map(|f| f.key.as_str()).collect();
+        assert_eq!(keys, ["Total"]);
+    }
+
+    #[test]
+    fn parses_html_tables_with_spans() {
+        let html = r#"<table>
+<tr><th>Region</th><th colspan="2">Sales</th></tr>
+<tr><td rowspan="2">North</td><td>Q1</td><td>1,000</td></tr>
+<tr><td>Q2</td><td>&quot;n/a&quot;</td></tr>
+</table>"#;
+        let tables = Table::parse_all(html);
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].headers, ["Region", "Sales", ""]);
+        assert_eq!(tables[0].rows[0][0].rowspan, 2);
+        assert_eq!(
+            tables[0].to_csv(),
+            "Region,Sales,\nNorth,Q1,\"1,000\"\n,Q2,\"\"\"n/a\"\"\"\n"
+        );
+    }
+}
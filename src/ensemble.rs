@@ -0,0 +1,307 @@
+use serde::{Deserialize, Serialize};
+
+use crate::parsing;
+use crate::pointing::{BoundingBox, Point, Pointing};
+use crate::types::{OutputFormat, PointingResponse, TextResponse};
+
+/// Minimum intersection over union for boxes from different samples to be the same object.
+const MIN_BOX_IOU: f32 = 0.5;
+/// Maximum distance, in model coordinates, for points from different samples to be the same object.
+const MAX_POINT_DISTANCE: f32 = 30.0;
+
+/// Self-consistency settings: sample the model several times and keep what the samples agree on.
+///
+/// Use a non-zero temperature, otherwise the samples are likely to be identical.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Ensemble {
+    /// Number of samples to draw.
+    pub samples: u32,
+    /// Minimum number of samples an object must appear in to be kept. Defaults to a majority.
+    pub min_votes: Option<u32>,
+    /// Request all samples in one call using the `n` parameter, for servers that support it.
+    /// Samples the server does not return are requested separately.
+    pub use_choices: Option<bool>,
+}
+
+impl Ensemble {
+    /// Draw `samples` samples and keep objects found by a majority of them.
+    pub fn new(samples: u32) -> Self {
+        Self {
+            samples,
+            min_votes: None,
+            use_choices: None,
+        }
+    }
+
+    /// Keep objects found in at least `votes` samples.
+    pub fn min_votes(mut self, votes: u32) -> Self {
+        self.min_votes = Some(votes);
+        self
+    }
+
+    /// Request all samples in one call using the `n` parameter.
+    pub fn use_choices(mut self, enable: bool) -> Self {
+        self.use_choices = Some(enable);
+        self
+    }
+
+    /// Votes needed to keep an object, between 1 and the number of samples.
+    pub(crate) fn threshold(&self) -> u32 {
+        let samples = self.samples.max(1);
+        self.min_votes.unwrap_or(samples / 2 + 1).clamp(1, samples)
+    }
+}
+
+impl From<u32> for Ensemble {
+    fn from(samples: u32) -> Self {
+        Self::new(samples)
+    }
+}
+
+/// How much the samples of an ensembled request agreed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct EnsembleStats {
+    /// Number of samples drawn.
+    pub samples: u32,
+    /// Share of samples whose text answer matches the chosen one.
+    pub answer_agreement: f32,
+    /// Share of samples that found each box, in the order of `pointing.boxes`.
+    pub box_agreement: Vec<f32>,
+    /// Share of samples that found each point, in the order of `pointing.points`.
+    pub point_agreement: Vec<f32>,
+}
+
+/// Normalized answer text used for voting: tags removed, lowercase, no trailing punctuation.
+fn vote_key(content: Option<&str>) -> String {
+    let text = parsing::strip_tags(content.unwrap_or_default()).to_lowercase();
+    text.trim_end_matches(['.', '!', '?']).trim().to_string()
+}
+
+fn same_mention(a: Option<&str>, b: Option<&str>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => a.trim().eq_ignore_ascii_case(b.trim()),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+fn iou(a: &BoundingBox, b: &BoundingBox) -> f32 {
+    let width = a.x2.min(b.x2).saturating_sub(a.x1.max(b.x1)) as f32;
+    let height = a.y2.min(b.y2).saturating_sub(a.y1.max(b.y1)) as f32;
+    let area = |b: &BoundingBox| (b.x2.saturating_sub(b.x1) * b.y2.saturating_sub(b.y1)) as f32;
+    let intersection = width * height;
+    let union = area(a) + area(b) - intersection;
+    if union > 0.0 { intersection / union } else { 0.0 }
+}
+
+/// Group items from different samples that refer to the same object. Each cluster holds at
+/// most one item per sample and is matched against its first item.
+fn cluster<T>(samples: Vec<Vec<T>>, same: impl Fn(&T, &T) -> bool) -> Vec<Vec<T>> {
+    let mut clusters: Vec<(Vec<usize>, Vec<T>)> = Vec::new();
+    for (sample, items) in samples.into_iter().enumerate() {
+        for item in items {
+            match clusters
+                .iter_mut()
+                .find(|(members, items)| !members.contains(&sample) && same(&items[0], &item))
+            {
+                Some((members, items)) => {
+                    members.push(sample);
+                    items.push(item);
+                }
+                None => clusters.push((vec![sample], vec![item])),
+            }
+        }
+    }
+    clusters.into_iter().map(|(_, items)| items).collect()
+}
+
+fn mean(values: impl Iterator<Item = u32>, count: usize) -> u32 {
+    (values.map(f64::from).sum::<f64>() / count as f64).round() as u32
+}
+
+fn consensus_boxes(samples: Vec<Vec<BoundingBox>>, min_votes: usize, total: f32) -> (Vec<BoundingBox>, Vec<f32>) {
+    cluster(samples, |a, b| {
+        same_mention(a.mention.as_deref(), b.mention.as_deref()) && iou(a, b) >= MIN_BOX_IOU
+    })
+    .into_iter()
+    .filter(|items| items.len() >= min_votes)
+    .map(|items| {
+        let n = items.len();
+        let merged = BoundingBox {
+            x1: mean(items.iter().map(|b| b.x1), n),
+            y1: mean(items.iter().map(|b| b.y1), n),
+            x2: mean(items.iter().map(|b| b.x2), n),
+            y2: mean(items.iter().map(|b| b.y2), n),
+            ..items[0].clone()
+        };
+        (merged, n as f32 / total)
+    })
+    .unzip()
+}
+
+fn consensus_points(samples: Vec<Vec<Point>>, min_votes: usize, total: f32) -> (Vec<Point>, Vec<f32>) {
+    cluster(samples, |a, b| {
+        let distance = (a.x as f32 - b.x as f32).hypot(a.y as f32 - b.y as f32);
+        same_mention(a.mention.as_deref(), b.mention.as_deref()) && distance <= MAX_POINT_DISTANCE
+    })
+    .into_iter()
+    .filter(|items| items.len() >= min_votes)
+    .map(|items| {
+        let n = items.len();
+        let merged = Point {
+            x: mean(items.iter().map(|p| p.x), n),
+            y: mean(items.iter().map(|p| p.y), n),
+            ..items[0].clone()
+        };
+        (merged, n as f32 / total)
+    })
+    .unzip()
+}
+
+/// Combine samples into one response.
+///
+/// The text answer is chosen by majority vote (earliest sample on ties), and its content and
+/// reasoning are returned. Boxes and points are clustered across samples and kept when found
+/// in at least `min_votes` samples, with averaged coordinates. Polygons and clips come from
+/// the chosen sample. As for a single response, `pointing` is `None` when nothing is left,
+/// including when no box or point reached `min_votes`.
+pub(crate) fn aggregate(
+    samples: Vec<TextResponse>,
+    output_format: Option<&OutputFormat>,
+    min_votes: u32,
+) -> PointingResponse {
+    let total = samples.len().max(1) as f32;
+    let keys: Vec<String> = samples.iter().map(|s| vote_key(s.content.as_deref())).collect();
+    let votes = |key: &String| keys.iter().filter(|k| *k == key).count();
+    let chosen = (0..keys.len())
+        .max_by(|&a, &b| votes(&keys[a]).cmp(&votes(&keys[b])).then(b.cmp(&a)))
+        .unwrap_or_default();
+    let answer_agreement = keys.get(chosen).map_or(0.0, |key| votes(key) as f32 / total);
+
+    let parsed: Vec<Pointing> = samples
+        .iter()
+        .map(|s| {
+            s.content
+                .as_deref()
+                .and_then(|text| parsing::extract(text, output_format))
+                .unwrap_or_default()
+        })
+        .collect();
+    let mut pointing = output_format.and(parsed.get(chosen).cloned());
+    let (box_agreement, point_agreement) = match pointing.as_mut() {
+        Some(pointing) => {
+            let min_votes = min_votes as usize;
+            let (boxes, box_agreement) =
+                consensus_boxes(parsed.iter().map(|p| p.boxes.clone()).collect(), min_votes, total);
+            let (points, point_agreement) =
+                consensus_points(parsed.iter().map(|p| p.points.clone()).collect(), min_votes, total);
+            pointing.boxes = boxes;
+            pointing.points = points;
+            (box_agreement, point_agreement)
+        }
+        None => (Vec::new(), Vec::new()),
+    };
+    let pointing = pointing.filter(|p| *p != Pointing::default());
+
+    let cached = !samples.is_empty() && samples.iter().all(|s| s.cached);
+    let representative = samples.into_iter().nth(chosen);
+    PointingResponse {
+        content: representative.as_ref().and_then(|s| s.content.clone()),
+        reasoning: representative.and_then(|s| s.reasoning),
+        cached,
+        pointing,
+        ensemble: Some(EnsembleStats {
+            samples: total as u32,
+            answer_agreement,
+            box_agreement,
+            point_agreement,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(content: &str) -> TextResponse {
+        TextResponse {
+            content: Some(content.to_string()),
+            reasoning: None,
+            cached: false,
+        }
+    }
+
+    #[test]
+    fn keeps_boxes_found_by_majority() {
+        let samples = vec![
+            sample(
+                r#"<point_box mention="car"> (100,100) (200,200) </point_box> <point_box mention="dog"> (500,500) (600,600) </point_box>"#,
+            ),
+            sample(r#"<point_box mention="Car"> (110,100) (210,200) </point_box>"#),
+            sample(
+                r#"<point_box mention="car"> (105,95) (205,195) </point_box> <point_box mention="cat"> (500,500) (600,600) </point_box>"#,
+            ),
+        ];
+        let response = aggregate(samples, Some(&OutputFormat::Box), 2);
+        let pointing = response.pointing.expect("pointing");
+        assert_eq!(pointing.boxes.len(), 1);
+        let b = &pointing.boxes[0];
+        assert_eq!(
+            (b.x1, b.y1, b.x2, b.y2, b.mention.as_deref()),
+            (105, 98, 205, 198, Some("car"))
+        );
+        let stats = response.ensemble.expect("stats");
+        assert_eq!(stats.samples, 3);
+        assert_eq!(stats.box_agreement, [1.0]);
+    }
+
+    #[test]
+    fn clusters_points() {
+        let samples = vec![
+            sample("<point> (100,100) </point> <point> (400,400) </point>"),
+            sample("<point> (110,90) </point>"),
+            sample("<point> (300,300) </point> <point> (405,395) </point>"),
+        ];
+        let response = aggregate(samples, Some(&OutputFormat::Point), 1);
+        let points: Vec<_> = response
+            .pointing
+            .expect("pointing")
+            .points
+            .iter()
+            .map(|p| (p.x, p.y))
+            .collect();
+        assert_eq!(points, [(105, 95), (403, 398), (300, 300)]);
+        let agreement = response.ensemble.expect("stats").point_agreement;
+        assert_eq!(agreement, [2.0 / 3.0, 2.0 / 3.0, 1.0 / 3.0]);
+    }
+
+    #[test]
+    fn no_consensus_has_no_pointing() {
+        let samples = vec![
+            sample(r#"<point_box mention="car"> (100,100) (200,200) </point_box>"#),
+            sample(r#"<point_box mention="dog"> (500,500) (600,600) </point_box>"#),
+            sample("Nothing here."),
+        ];
+        let response = aggregate(samples, Some(&OutputFormat::Box), 2);
+        assert_eq!(response.pointing, None);
+        assert!(response.ensemble.expect("stats").box_agreement.is_empty());
+    }
+
+    #[test]
+    fn majority_vote_for_text() {
+        let samples = vec![sample("Red."), sample("Blue"), sample("red"), sample("blue.")];
+        let response = aggregate(samples, None, 2);
+        assert_eq!(response.content.as_deref(), Some("Red."));
+        assert_eq!(response.pointing, None);
+        assert_eq!(response.ensemble.expect("stats").answer_agreement, 0.5);
+    }
+
+    #[test]
+    fn default_threshold_is_majority() {
+        assert_eq!(Ensemble::new(5).threshold(), 3);
+        assert_eq!(Ensemble::new(4).threshold(), 3);
+        assert_eq!(Ensemble::new(3).min_votes(9).threshold(), 3);
+    }
+}
@@ -27,7 +27,23 @@ pub enum PerceptronError {
     #[error("API error ({status}): {}", detail.message)]
     ApiError { status: u16, detail: ApiErrorDetail },
 
+    /// The client does not implement this task.
+    #[error("{0} is not supported by this client")]
+    Unsupported(&'static str),
+
+    /// The request is invalid and was not sent.
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+
     /// Failed to parse the API response.
     #[error("Failed to parse response: {0}")]
     ParseFailed(String),
+
+    /// Reading or writing a batch file failed.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The request was part of a batch that was cancelled before it finished.
+    #[error("Request cancelled")]
+    Cancelled,
 }
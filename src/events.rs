@@ -0,0 +1,177 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::parsing;
+use crate::pointing::{BoundingBox, ClipTimestamp, Point};
+use crate::types::OutputFormat;
+
+/// Hits separated by at most this many seconds are merged unless the request overrides it.
+pub(crate) const DEFAULT_MERGE_GAP: f32 = 1.0;
+
+static CLIP_TAG_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)<clip\b[^>]*/>").expect("regex creation should never fail here"));
+static CONFIDENCE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)\b(?:confidence|conf|score)=["']?(\d+(?:\.\d+)?)"#)
+        .expect("regex creation should never fail here")
+});
+
+/// How the representative frame of each event hit is located.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum KeyFrameFormat {
+    /// A `<point>` on the event.
+    Point,
+    /// A `<point_box>` around the event.
+    Box,
+}
+
+/// A representative frame of an event hit, located in the video.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum KeyFrame {
+    /// A point on the event.
+    Point(Point),
+    /// A box around the event.
+    Box(BoundingBox),
+}
+
+/// A time range where the searched event happens.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct EventHit {
+    /// When the event happens.
+    pub timestamp: ClipTimestamp,
+    /// Model confidence between 0 and 1, if it gave one. Merged hits keep the highest.
+    pub confidence: Option<f32>,
+    /// Representative frame, if key frames were requested. Merged hits keep the one from
+    /// their most confident part.
+    pub key_frame: Option<KeyFrame>,
+}
+
+struct Span {
+    start: f32,
+    end: f32,
+    confidence: Option<f32>,
+    key_frame: Option<KeyFrame>,
+}
+
+fn parse_confidence(attrs: &str) -> Option<f32> {
+    let value: f32 = CONFIDENCE_REGEX.captures(attrs)?[1].parse().ok()?;
+    Some(if value > 1.0 { value / 100.0 } else { value }.clamp(0.0, 1.0))
+}
+
+fn key_frame(line: &str, format: KeyFrameFormat) -> Option<KeyFrame> {
+    match format {
+        KeyFrameFormat::Point => parsing::extract(line, Some(&OutputFormat::Point))?
+            .points
+            .into_iter()
+            .next()
+            .map(KeyFrame::Point),
+        KeyFrameFormat::Box => parsing::extract(line, Some(&OutputFormat::Box))?
+            .boxes
+            .into_iter()
+            .next()
+            .map(KeyFrame::Box),
+    }
+}
+
+/// Read event hits from `<clip>` tags and merge those within `merge_gap` seconds.
+///
+/// A key frame in the given format is attached to the first clip on its line.
+pub(crate) fn extract_hits(text: &str, key_frames: Option<KeyFrameFormat>, merge_gap: f32) -> Vec<EventHit> {
+    let mut spans: Vec<Span> = Vec::new();
+    for line in text.lines() {
+        let mut key_frame = key_frames.and_then(|format| key_frame(line, format));
+        for tag in CLIP_TAG_REGEX.find_iter(line) {
+            let Some(clip) = parsing::extract(tag.as_str(), Some(&OutputFormat::Clip))
+                .and_then(|pointing| pointing.clips.into_iter().next())
+            else {
+                continue;
+            };
+            let (start, end) = match clip.timestamp {
+                ClipTimestamp::Moment(t) => (t, t),
+                ClipTimestamp::Range { start, end } => (start.min(end), start.max(end)),
+            };
+            spans.push(Span {
+                start,
+                end,
+                confidence: parse_confidence(tag.as_str()),
+                key_frame: key_frame.take(),
+            });
+        }
+    }
+    spans.sort_by(|a, b| a.start.total_cmp(&b.start));
+
+    let mut merged: Vec<Span> = Vec::new();
+    for span in spans {
+        match merged.last_mut() {
+            Some(last) if span.start - last.end <= merge_gap => {
+                last.end = last.end.max(span.end);
+                let more_confident = span.confidence.unwrap_or(0.0) > last.confidence.unwrap_or(0.0);
+                if span.key_frame.is_some() && (last.key_frame.is_none() || more_confident) {
+                    last.key_frame = span.key_frame;
+                }
+                if more_confident {
+                    last.confidence = span.confidence;
+                }
+            }
+            _ => merged.push(span),
+        }
+    }
+    merged
+        .into_iter()
+        .map(|span| EventHit {
+            timestamp: if span.start == span.end {
+                ClipTimestamp::Moment(span.start)
+            } else {
+                ClipTimestamp::Range {
+                    start: span.start,
+                    end: span.end,
+                }
+            },
+            confidence: span.confidence,
+            key_frame: span.key_frame,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_adjacent_hits() {
+        let text = r#"<clip t="40 45" confidence="0.6"/>
+<clip t="10 20" confidence="0.9"/> <clip t="20.5 25" confidence="70"/>
+<clip t=60/>"#;
+        let hits = extract_hits(text, None, DEFAULT_MERGE_GAP);
+        let summary: Vec<_> = hits.iter().map(|h| (h.timestamp.clone(), h.confidence)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (ClipTimestamp::Range { start: 10.0, end: 25.0 }, Some(0.9)),
+                (ClipTimestamp::Range { start: 40.0, end: 45.0 }, Some(0.6)),
+                (ClipTimestamp::Moment(60.0), None),
+            ]
+        );
+        assert_eq!(extract_hits(text, None, 0.0).len(), 4);
+    }
+
+    #[test]
+    fn attaches_key_frames() {
+        let text = r#"<clip t="10 12" confidence="0.5"/> <point_box t=11> (1,1) (5,5) </point_box>
+<clip t="12 14" confidence="0.8"/> <point_box t=13> (2,2) (6,6) </point_box>
+<clip t="30 31"/> <point t=30> (7,7) </point>"#;
+        let hits = extract_hits(text, Some(KeyFrameFormat::Box), DEFAULT_MERGE_GAP);
+        assert_eq!(hits.len(), 2);
+        assert!(matches!(&hits[0].key_frame, Some(KeyFrame::Box(b)) if b.x1 == 2 && b.timestamp == Some(13.0)));
+        assert_eq!(hits[1].key_frame, None);
+
+        let hits = extract_hits(text, Some(KeyFrameFormat::Point), DEFAULT_MERGE_GAP);
+        assert!(matches!(&hits[1].key_frame, Some(KeyFrame::Point(p)) if p.x == 7));
+    }
+}
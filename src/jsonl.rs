@@ -0,0 +1,369 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tokio::fs::{self, File, OpenOptions};
+use tokio::io::{AsyncWriteExt, BufWriter};
+use tokio::sync::mpsc;
+
+use crate::batch::{BatchProgress, BatchTask, CancelToken};
+use crate::client::{Perceptron, PerceptronClient};
+use crate::error::PerceptronError;
+use crate::types::*;
+
+/// A request for one of the tasks a JSONL batch can run, tagged by task name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "task", content = "request", rename_all = "snake_case")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum TaskRequest {
+    Analyze(AnalyzeRequest),
+    Caption(CaptionRequest),
+    Ocr(OcrRequest),
+    Detect(DetectRequest),
+    Question(QuestionRequest),
+}
+
+/// The response to a [`TaskRequest`], tagged by task name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "task", content = "response", rename_all = "snake_case")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum TaskResponse {
+    Analyze(PointingResponse),
+    Caption(PointingResponse),
+    Ocr(TextResponse),
+    Detect(PointingResponse),
+    Question(PointingResponse),
+}
+
+impl BatchTask for TaskRequest {
+    type Output = TaskResponse;
+
+    async fn run(self, client: &PerceptronClient) -> Result<TaskResponse, PerceptronError> {
+        Ok(match self {
+            Self::Analyze(request) => TaskResponse::Analyze(client.analyze(request).await?),
+            Self::Caption(request) => TaskResponse::Caption(client.caption(request).await?),
+            Self::Ocr(request) => TaskResponse::Ocr(client.ocr(request).await?),
+            Self::Detect(request) => TaskResponse::Detect(client.detect(request).await?),
+            Self::Question(request) => TaskResponse::Question(client.question(request).await?),
+        })
+    }
+}
+
+/// A line of a JSONL batch input file:
+///
+/// ```json
+/// {"id": "img-001", "task": "ocr", "request": {"model": "isaac-0.1", "mode": "plain", "image": {"type": "url", "src": "..."}}}
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct BatchLine {
+    /// Key for the line's result. Defaults to the 1-based line number. Should be unique.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// The request to run.
+    #[serde(flatten)]
+    pub request: TaskRequest,
+}
+
+/// A line of a JSONL batch output file, with either a response or an error:
+///
+/// ```json
+/// {"id": "img-001", "task": "detect", "response": {"content": ..., "pointing": ...}}
+/// {"id": "img-002", "error": "API error (500): ..."}
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct BatchRecord {
+    /// Id of the input line.
+    pub id: String,
+    /// What running the line produced.
+    #[serde(flatten)]
+    pub outcome: BatchOutcome,
+}
+
+/// Result of one line of a JSONL batch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum BatchOutcome {
+    /// The request succeeded.
+    Response(TaskResponse),
+    /// The line could not be parsed or the request failed.
+    Error { error: String },
+}
+
+/// Counts for a finished JSONL batch, including lines completed by earlier runs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct BatchSummary {
+    /// Non-empty lines in the input file.
+    pub total: usize,
+    /// Lines with a response in the output file.
+    pub succeeded: usize,
+    /// Lines with an error in the output file.
+    pub failed: usize,
+    /// Lines skipped because an earlier run already recorded them.
+    pub resumed: usize,
+    /// Lines not recorded because the batch was cancelled.
+    pub pending: usize,
+}
+
+/// Runs a JSONL file of [`BatchLine`]s and writes a [`BatchRecord`] per line.
+/// Created with [`PerceptronClient::run_jsonl`].
+///
+/// Records are appended as requests finish, so an interrupted run can be resumed by running
+/// the same input and output again: lines already in the output are skipped. Cancelled
+/// requests are not recorded and run again on resume.
+///
+/// A line recorded with an error counts as done and is not run again, unless
+/// [`Self::retry_failed`] is set.
+pub struct JsonlBatch {
+    client: PerceptronClient,
+    input: PathBuf,
+    output: PathBuf,
+    summary: Option<PathBuf>,
+    concurrency: Option<usize>,
+    progress: Option<Box<dyn FnMut(BatchProgress) + Send>>,
+    cancel: CancelToken,
+    retry_failed: bool,
+}
+
+impl JsonlBatch {
+    pub(crate) fn new(client: PerceptronClient, input: PathBuf, output: PathBuf) -> Self {
+        Self {
+            client,
+            input,
+            output,
+            summary: None,
+            concurrency: None,
+            progress: None,
+            cancel: CancelToken::new(),
+            retry_failed: false,
+        }
+    }
+
+    /// Maximum number of requests in flight at once.
+    pub fn concurrency(mut self, limit: usize) -> Self {
+        self.concurrency = Some(limit);
+        self
+    }
+
+    /// Call `callback` each time a request finishes. Counts cover the lines run this time.
+    pub fn on_progress(mut self, callback: impl FnMut(BatchProgress) + Send + 'static) -> Self {
+        self.progress = Some(Box::new(callback));
+        self
+    }
+
+    /// Stop the batch when `token` is cancelled.
+    pub fn cancel_token(mut self, token: CancelToken) -> Self {
+        self.cancel = token;
+        self
+    }
+
+    /// Run lines again whose earlier record is an error. The new record is appended after
+    /// the old one, and the last record for an id is the one that counts.
+    pub fn retry_failed(mut self, retry: bool) -> Self {
+        self.retry_failed = retry;
+        self
+    }
+
+    /// Where to write the [`BatchSummary`] as JSON. Defaults to the output path with a
+    /// `.summary.json` extension.
+    pub fn summary_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.summary = Some(path.into());
+        self
+    }
+
+    /// Run the lines not yet in the output file and write the summary.
+    pub async fn run(self) -> Result<BatchSummary, PerceptronError> {
+        let input = fs::read_to_string(&self.input).await?;
+        let recorded = resume(&self.output).await?;
+        let file = OpenOptions::new().create(true).append(true).open(&self.output).await?;
+        // A write error stops this batch only, not others sharing the caller's token.
+        let cancel = self.cancel.child();
+        let (records, received) = mpsc::unbounded_channel();
+        let writer = tokio::spawn(write_records(BufWriter::new(file), received, cancel.clone()));
+        let mut summary = BatchSummary::default();
+        let mut ids = Vec::new();
+        let mut requests = Vec::new();
+
+        for (number, line) in input.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            summary.total += 1;
+            let parsed = serde_json::from_str::<BatchLine>(line);
+            let id = match &parsed {
+                Ok(line) => line.id.clone(),
+                Err(_) => line_id(line),
+            }
+            .unwrap_or_else(|| (number + 1).to_string());
+            if let Some(&succeeded) = recorded.get(&id)
+                && (succeeded || !self.retry_failed)
+            {
+                summary.resumed += 1;
+                count(&mut summary, succeeded);
+                continue;
+            }
+            match parsed {
+                Ok(line) => {
+                    ids.push(id);
+                    requests.push(line.request);
+                }
+                Err(e) => {
+                    let outcome = BatchOutcome::Error {
+                        error: format!("Invalid batch line: {e}"),
+                    };
+                    // A send only fails once the writer stopped on an error, returned below.
+                    let _ = records.send(BatchRecord { id, outcome });
+                    count(&mut summary, false);
+                }
+            }
+        }
+
+        let mut batch = self.client.batch(requests).cancel_token(cancel);
+        if let Some(limit) = self.concurrency {
+            batch = batch.concurrency(limit);
+        }
+        if let Some(progress) = self.progress {
+            batch = batch.on_progress(progress);
+        }
+        batch
+            .for_each(|index, result| {
+                count(&mut summary, result.is_ok());
+                let outcome = match result {
+                    Ok(response) => BatchOutcome::Response(response),
+                    Err(e) => BatchOutcome::Error { error: e.to_string() },
+                };
+                let _ = records.send(BatchRecord {
+                    id: ids[index].clone(),
+                    outcome,
+                });
+            })
+            .await;
+        drop(records);
+        writer
+            .await
+            .map_err(|e| PerceptronError::RequestFailed(e.to_string()))??;
+
+        summary.pending = summary.total - summary.succeeded - summary.failed;
+        let summary_path = self
+            .summary
+            .unwrap_or_else(|| self.output.with_extension("summary.json"));
+        let json = serde_json::to_string_pretty(&summary).map_err(|e| PerceptronError::ParseFailed(e.to_string()))?;
+        fs::write(summary_path, json).await?;
+        Ok(summary)
+    }
+}
+
+fn count(summary: &mut BatchSummary, succeeded: bool) {
+    if succeeded {
+        summary.succeeded += 1;
+    } else {
+        summary.failed += 1;
+    }
+}
+
+/// The `id` of a line that is valid JSON but not a valid [`BatchLine`].
+fn line_id(line: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(line).ok()?;
+    value.get("id")?.as_str().map(str::to_string)
+}
+
+/// Read the ids already recorded in `output`, and whether the last record of each succeeded.
+///
+/// A trailing line cut short by a crash is removed so new records start on a fresh line.
+async fn resume(output: &Path) -> Result<HashMap<String, bool>, PerceptronError> {
+    let contents = match fs::read_to_string(output).await {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(HashMap::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let complete = contents.rfind('\n').map_or(0, |end| end + 1);
+    if complete < contents.len() {
+        OpenOptions::new()
+            .write(true)
+            .open(output)
+            .await?
+            .set_len(complete as u64)
+            .await?;
+    }
+    Ok(contents[..complete]
+        .lines()
+        .filter_map(|line| {
+            let value: serde_json::Value = serde_json::from_str(line).ok()?;
+            let id = value.get("id")?.as_str()?.to_string();
+            Some((id, value.get("error").is_none()))
+        })
+        .collect())
+}
+
+/// Append each record received to the output, one JSON object per line.
+///
+/// The output is flushed whenever no more records are waiting, so finished lines survive an
+/// interruption. A write error cancels `cancel` to stop the batch.
+async fn write_records(
+    mut writer: BufWriter<File>,
+    mut records: mpsc::UnboundedReceiver<BatchRecord>,
+    cancel: CancelToken,
+) -> Result<(), PerceptronError> {
+    let result = async {
+        while let Some(record) = records.recv().await {
+            let mut line = serde_json::to_vec(&record).map_err(|e| PerceptronError::ParseFailed(e.to_string()))?;
+            line.push(b'\n');
+            writer.write_all(&line).await?;
+            if records.is_empty() {
+                writer.flush().await?;
+            }
+        }
+        Ok(())
+    }
+    .await;
+    if result.is_err() {
+        cancel.cancel();
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::media::Image;
+
+    #[test]
+    fn line_format() {
+        let line: BatchLine = serde_json::from_str(
+            r#"{"id": "a", "task": "ocr", "request": {"model": "isaac", "mode": "plain", "image": {"type": "url", "src": "https://example.com/a.jpg"}}}"#,
+        )
+        .expect("line");
+        assert_eq!(line.id.as_deref(), Some("a"));
+        assert_eq!(
+            line.request,
+            TaskRequest::Ocr(OcrRequest::new("isaac", Image::url("https://example.com/a.jpg")))
+        );
+
+        let record = BatchRecord {
+            id: "a".to_string(),
+            outcome: BatchOutcome::Response(TaskResponse::Ocr(TextResponse {
+                content: Some("text".to_string()),
+                reasoning: None,
+                cached: false,
+            })),
+        };
+        let json = serde_json::to_string(&record).expect("serialize");
+        assert_eq!(
+            json,
+            r#"{"id":"a","task":"ocr","response":{"content":"text","reasoning":null}}"#
+        );
+        assert_eq!(serde_json::from_str::<BatchRecord>(&json).expect("record"), record);
+
+        let error = r#"{"id":"b","error":"boom"}"#;
+        let record: BatchRecord = serde_json::from_str(error).expect("error record");
+        assert_eq!(
+            record.outcome,
+            BatchOutcome::Error {
+                error: "boom".to_string()
+            }
+        );
+    }
+}
@@ -0,0 +1,358 @@
+use std::fmt::Write;
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::parsing;
+use crate::pointing::BoundingBox;
+use crate::types::OutputFormat;
+
+static BLOCK_SEPARATOR_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\n[ \t]*\n").expect("regex creation should never fail here"));
+
+/// A block, line or word of text located on the page.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct LayoutElement {
+    /// Transcribed text.
+    pub text: String,
+    /// Region covered by the text, on the model's 0–1000 grid. The box carries no mention or
+    /// timestamp.
+    pub bbox: BoundingBox,
+    /// Position in reading order among elements of the same level, starting at 0.
+    pub order: usize,
+    /// Index of the enclosing element: the block for a line, the line for a word.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<usize>,
+    /// Whether the box was estimated rather than located by the model. Only word boxes
+    /// split out of a multi-word line are estimated.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub estimated: bool,
+}
+
+/// Text layout extracted by layout OCR, as flat lists of blocks, lines and words.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct OcrLayout {
+    /// Text blocks (paragraphs, columns, captions) in reading order.
+    pub blocks: Vec<LayoutElement>,
+    /// Text lines in reading order.
+    pub lines: Vec<LayoutElement>,
+    /// Words in reading order. The box of a single-word line is the line box; other word
+    /// boxes are estimated by splitting the line box in proportion to character counts.
+    pub words: Vec<LayoutElement>,
+}
+
+/// Response for layout OCR.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct OcrLayoutResponse {
+    /// The main response content from the model.
+    pub content: Option<String>,
+    /// Chain-of-thought reasoning content (if reasoning was enabled).
+    pub reasoning: Option<String>,
+    /// Whether the response was served from the client's response cache.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cached: bool,
+    /// Extracted text layout.
+    pub layout: Option<OcrLayout>,
+}
+
+fn region(x1: u32, y1: u32, x2: u32, y2: u32) -> BoundingBox {
+    BoundingBox {
+        x1,
+        y1,
+        x2,
+        y2,
+        mention: None,
+        timestamp: None,
+    }
+}
+
+fn union(boxes: &[&BoundingBox]) -> BoundingBox {
+    region(
+        boxes.iter().map(|b| b.x1).min().unwrap_or_default(),
+        boxes.iter().map(|b| b.y1).min().unwrap_or_default(),
+        boxes.iter().map(|b| b.x2).max().unwrap_or_default(),
+        boxes.iter().map(|b| b.y2).max().unwrap_or_default(),
+    )
+}
+
+/// Split a line box into word boxes, sized by each word's share of the line's characters.
+fn split_words(line: &BoundingBox, text: &str) -> Vec<(String, BoundingBox)> {
+    let total = text.chars().count().max(1) as f32;
+    let width = line.x2.saturating_sub(line.x1) as f32;
+    let x_at = |chars: usize| line.x1 + (width * chars as f32 / total).round() as u32;
+
+    let mut words = Vec::new();
+    let mut offset = 0;
+    for word in text.split(' ') {
+        let len = word.chars().count();
+        if !word.is_empty() {
+            words.push((
+                word.to_string(),
+                region(x_at(offset), line.y1, x_at(offset + len), line.y2),
+            ));
+        }
+        offset += len + 1;
+    }
+    words
+}
+
+/// Parse layout OCR output: one `<point_box>` per line whose mention is the line text,
+/// with blank lines separating blocks.
+pub(crate) fn extract(text: &str) -> Option<OcrLayout> {
+    let mut layout = OcrLayout::default();
+    for chunk in BLOCK_SEPARATOR_REGEX.split(text) {
+        let Some(pointing) = parsing::extract(chunk, Some(&OutputFormat::Box)) else {
+            continue;
+        };
+        let block = layout.blocks.len();
+        let first_line = layout.lines.len();
+        for b in &pointing.boxes {
+            let text = b.mention.as_deref().unwrap_or_default();
+            let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+            let bbox = region(b.x1, b.y1, b.x2, b.y2);
+            let line = layout.lines.len();
+            let words = split_words(&bbox, &text);
+            let estimated = words.len() > 1;
+            for (word, word_box) in words {
+                layout.words.push(LayoutElement {
+                    text: word,
+                    bbox: word_box,
+                    order: layout.words.len(),
+                    parent: Some(line),
+                    estimated,
+                });
+            }
+            layout.lines.push(LayoutElement {
+                text,
+                bbox,
+                order: line,
+                parent: Some(block),
+                estimated: false,
+            });
+        }
+        let lines = &layout.lines[first_line..];
+        layout.blocks.push(LayoutElement {
+            text: lines.iter().map(|l| l.text.as_str()).collect::<Vec<_>>().join("\n"),
+            bbox: union(&lines.iter().map(|l| &l.bbox).collect::<Vec<_>>()),
+            order: block,
+            parent: None,
+            estimated: false,
+        });
+    }
+    (!layout.blocks.is_empty()).then_some(layout)
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Box coordinates from the model run from 0 to this value on both axes, whatever the image size.
+const MODEL_GRID: u64 = 1_000;
+
+/// Scale `bbox` from the model's grid to an image of `width` x `height` pixels.
+fn to_pixels(bbox: &BoundingBox, width: u32, height: u32) -> BoundingBox {
+    let scale = |value: u32, size: u32| (u64::from(value.min(MODEL_GRID as u32)) * u64::from(size) / MODEL_GRID) as u32;
+    region(
+        scale(bbox.x1, width),
+        scale(bbox.y1, height),
+        scale(bbox.x2, width),
+        scale(bbox.y2, height),
+    )
+}
+
+impl OcrLayout {
+    /// Words of line `line` whose boxes were located by the model, with their indices.
+    fn exact_words(&self, line: usize) -> impl Iterator<Item = (usize, &LayoutElement)> {
+        self.words
+            .iter()
+            .enumerate()
+            .filter(move |(_, w)| w.parent == Some(line) && !w.estimated)
+    }
+
+    /// Export as an hOCR (HTML) document for an image of `width` x `height` pixels.
+    ///
+    /// Boxes are scaled from the model's 0–1000 grid to pixels of the image, which is the page.
+    /// Estimated word boxes are left out: a line without exact word boxes holds its text directly.
+    pub fn to_hocr(&self, width: u32, height: u32) -> String {
+        let title = |b: &BoundingBox| {
+            let b = to_pixels(b, width, height);
+            format!("bbox {} {} {} {}", b.x1, b.y1, b.x2, b.y2)
+        };
+        let mut out = String::from(
+            "<!DOCTYPE html>\n<html xmlns=\"http://www.w3.org/1999/xhtml\">\n<head>\n\
+             <meta http-equiv=\"Content-Type\" content=\"text/html; charset=utf-8\" />\n\
+             <meta name=\"ocr-system\" content=\"perceptron-ai\" />\n\
+             <meta name=\"ocr-capabilities\" content=\"ocr_page ocr_carea ocr_par ocr_line ocrx_word\" />\n\
+             </head>\n<body>\n",
+        );
+        let _ = writeln!(
+            out,
+            "<div class=\"ocr_page\" id=\"page_1\" title=\"bbox 0 0 {width} {height}\">"
+        );
+        for (b, block) in self.blocks.iter().enumerate() {
+            let _ = writeln!(
+                out,
+                "<div class=\"ocr_carea\" id=\"block_{}\" title=\"{}\">\n<p class=\"ocr_par\" id=\"par_{}\" title=\"{}\">",
+                b + 1,
+                title(&block.bbox),
+                b + 1,
+                title(&block.bbox)
+            );
+            for (l, line) in self.lines.iter().enumerate().filter(|(_, l)| l.parent == Some(b)) {
+                let _ = write!(
+                    out,
+                    "<span class=\"ocr_line\" id=\"line_{}\" title=\"{}\">",
+                    l + 1,
+                    title(&line.bbox)
+                );
+                let words: Vec<String> = self
+                    .exact_words(l)
+                    .map(|(w, word)| {
+                        format!(
+                            "<span class=\"ocrx_word\" id=\"word_{}\" title=\"{}\">{}</span>",
+                            w + 1,
+                            title(&word.bbox),
+                            escape_xml(&word.text)
+                        )
+                    })
+                    .collect();
+                if words.is_empty() {
+                    let _ = writeln!(out, "{}</span>", escape_xml(&line.text));
+                } else {
+                    let _ = writeln!(out, "{}</span>", words.join(" "));
+                }
+            }
+            out.push_str("</p>\n</div>\n");
+        }
+        out.push_str("</div>\n</body>\n</html>\n");
+        out
+    }
+
+    /// Export as an ALTO v4 XML document for an image of `width` x `height` pixels.
+    ///
+    /// Boxes are scaled from the model's 0–1000 grid to pixels of the image, which is the page.
+    /// Estimated word boxes are left out: a line without exact word boxes holds a single
+    /// `String` with the line text and box.
+    pub fn to_alto(&self, width: u32, height: u32) -> String {
+        let pos = |b: &BoundingBox| {
+            let b = to_pixels(b, width, height);
+            format!(
+                "HPOS=\"{}\" VPOS=\"{}\" WIDTH=\"{}\" HEIGHT=\"{}\"",
+                b.x1,
+                b.y1,
+                b.x2.saturating_sub(b.x1),
+                b.y2.saturating_sub(b.y1)
+            )
+        };
+        let print_space = union(&self.blocks.iter().map(|b| &b.bbox).collect::<Vec<_>>());
+        let mut out = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <alto xmlns=\"http://www.loc.gov/standards/alto/ns-v4#\">\n\
+             <Description>\n<MeasurementUnit>pixel</MeasurementUnit>\n</Description>\n<Layout>\n",
+        );
+        let _ = writeln!(
+            out,
+            "<Page ID=\"page_1\" PHYSICAL_IMG_NR=\"1\" WIDTH=\"{width}\" HEIGHT=\"{height}\">\n<PrintSpace {}>",
+            pos(&print_space)
+        );
+        for (b, block) in self.blocks.iter().enumerate() {
+            let _ = writeln!(out, "<TextBlock ID=\"block_{}\" {}>", b + 1, pos(&block.bbox));
+            for (l, line) in self.lines.iter().enumerate().filter(|(_, l)| l.parent == Some(b)) {
+                let _ = writeln!(out, "<TextLine ID=\"line_{}\" {}>", l + 1, pos(&line.bbox));
+                let mut words: Vec<String> = self
+                    .exact_words(l)
+                    .map(|(w, word)| {
+                        format!(
+                            "<String ID=\"word_{}\" CONTENT=\"{}\" {}/>",
+                            w + 1,
+                            escape_xml(&word.text),
+                            pos(&word.bbox)
+                        )
+                    })
+                    .collect();
+                if words.is_empty() {
+                    words.push(format!(
+                        "<String ID=\"line_{}_text\" CONTENT=\"{}\" {}/>",
+                        l + 1,
+                        escape_xml(&line.text),
+                        pos(&line.bbox)
+                    ));
+                }
+                let _ = writeln!(out, "{}", words.join("<SP/>"));
+                out.push_str("</TextLine>\n");
+            }
+            out.push_str("</TextBlock>\n");
+        }
+        out.push_str("</PrintSpace>\n</Page>\n</Layout>\n</alto>\n");
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"<point_box mention="INVOICE #42"> (100,50) (320,80) </point_box>
+
+<point_box mention="Total: 9.99"> (100,200) (210,220) </point_box>
+<point_box mention="Say &quot;hi&quot;"> (100,230) (180,250) </point_box>
+<point_box mention="Thanks!"> (100,260) (160,280) </point_box>"#;
+
+    #[test]
+    fn extracts_blocks_lines_and_words() {
+        let layout = extract(SAMPLE).expect("layout");
+        assert_eq!(layout.blocks.len(), 2);
+        assert_eq!(layout.lines.len(), 4);
+        assert_eq!(layout.words.len(), 7);
+
+        assert_eq!(layout.blocks[1].text, "Total: 9.99\nSay \"hi\"\nThanks!");
+        assert_eq!(layout.blocks[1].bbox, region(100, 200, 210, 280));
+        assert_eq!(layout.lines[2].parent, Some(1));
+        assert_eq!(layout.lines[2].order, 2);
+
+        // "INVOICE #42" is 11 characters over 220 units: "INVOICE" covers 7 of them.
+        assert_eq!(layout.words[0].text, "INVOICE");
+        assert_eq!(layout.words[0].bbox, region(100, 50, 240, 80));
+        assert_eq!(layout.words[1].bbox, region(260, 50, 320, 80));
+        assert!(layout.words[0].estimated);
+        assert_eq!(layout.words[5].parent, Some(2));
+
+        // A single-word line's box is exact.
+        assert_eq!(layout.words[6].bbox, region(100, 260, 160, 280));
+        assert!(!layout.words[6].estimated);
+    }
+
+    #[test]
+    fn exports_hocr_and_alto() {
+        let layout = extract(SAMPLE).expect("layout");
+        // x is scaled by 2 and y by 0.5 from the 1000x1000 model grid.
+        let hocr = layout.to_hocr(2000, 500);
+        assert!(hocr.contains(r#"<div class="ocr_page" id="page_1" title="bbox 0 0 2000 500">"#));
+        assert!(
+            hocr.contains(
+                r#"<span class="ocr_line" id="line_3" title="bbox 200 115 360 125">Say &quot;hi&quot;</span>"#
+            )
+        );
+        assert!(hocr.contains(r#"<span class="ocrx_word" id="word_7" title="bbox 200 130 320 140">Thanks!</span>"#));
+        assert!(!hocr.contains(r#"id="word_1""#));
+
+        let alto = layout.to_alto(2000, 500);
+        assert!(alto.contains(r#"<Page ID="page_1" PHYSICAL_IMG_NR="1" WIDTH="2000" HEIGHT="500">"#));
+        assert!(alto.contains(r#"<PrintSpace HPOS="200" VPOS="25" WIDTH="440" HEIGHT="115">"#));
+        assert!(alto.contains(r#"<TextBlock ID="block_2" HPOS="200" VPOS="100" WIDTH="220" HEIGHT="40">"#));
+        assert!(alto.contains(
+            r#"<String ID="line_1_text" CONTENT="INVOICE #42" HPOS="200" VPOS="25" WIDTH="440" HEIGHT="15"/>"#
+        ));
+        assert!(
+            alto.contains(r#"<String ID="word_7" CONTENT="Thanks!" HPOS="200" VPOS="130" WIDTH="120" HEIGHT="10"/>"#)
+        );
+        assert!(!alto.contains(r#"ID="word_1""#));
+    }
+}
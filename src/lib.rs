@@ -1,20 +1,65 @@
 mod api;
+mod batch;
+mod cache;
+mod chapters;
+mod classification;
 mod client;
+mod comparison;
+mod counting;
+mod document;
+mod ensemble;
 mod error;
+mod events;
+mod jsonl;
+mod layout;
 mod media;
 mod models;
 mod parsing;
 mod pointing;
 mod prompting;
+mod rate_limit;
+mod tagging;
+mod timeline;
+mod tracking;
 mod types;
 
-pub use client::{Perceptron, PerceptronClient};
+pub use api::chat_completions::{
+    ChatCompletionAssistantMessage, ChatCompletionAssistantMessageContent, ChatCompletionContentPart,
+    ChatCompletionContentPartImage, ChatCompletionContentPartText, ChatCompletionContentPartVideo,
+    ChatCompletionMessage, ChatCompletionSystemMessage, ChatCompletionSystemMessageContent, ChatCompletionUserMessage,
+    ChatCompletionUserMessageContent, CreateChatCompletionRequest, ImageUrl, VideoUrl,
+};
+pub use batch::{Batch, BatchProgress, BatchTask, CancelToken, OcrLayoutTask};
+pub use cache::{Cache, DiskCache, MemoryCache};
+pub use chapters::Chapter;
+pub use classification::{LabelScore, UNKNOWN_LABEL};
+pub use client::{IntoWireRequest, Perceptron, PerceptronClient};
+pub use comparison::{Change, ChangeKind, ComparedImage};
+pub use counting::ClassCount;
+pub use document::{Cell, DocumentField, FieldValue, Table};
+pub use ensemble::{Ensemble, EnsembleStats};
 pub use error::ApiErrorDetail;
 pub use error::PerceptronError;
+pub use events::{EventHit, KeyFrame, KeyFrameFormat};
+pub use jsonl::{BatchLine, BatchOutcome, BatchRecord, BatchSummary, JsonlBatch, TaskRequest, TaskResponse};
+pub use layout::{LayoutElement, OcrLayout, OcrLayoutResponse};
 pub use media::{Image, ImageFormat, Media, Modality, Video, VideoFormat};
 pub use models::{Model, SamplingParameter};
-pub use pointing::{BoundingBox, Clip, ClipTimestamp, Point, Pointing, Polygon};
+pub use pointing::{BoundingBox, Clip, ClipTimestamp, Point, Pointing, Polygon, Region};
+pub use prompting::{
+    CaptionPromptTemplate, ClassifyPromptTemplate, ComparePromptTemplate, CountPromptTemplate, DetectPromptTemplate,
+    DocumentPromptTemplate, GroundPromptTemplate, LanguagePromptTemplate, LocatePromptTemplate, ModalityPrompt,
+    OcrPromptTemplate, PromptProfile, PromptRegistry, QuestionPromptTemplate, TagPromptTemplate,
+    VideoSummaryPromptTemplate,
+};
+pub use rate_limit::RateLimiter;
+pub use tagging::{Tag, Taxonomy, TaxonomyNode};
+pub use timeline::{Timeline, TimelineEntry, frame_index};
+pub use tracking::{Observation, Track, TrackOptions, to_mot_csv};
 pub use types::{
-    AnalyzeRequest, CaptionRequest, CaptionStyle, DetectRequest, OcrMode, OcrRequest, OutputFormat, PointingResponse,
-    QuestionRequest, TextResponse,
+    AnalyzeRequest, CaptionLength, CaptionRequest, CaptionStyle, ChatContentPart, ChatMessage, ChatRequest,
+    ChatResponse, ClassifyRequest, ClassifyResponse, CompareRequest, CompareResponse, CountRequest, CountResponse,
+    DetectRequest, DocumentRequest, DocumentResponse, FewShotAnswer, FewShotExample, GroundRequest, GroundResponse,
+    LengthPolicy, LengthUnit, LocateRequest, LocateResponse, OcrMode, OcrRequest, OutputFormat, PointingResponse,
+    QuestionRequest, TagRequest, TagResponse, TextResponse, Usage, VideoSummaryRequest, VideoSummaryResponse,
 };
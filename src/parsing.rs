@@ -24,8 +24,24 @@ static MENTION_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"mention="
 
 static T_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"\bt=(?:"([^"]*)"|(\S+))"#).expect(REGEX_EXPECT));
 
+static ANY_TAG_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)<(point_box|point|polygon)\b[^>]*>[\s\S]*?</(?:point_box|point|polygon)>|</?collection\b[^>]*>|<clip\b[^>]*/>")
+        .expect(REGEX_EXPECT)
+});
+
+/// Remove annotation tags from model output, leaving only the prose with whitespace collapsed.
+pub(crate) fn strip_tags(text: &str) -> String {
+    ANY_TAG_REGEX
+        .replace_all(text, " ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 fn parse_mention(attr_str: &str) -> Option<String> {
-    MENTION_REGEX.captures(attr_str).map(|c| c[1].to_string())
+    MENTION_REGEX
+        .captures(attr_str)
+        .map(|c| c[1].replace("&quot;", "\"").replace("&amp;", "&"))
 }
 
 fn parse_t(attr_str: &str) -> Option<f32> {
@@ -142,12 +158,72 @@ fn extract_clips(text: &str) -> Vec<Clip> {
     results
 }
 
+/// Format the `mention` and `t` attributes of a tag, each preceded by a space.
+fn format_attrs(mention: Option<&str>, timestamp: Option<&str>) -> String {
+    let mut attrs = String::new();
+    if let Some(mention) = mention {
+        let mention = mention.replace('&', "&amp;").replace('"', "&quot;");
+        attrs.push_str(&format!(r#" mention="{mention}""#));
+    }
+    if let Some(t) = timestamp {
+        attrs.push_str(&format!(r#" t="{t}""#));
+    }
+    attrs
+}
+
+fn format_coords(coords: &[(u32, u32)]) -> String {
+    coords
+        .iter()
+        .map(|(x, y)| format!("({x},{y})"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl Pointing {
+    /// Serialize the annotations in the model's tag syntax, one tag per line.
+    /// This is the inverse of the extraction applied to model responses.
+    pub fn to_tags(&self) -> String {
+        let mut tags = Vec::new();
+        for p in &self.points {
+            let t = p.timestamp.map(|t| t.to_string());
+            tags.push(format!(
+                "<point{}> {} </point>",
+                format_attrs(p.mention.as_deref(), t.as_deref()),
+                format_coords(&[(p.x, p.y)])
+            ));
+        }
+        for b in &self.boxes {
+            let t = b.timestamp.map(|t| t.to_string());
+            tags.push(format!(
+                "<point_box{}> {} </point_box>",
+                format_attrs(b.mention.as_deref(), t.as_deref()),
+                format_coords(&[(b.x1, b.y1), (b.x2, b.y2)])
+            ));
+        }
+        for p in &self.polygons {
+            let t = p.timestamp.map(|t| t.to_string());
+            tags.push(format!(
+                "<polygon{}> {} </polygon>",
+                format_attrs(p.mention.as_deref(), t.as_deref()),
+                format_coords(&p.hull)
+            ));
+        }
+        for c in &self.clips {
+            let t = match c.timestamp {
+                ClipTimestamp::Moment(t) => t.to_string(),
+                ClipTimestamp::Range { start, end } => format!("{start} {end}"),
+            };
+            tags.push(format!("<clip{}/>", format_attrs(c.mention.as_deref(), Some(&t))));
+        }
+        tags.join("\n")
+    }
+}
+
+/// Builds an annotation from parsed coordinates, mention, and timestamp.
+type ParseFn<T> = fn(&[(u32, u32)], Option<String>, Option<f32>) -> Option<T>;
+
 /// Extract items of the target tag type, flattening collections.
-fn extract_items<T>(
-    text: &str,
-    target_regex: &Regex,
-    parse_fn: fn(&[(u32, u32)], Option<String>, Option<f32>) -> Option<T>,
-) -> Vec<T> {
+fn extract_items<T>(text: &str, target_regex: &Regex, parse_fn: ParseFn<T>) -> Vec<T> {
     let mut results = Vec::new();
 
     // Process collections and strip them from the text
@@ -295,6 +371,81 @@ mod tests {
         assert_eq!(clips[3].timestamp, ClipTimestamp::Range { start: 30.0, end: 45.0 });
     }
 
+    #[test]
+    fn to_tags_round_trips() {
+        let pointing = Pointing {
+            points: vec![Point {
+                x: 1,
+                y: 2,
+                mention: Some("nose".to_string()),
+                timestamp: Some(0.5),
+            }],
+            boxes: vec![BoundingBox {
+                x1: 10,
+                y1: 20,
+                x2: 30,
+                y2: 40,
+                mention: Some("cat".to_string()),
+                timestamp: None,
+            }],
+            polygons: vec![Polygon {
+                hull: vec![(0, 0), (10, 0), (10, 10)],
+                mention: None,
+                timestamp: None,
+            }],
+            clips: vec![Clip {
+                mention: Some("goal".to_string()),
+                timestamp: ClipTimestamp::Range { start: 1.5, end: 3.0 },
+            }],
+        };
+        let tags = pointing.to_tags();
+        assert_eq!(
+            tags.lines().nth(1),
+            Some(r#"<point_box mention="cat"> (10,20) (30,40) </point_box>"#)
+        );
+        for format in [
+            OutputFormat::Point,
+            OutputFormat::Box,
+            OutputFormat::Polygon,
+            OutputFormat::Clip,
+        ] {
+            let extracted = extract(&tags, Some(&format)).expect("expected Some(Pointing)");
+            match format {
+                OutputFormat::Point => assert_eq!(extracted.points, pointing.points),
+                OutputFormat::Box => assert_eq!(extracted.boxes, pointing.boxes),
+                OutputFormat::Polygon => assert_eq!(extracted.polygons, pointing.polygons),
+                OutputFormat::Clip => assert_eq!(extracted.clips, pointing.clips),
+            }
+        }
+    }
+
+    #[test]
+    fn to_tags_escapes_mention_quotes() {
+        let pointing = Pointing {
+            points: vec![Point {
+                x: 1,
+                y: 2,
+                mention: Some(r#"the "red" R&D sign"#.to_string()),
+                timestamp: None,
+            }],
+            ..Default::default()
+        };
+        let tags = pointing.to_tags();
+        assert_eq!(
+            tags,
+            r#"<point mention="the &quot;red&quot; R&amp;D sign"> (1,2) </point>"#
+        );
+        let extracted = extract(&tags, Some(&OutputFormat::Point)).expect("expected Some(Pointing)");
+        assert_eq!(extracted.points, pointing.points);
+    }
+
+    #[test]
+    fn strip_tags_leaves_prose() {
+        let text = r#"A cat <point_box mention="cat"> (10,20) (30,40) </point_box> sits
+            <collection mention="bird"><point> (1,2) </point></collection> near a window <clip t=1/>."#;
+        assert_eq!(strip_tags(text), "A cat sits near a window .");
+    }
+
     #[test]
     fn extract_multiple_points() {
         let text = r#"
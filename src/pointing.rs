@@ -90,3 +90,14 @@ pub struct Pointing {
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub clips: Vec<Clip>,
 }
+
+/// A single image region, as a box or a polygon.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Region {
+    /// A bounding box region.
+    Box(BoundingBox),
+    /// A polygon region.
+    Polygon(Polygon),
+}
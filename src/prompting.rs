@@ -1,21 +1,42 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+use crate::events::KeyFrameFormat;
 use crate::media::Media;
-use crate::types::{CaptionStyle, OcrMode, OutputFormat};
+use crate::types::{CaptionLength, CaptionStyle, LengthUnit, OcrMode, OutputFormat};
 
 /// A prompt whose text varies by media modality.
 #[derive(Debug, Clone, PartialEq)]
 pub struct ModalityPrompt {
     /// Text used when the request media is an image.
-    image: &'static str,
+    pub image: Cow<'static, str>,
     /// Text used when the request media is a video.
-    video: &'static str,
+    pub video: Cow<'static, str>,
 }
 
 impl ModalityPrompt {
+    /// Create a prompt with separate image and video text.
+    pub fn new(image: impl Into<Cow<'static, str>>, video: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            image: image.into(),
+            video: video.into(),
+        }
+    }
+
+    /// Create a prompt that uses the same text for every modality.
+    pub fn uniform(text: impl Into<Cow<'static, str>>) -> Self {
+        let text = text.into();
+        Self {
+            image: text.clone(),
+            video: text,
+        }
+    }
+
     /// Return the prompt text for the given media.
-    pub fn get(&self, media: &Media) -> &'static str {
+    pub fn get(&self, media: &Media) -> &str {
         match media {
-            Media::Image(_) => self.image,
-            Media::Video(_) => self.video,
+            Media::Image(_) => &self.image,
+            Media::Video(_) => &self.video,
         }
     }
 }
@@ -24,15 +45,15 @@ impl ModalityPrompt {
 #[derive(Debug, Clone, PartialEq)]
 pub struct QuestionPromptTemplate {
     /// System instruction for open-ended (text) questions.
-    open_instruction: Option<ModalityPrompt>,
+    pub open_instruction: Option<ModalityPrompt>,
     /// System instruction for grounded (spatial) questions.
-    grounded_instruction: Option<ModalityPrompt>,
+    pub grounded_instruction: Option<ModalityPrompt>,
 }
 
 impl QuestionPromptTemplate {
     /// Resolve the system instruction for the given output format and media.
     /// `None` for `output_format` selects the open (text) instruction.
-    pub fn resolve_system(&self, output_format: Option<&OutputFormat>, media: &Media) -> Option<&'static str> {
+    pub fn resolve_system(&self, output_format: Option<&OutputFormat>, media: &Media) -> Option<&str> {
         let prompt = match output_format {
             None => self.open_instruction.as_ref(),
             Some(_) => self.grounded_instruction.as_ref(),
@@ -45,53 +66,89 @@ impl QuestionPromptTemplate {
 #[derive(Debug, Clone, PartialEq)]
 pub struct CaptionPromptTemplate {
     /// Optional system instruction for the caption endpoint.
-    system: Option<ModalityPrompt>,
+    pub system: Option<ModalityPrompt>,
     /// User text for concise captions.
-    concise: ModalityPrompt,
+    pub concise: ModalityPrompt,
     /// User text for detailed captions.
-    detailed: ModalityPrompt,
+    pub detailed: ModalityPrompt,
+    /// User text for accessibility alt text.
+    pub alt_text: ModalityPrompt,
+    /// User text for product-listing captions.
+    pub product: ModalityPrompt,
+    /// User text for social media captions.
+    pub social: ModalityPrompt,
+    /// User text for dense, per-region captions.
+    pub dense_region: ModalityPrompt,
+    /// Length instruction with a `{max}` placeholder, in words.
+    pub max_words: Cow<'static, str>,
+    /// Length instruction with `{min}` and `{max}` placeholders, in words.
+    pub word_range: Cow<'static, str>,
+    /// Length instruction with a `{max}` placeholder, in characters.
+    pub max_characters: Cow<'static, str>,
+    /// Length instruction with `{min}` and `{max}` placeholders, in characters.
+    pub character_range: Cow<'static, str>,
 }
 
 impl CaptionPromptTemplate {
     /// Resolve the system instruction for the given media, if any.
-    pub fn resolve_system(&self, media: &Media) -> Option<&'static str> {
+    pub fn resolve_system(&self, media: &Media) -> Option<&str> {
         self.system.as_ref().map(|p| p.get(media))
     }
 
     /// Resolve the user text for the given caption style and media.
-    pub fn resolve_user(&self, style: &CaptionStyle, media: &Media) -> &'static str {
+    pub fn resolve_user(&self, style: &CaptionStyle, media: &Media) -> &str {
         match style {
             CaptionStyle::Concise => self.concise.get(media),
             CaptionStyle::Detailed => self.detailed.get(media),
+            CaptionStyle::AltText => self.alt_text.get(media),
+            CaptionStyle::Product => self.product.get(media),
+            CaptionStyle::Social => self.social.get(media),
+            CaptionStyle::DenseRegion => self.dense_region.get(media),
         }
     }
+
+    /// Resolve the length instruction for the given target length.
+    pub fn resolve_length(&self, length: &CaptionLength) -> String {
+        let template = match (length.unit, length.min) {
+            (LengthUnit::Words, 0) => &self.max_words,
+            (LengthUnit::Words, _) => &self.word_range,
+            (LengthUnit::Characters, 0) => &self.max_characters,
+            (LengthUnit::Characters, _) => &self.character_range,
+        };
+        template
+            .replace("{min}", &length.min.to_string())
+            .replace("{max}", &length.max.to_string())
+    }
 }
 
 /// Prompt template for OCR requests.
 #[derive(Debug, Clone, PartialEq)]
 pub struct OcrPromptTemplate {
     /// Optional system instruction for the OCR endpoint.
-    system: Option<&'static str>,
+    pub system: Option<Cow<'static, str>>,
     /// User text for plain mode (None means no user text).
-    plain: Option<&'static str>,
+    pub plain: Option<Cow<'static, str>>,
     /// User text for markdown mode.
-    markdown: &'static str,
+    pub markdown: Cow<'static, str>,
     /// User text for HTML mode.
-    html: &'static str,
+    pub html: Cow<'static, str>,
+    /// User text for layout mode, asking for one `<point_box>` per line.
+    pub layout: Cow<'static, str>,
 }
 
 impl OcrPromptTemplate {
     /// Resolve the system instruction, if any.
-    pub fn resolve_system(&self) -> Option<&'static str> {
-        self.system
+    pub fn resolve_system(&self) -> Option<&str> {
+        self.system.as_deref()
     }
 
     /// Resolve the user text for the given OCR mode, or `None` for plain when omitted.
-    pub fn resolve_user(&self, mode: &OcrMode) -> Option<&'static str> {
+    pub fn resolve_user(&self, mode: &OcrMode) -> Option<&str> {
         match mode {
-            OcrMode::Plain => self.plain,
-            OcrMode::Markdown => Some(self.markdown),
-            OcrMode::Html => Some(self.html),
+            OcrMode::Plain => self.plain.as_deref(),
+            OcrMode::Markdown => Some(&self.markdown),
+            OcrMode::Html => Some(&self.html),
+            OcrMode::Layout => Some(&self.layout),
         }
     }
 }
@@ -100,26 +157,251 @@ impl OcrPromptTemplate {
 #[derive(Debug, Clone, PartialEq)]
 pub struct DetectPromptTemplate {
     /// System text when no categories are specified.
-    general: ModalityPrompt,
+    pub general: ModalityPrompt,
     /// Template with `{categories}` placeholder for category-specific detection.
-    category_template: ModalityPrompt,
+    pub category_template: ModalityPrompt,
+    /// Format of a category with a description, with `{category}` and `{description}` placeholders.
+    pub described_category: Cow<'static, str>,
+    /// Constraint with a `{categories}` placeholder listing categories to leave out.
+    pub exclusions: Cow<'static, str>,
+    /// Constraint with a `{count}` placeholder capping instances per category.
+    pub max_instances: Cow<'static, str>,
+    /// Constraint with a `{size}` placeholder for the smallest object to return.
+    pub min_size: Cow<'static, str>,
 }
 
 impl DetectPromptTemplate {
     /// Resolve the system text for the given categories and media, substituting `{categories}` if provided.
-    pub fn resolve_system(&self, categories: Option<&[String]>, media: &Media) -> String {
+    /// Categories with an entry in `descriptions` are formatted with [`Self::described_category`].
+    pub fn resolve_system(
+        &self,
+        categories: Option<&[String]>,
+        descriptions: &BTreeMap<String, String>,
+        media: &Media,
+    ) -> String {
         match categories {
-            Some(cats) if !cats.is_empty() => self
-                .category_template
-                .get(media)
-                .replace("{categories}", &cats.join(", ")),
+            Some(cats) if !cats.is_empty() => {
+                let cats: Vec<String> = cats
+                    .iter()
+                    .map(|cat| match descriptions.get(cat) {
+                        Some(description) => self
+                            .described_category
+                            .replace("{category}", cat)
+                            .replace("{description}", description),
+                        None => cat.clone(),
+                    })
+                    .collect();
+                self.category_template
+                    .get(media)
+                    .replace("{categories}", &cats.join(", "))
+            }
             _ => self.general.get(media).to_string(),
         }
     }
+
+    /// Resolve the constraint text for exclusions, instance caps and minimum size,
+    /// or `None` when no constraint is set.
+    pub fn resolve_constraints(
+        &self,
+        exclude: &[String],
+        max_instances: Option<u32>,
+        min_size: Option<u32>,
+    ) -> Option<String> {
+        let mut constraints = Vec::new();
+        if !exclude.is_empty() {
+            constraints.push(self.exclusions.replace("{categories}", &exclude.join(", ")));
+        }
+        if let Some(count) = max_instances {
+            constraints.push(self.max_instances.replace("{count}", &count.to_string()));
+        }
+        if let Some(size) = min_size {
+            constraints.push(self.min_size.replace("{size}", &size.to_string()));
+        }
+        (!constraints.is_empty()).then(|| constraints.join(" "))
+    }
+}
+
+/// Prompt template for classify requests.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassifyPromptTemplate {
+    /// User text with a `{labels}` placeholder when exactly one label applies.
+    pub single_label: ModalityPrompt,
+    /// User text with a `{labels}` placeholder when any number of labels may apply.
+    pub multi_label: ModalityPrompt,
+}
+
+impl ClassifyPromptTemplate {
+    /// Resolve the user text for the given candidate labels and media.
+    pub fn resolve_user(&self, labels: &[String], multi_label: bool, media: &Media) -> String {
+        let template = if multi_label {
+            &self.multi_label
+        } else {
+            &self.single_label
+        };
+        template.get(media).replace("{labels}", &labels.join(", "))
+    }
+}
+
+/// Prompt template for count requests.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CountPromptTemplate {
+    /// User text with a `{classes}` placeholder listing the categories to count.
+    pub instruction: ModalityPrompt,
+}
+
+impl CountPromptTemplate {
+    /// Resolve the user text for the given categories and media.
+    pub fn resolve_user(&self, classes: &[String], media: &Media) -> String {
+        self.instruction.get(media).replace("{classes}", &classes.join(", "))
+    }
+}
+
+/// Prompt template for ground requests. The system instruction comes from
+/// [`QuestionPromptTemplate::grounded_instruction`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroundPromptTemplate {
+    /// User text with an `{expression}` placeholder, asking for exactly one region.
+    pub single: ModalityPrompt,
+    /// User text with an `{expression}` placeholder, asking for every matching region.
+    pub all: ModalityPrompt,
+}
+
+impl GroundPromptTemplate {
+    /// Resolve the user text for the given referring expression and media.
+    pub fn resolve_user(&self, expression: &str, all_matches: bool, media: &Media) -> String {
+        let template = if all_matches { &self.all } else { &self.single };
+        template.get(media).replace("{expression}", expression)
+    }
+}
+
+/// Prompt template for compare requests.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComparePromptTemplate {
+    /// Text sent before the first image.
+    pub before_label: Cow<'static, str>,
+    /// Text sent before the second image.
+    pub after_label: Cow<'static, str>,
+    /// User text sent after both images. It should ask for boxes whose mention is
+    /// `added: <object>`, `removed: <object>` or `changed (before|after): <object>`.
+    pub instruction: Cow<'static, str>,
+}
+
+/// Prompt template for document requests.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocumentPromptTemplate {
+    /// User text with a `{fields}` placeholder. It should ask for `field: value` lines
+    /// and a `<point_box>` per value whose mention is the field name.
+    pub fields: Cow<'static, str>,
+    /// User text used when no fields are requested, asking for every labeled field.
+    pub all_fields: Cow<'static, str>,
+    /// Text appended when tables are requested, asking for Markdown tables.
+    pub tables: Cow<'static, str>,
+}
+
+impl DocumentPromptTemplate {
+    /// Resolve the user text for the given fields.
+    pub fn resolve_user(&self, fields: &[String], tables: bool) -> String {
+        let mut text = if fields.is_empty() {
+            self.all_fields.to_string()
+        } else {
+            self.fields.replace("{fields}", &fields.join(", "))
+        };
+        if tables {
+            text.push(' ');
+            text.push_str(&self.tables);
+        }
+        text
+    }
+}
+
+/// Prompt template for video summary requests.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VideoSummaryPromptTemplate {
+    /// User text asking for a short summary followed by one `<clip>` line per chapter,
+    /// whose mention is the chapter title.
+    pub instruction: Cow<'static, str>,
+    /// Text with a `{duration}` placeholder (in seconds), appended when the duration is known.
+    pub duration: Cow<'static, str>,
+}
+
+impl VideoSummaryPromptTemplate {
+    /// Resolve the user text, mentioning the video duration when known.
+    pub fn resolve_user(&self, duration: Option<f32>) -> String {
+        match duration {
+            Some(duration) => format!(
+                "{} {}",
+                self.instruction,
+                self.duration.replace("{duration}", &duration.to_string())
+            ),
+            None => self.instruction.to_string(),
+        }
+    }
+}
+
+/// Prompt template for locate requests.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocatePromptTemplate {
+    /// User text with a `{query}` placeholder, asking for one `<clip>` per occurrence
+    /// with a `confidence` attribute.
+    pub instruction: Cow<'static, str>,
+    /// Text appended when point key frames are requested.
+    pub key_point: Cow<'static, str>,
+    /// Text appended when box key frames are requested.
+    pub key_box: Cow<'static, str>,
+}
+
+impl LocatePromptTemplate {
+    /// Resolve the user text for the given event query and key frame format.
+    pub fn resolve_user(&self, query: &str, key_frames: Option<KeyFrameFormat>) -> String {
+        let text = self.instruction.replace("{query}", query);
+        match key_frames {
+            Some(KeyFrameFormat::Point) => format!("{text} {}", self.key_point),
+            Some(KeyFrameFormat::Box) => format!("{text} {}", self.key_box),
+            None => text,
+        }
+    }
+}
+
+/// Prompt template for tag requests.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagPromptTemplate {
+    /// User text with a `{tags}` placeholder listing the taxonomy paths.
+    pub instruction: ModalityPrompt,
+}
+
+impl TagPromptTemplate {
+    /// Resolve the user text for the given taxonomy paths and media.
+    pub fn resolve_user(&self, paths: &[String], media: &Media) -> String {
+        self.instruction.get(media).replace("{tags}", &paths.join("; "))
+    }
+}
+
+/// Prompt template for controlling the response language.
+///
+/// Only this instruction is added for `output_language`; the other templates stay in the
+/// profile's own language, which is English unless a translated profile is installed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LanguagePromptTemplate {
+    /// Instruction with a `{language}` placeholder. It should ask for translated prose
+    /// while keeping tag `mention` labels canonical.
+    pub instruction: Cow<'static, str>,
+}
+
+impl LanguagePromptTemplate {
+    /// Resolve the instruction for the given language name (e.g. `"Japanese"`).
+    pub fn resolve(&self, language: &str) -> String {
+        self.instruction.replace("{language}", language)
+    }
 }
 
 /// A collection of prompt templates for a specific model family.
+///
+/// Start from [`PromptProfile::ISAAC`] and replace the templates you want to tune, then
+/// install the profile with [`PerceptronClient::prompt_profile`](crate::PerceptronClient::prompt_profile).
+/// New tasks add templates, so a profile is built by changing fields of an existing one
+/// rather than written out in full.
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub struct PromptProfile {
     /// Question prompt template.
     pub question: QuestionPromptTemplate,
@@ -129,41 +411,742 @@ pub struct PromptProfile {
     pub ocr: OcrPromptTemplate,
     /// Detect prompt template.
     pub detect: DetectPromptTemplate,
+    /// Classify prompt template.
+    pub classify: ClassifyPromptTemplate,
+    /// Count prompt template.
+    pub count: CountPromptTemplate,
+    /// Ground prompt template.
+    pub ground: GroundPromptTemplate,
+    /// Compare prompt template.
+    pub compare: ComparePromptTemplate,
+    /// Document prompt template.
+    pub document: DocumentPromptTemplate,
+    /// Video summary prompt template.
+    pub video_summary: VideoSummaryPromptTemplate,
+    /// Locate prompt template.
+    pub locate: LocatePromptTemplate,
+    /// Tag prompt template.
+    pub tag: TagPromptTemplate,
+    /// Output language template.
+    pub language: LanguagePromptTemplate,
 }
 
-pub const ISAAC: PromptProfile = PromptProfile {
-    question: QuestionPromptTemplate {
-        open_instruction: None,
-        grounded_instruction: None,
-    },
-    caption: CaptionPromptTemplate {
-        system: None,
-        concise: ModalityPrompt {
-            image: "Provide a concise, human-friendly caption for the upcoming image.",
-            video: "Provide a concise, human-friendly caption for the upcoming video.",
-        },
-        detailed: ModalityPrompt {
-            image: "Provide a detailed caption describing key objects, relationships, and context in the upcoming image.",
-            video: "Provide a detailed caption describing key objects, relationships, and context in the upcoming video.",
-        },
-    },
-    ocr: OcrPromptTemplate {
-        system: Some(
-            "You are an OCR (Optical Character Recognition) system. \
-                Accurately detect, extract, and transcribe all readable text from the image.",
-        ),
-        plain: None,
-        markdown: "Transcribe every readable word in the image using Markdown formatting with headings, lists, tables, and other structural elements as appropriate.",
-        html: "Transcribe every readable word in the image using HTML markup.",
-    },
-    detect: DetectPromptTemplate {
-        general: ModalityPrompt {
-            image: "Your goal is to segment out the objects in the scene",
-            video: "Your goal is to segment out the objects in the scene. Make sure to track the objects.",
-        },
-        category_template: ModalityPrompt {
-            image: "Your goal is to segment out the following categories: {categories}",
-            video: "Your goal is to segment out the following categories: {categories}. Make sure to track the objects.",
-        },
-    },
-};
+impl PromptProfile {
+    /// Prompt templates for the Isaac model family.
+    pub const ISAAC: PromptProfile = PromptProfile {
+        question: QuestionPromptTemplate {
+            open_instruction: None,
+            grounded_instruction: None,
+        },
+        caption: CaptionPromptTemplate {
+            system: None,
+            concise: ModalityPrompt {
+                image: Cow::Borrowed("Provide a concise, human-friendly caption for the upcoming image."),
+                video: Cow::Borrowed("Provide a concise, human-friendly caption for the upcoming video."),
+            },
+            detailed: ModalityPrompt {
+                image: Cow::Borrowed(
+                    "Provide a detailed caption describing key objects, relationships, and context in the upcoming image.",
+                ),
+                video: Cow::Borrowed(
+                    "Provide a detailed caption describing key objects, relationships, and context in the upcoming video.",
+                ),
+            },
+            alt_text: ModalityPrompt {
+                image: Cow::Borrowed(
+                    "Write alt text for the upcoming image for screen reader users, following WCAG guidance: describe its essential content and purpose in under 125 characters, without starting with \"image of\".",
+                ),
+                video: Cow::Borrowed(
+                    "Write alt text for the upcoming video for screen reader users, following WCAG guidance: describe its essential content and purpose in under 125 characters, without starting with \"video of\".",
+                ),
+            },
+            product: ModalityPrompt {
+                image: Cow::Borrowed(
+                    "Write a product listing caption for the upcoming image, covering the product type, color, material, and notable features.",
+                ),
+                video: Cow::Borrowed(
+                    "Write a product listing caption for the upcoming video, covering the product type, color, material, and notable features.",
+                ),
+            },
+            social: ModalityPrompt {
+                image: Cow::Borrowed("Write an engaging social media caption for the upcoming image."),
+                video: Cow::Borrowed("Write an engaging social media caption for the upcoming video."),
+            },
+            dense_region: ModalityPrompt {
+                image: Cow::Borrowed(
+                    "Describe each distinct region of the upcoming image in its own sentence, covering every notable object and area.",
+                ),
+                video: Cow::Borrowed(
+                    "Describe each distinct region of the upcoming video in its own sentence, covering every notable object and area.",
+                ),
+            },
+            max_words: Cow::Borrowed("Use at most {max} words."),
+            word_range: Cow::Borrowed("Use between {min} and {max} words."),
+            max_characters: Cow::Borrowed("Use at most {max} characters."),
+            character_range: Cow::Borrowed("Use between {min} and {max} characters."),
+        },
+        ocr: OcrPromptTemplate {
+            system: Some(Cow::Borrowed(
+                "You are an OCR (Optical Character Recognition) system. \
+                    Accurately detect, extract, and transcribe all readable text from the image.",
+            )),
+            plain: None,
+            markdown: Cow::Borrowed(
+                "Transcribe every readable word in the image using Markdown formatting with headings, lists, tables, and other structural elements as appropriate.",
+            ),
+            html: Cow::Borrowed("Transcribe every readable word in the image using HTML markup."),
+            layout: Cow::Borrowed(
+                "Transcribe every readable line of text in the image in reading order. \
+                    Output each line as a <point_box> whose mention attribute is the exact text of the line, \
+                    writing double quotes as &quot;. Separate text blocks with a blank line.",
+            ),
+        },
+        detect: DetectPromptTemplate {
+            general: ModalityPrompt {
+                image: Cow::Borrowed("Your goal is to segment out the objects in the scene"),
+                video: Cow::Borrowed(
+                    "Your goal is to segment out the objects in the scene. Make sure to track the objects.",
+                ),
+            },
+            category_template: ModalityPrompt {
+                image: Cow::Borrowed("Your goal is to segment out the following categories: {categories}"),
+                video: Cow::Borrowed(
+                    "Your goal is to segment out the following categories: {categories}. Make sure to track the objects.",
+                ),
+            },
+            described_category: Cow::Borrowed("{category} ({description})"),
+            exclusions: Cow::Borrowed("Do not segment the following categories: {categories}."),
+            max_instances: Cow::Borrowed("Return at most {count} instances per category."),
+            min_size: Cow::Borrowed("Ignore objects smaller than {size}x{size} in output coordinates."),
+        },
+        classify: ClassifyPromptTemplate {
+            single_label: ModalityPrompt {
+                image: Cow::Borrowed(
+                    "Classify the image as one of these labels: {labels}. \
+                        List the likely labels from most to least likely, one per line, as `label: confidence` with a confidence between 0 and 1.",
+                ),
+                video: Cow::Borrowed(
+                    "Classify the video as one of these labels: {labels}. \
+                        List the likely labels from most to least likely, one per line, as `label: confidence` with a confidence between 0 and 1.",
+                ),
+            },
+            multi_label: ModalityPrompt {
+                image: Cow::Borrowed(
+                    "Which of these labels apply to the image: {labels}? \
+                        List every label that applies, one per line, as `label: confidence` with a confidence between 0 and 1.",
+                ),
+                video: Cow::Borrowed(
+                    "Which of these labels apply to the video: {labels}? \
+                        List every label that applies, one per line, as `label: confidence` with a confidence between 0 and 1.",
+                ),
+            },
+        },
+        count: CountPromptTemplate {
+            instruction: ModalityPrompt {
+                image: Cow::Borrowed(
+                    "Count every instance of these categories in the image: {classes}. \
+                        Point to each instance with a <point> tag whose mention is its category, \
+                        then state the total for each category on its own line as `category: number`.",
+                ),
+                video: Cow::Borrowed(
+                    "Count every distinct instance of these categories in the video: {classes}. \
+                        Point to each instance once with a <point> tag whose mention is its category, \
+                        then state the total for each category on its own line as `category: number`.",
+                ),
+            },
+        },
+        ground: GroundPromptTemplate {
+            single: ModalityPrompt {
+                image: Cow::Borrowed(
+                    "Locate {expression}. Return exactly one region; if several regions could match, choose the single best match.",
+                ),
+                video: Cow::Borrowed(
+                    "Locate {expression} in the video. Return exactly one region on the frame where it is clearest; \
+                        if several regions could match, choose the single best match.",
+                ),
+            },
+            all: ModalityPrompt {
+                image: Cow::Borrowed("Locate every region matching {expression}."),
+                video: Cow::Borrowed(
+                    "Locate every region matching {expression} in the video. Make sure to track the objects.",
+                ),
+            },
+        },
+        compare: ComparePromptTemplate {
+            before_label: Cow::Borrowed("Before:"),
+            after_label: Cow::Borrowed("After:"),
+            instruction: Cow::Borrowed(
+                "Compare the before and after images. Summarize what changed in one or two sentences, \
+                    then mark every change with a <point_box>. Use the mention `added: <object>` for objects only in the after image, \
+                    `removed: <object>` for objects only in the before image, and for an object that changed, \
+                    one box in each image with the mentions `changed (before): <object>` and `changed (after): <object>`.",
+            ),
+        },
+        document: DocumentPromptTemplate {
+            fields: Cow::Borrowed(
+                "Extract these fields from the document: {fields}. \
+                    Write one line per field as `field: value`, or `field: null` if the field is not present, \
+                    and mark where each value appears with a <point_box> whose mention is the field name. \
+                    Write numbers without units and dates as YYYY-MM-DD.",
+            ),
+            all_fields: Cow::Borrowed(
+                "Extract every labeled field from the document. Write one line per field as `field: value` \
+                    and mark where each value appears with a <point_box> whose mention is the field name. \
+                    Write numbers without units and dates as YYYY-MM-DD.",
+            ),
+            tables: Cow::Borrowed("Then transcribe every table in the document as a Markdown table with a header row."),
+        },
+        video_summary: VideoSummaryPromptTemplate {
+            instruction: Cow::Borrowed(
+                "Summarize the video in two or three sentences. Then split it into chapters in chronological order, \
+                    writing one line per chapter: a <clip> tag whose mention is a short chapter title and whose t is the \
+                    start and end time in seconds, followed by a one-sentence description of the chapter.",
+            ),
+            duration: Cow::Borrowed("The video is {duration} seconds long."),
+        },
+        locate: LocatePromptTemplate {
+            instruction: Cow::Borrowed(
+                "Find every time range in the video where this happens: {query}. \
+                    Write one line per occurrence with a <clip> tag whose t is the start and end time in seconds \
+                    and whose confidence attribute is between 0 and 1. If it never happens, say so.",
+            ),
+            key_point: Cow::Borrowed(
+                "On the same line, add a <point> on the event whose t is its most representative moment.",
+            ),
+            key_box: Cow::Borrowed(
+                "On the same line, add a <point_box> around the event whose t is its most representative moment.",
+            ),
+        },
+        tag: TagPromptTemplate {
+            instruction: ModalityPrompt {
+                image: Cow::Borrowed(
+                    "Tag the image. Use only tags from this list: {tags}. Write one line per tag that applies, choosing the most specific tag. \
+                        If the tag can be located, write it as a <point_box> around the evidence whose mention is the tag; otherwise write the tag alone.",
+                ),
+                video: Cow::Borrowed(
+                    "Tag the video. Use only tags from this list: {tags}. Write one line per tag that applies, choosing the most specific tag. \
+                        If the tag can be located, write it as a <point_box> around the evidence whose mention is the tag; otherwise write the tag alone.",
+                ),
+            },
+        },
+        language: LanguagePromptTemplate {
+            instruction: Cow::Borrowed(
+                "Respond in {language}. Keep the mention attribute of every tag exactly as given.",
+            ),
+        },
+    };
+
+    /// Isaac prompt templates translated to German.
+    ///
+    /// Not selected by `output_language`; install it with
+    /// [`PerceptronClient::prompt_profile`](crate::PerceptronClient::prompt_profile) or a registry.
+    pub const ISAAC_DE: PromptProfile = PromptProfile {
+        question: QuestionPromptTemplate {
+            open_instruction: None,
+            grounded_instruction: None,
+        },
+        caption: CaptionPromptTemplate {
+            system: None,
+            concise: ModalityPrompt {
+                image: Cow::Borrowed(
+                    "Gib eine prägnante, leicht verständliche Bildunterschrift für das folgende Bild an.",
+                ),
+                video: Cow::Borrowed(
+                    "Gib eine prägnante, leicht verständliche Beschreibung für das folgende Video an.",
+                ),
+            },
+            detailed: ModalityPrompt {
+                image: Cow::Borrowed(
+                    "Gib eine ausführliche Beschreibung der wichtigsten Objekte, ihrer Beziehungen und des Kontexts im folgenden Bild an.",
+                ),
+                video: Cow::Borrowed(
+                    "Gib eine ausführliche Beschreibung der wichtigsten Objekte, ihrer Beziehungen und des Kontexts im folgenden Video an.",
+                ),
+            },
+            alt_text: ModalityPrompt {
+                image: Cow::Borrowed(
+                    "Schreibe einen Alternativtext für das folgende Bild für Screenreader-Nutzer gemäß WCAG: Beschreibe den wesentlichen Inhalt und Zweck in weniger als 125 Zeichen, ohne mit \"Bild von\" zu beginnen.",
+                ),
+                video: Cow::Borrowed(
+                    "Schreibe einen Alternativtext für das folgende Video für Screenreader-Nutzer gemäß WCAG: Beschreibe den wesentlichen Inhalt und Zweck in weniger als 125 Zeichen, ohne mit \"Video von\" zu beginnen.",
+                ),
+            },
+            product: ModalityPrompt {
+                image: Cow::Borrowed(
+                    "Schreibe eine Produktbeschreibung für das folgende Bild mit Produktart, Farbe, Material und besonderen Merkmalen.",
+                ),
+                video: Cow::Borrowed(
+                    "Schreibe eine Produktbeschreibung für das folgende Video mit Produktart, Farbe, Material und besonderen Merkmalen.",
+                ),
+            },
+            social: ModalityPrompt {
+                image: Cow::Borrowed("Schreibe eine ansprechende Social-Media-Bildunterschrift für das folgende Bild."),
+                video: Cow::Borrowed("Schreibe eine ansprechende Social-Media-Beschreibung für das folgende Video."),
+            },
+            dense_region: ModalityPrompt {
+                image: Cow::Borrowed(
+                    "Beschreibe jeden einzelnen Bereich des folgenden Bildes in einem eigenen Satz und erfasse alle wichtigen Objekte und Flächen.",
+                ),
+                video: Cow::Borrowed(
+                    "Beschreibe jeden einzelnen Bereich des folgenden Videos in einem eigenen Satz und erfasse alle wichtigen Objekte und Flächen.",
+                ),
+            },
+            max_words: Cow::Borrowed("Verwende höchstens {max} Wörter."),
+            word_range: Cow::Borrowed("Verwende zwischen {min} und {max} Wörter."),
+            max_characters: Cow::Borrowed("Verwende höchstens {max} Zeichen."),
+            character_range: Cow::Borrowed("Verwende zwischen {min} und {max} Zeichen."),
+        },
+        ocr: OcrPromptTemplate {
+            system: Some(Cow::Borrowed(
+                "Du bist ein OCR-System (optische Zeichenerkennung). \
+                    Erkenne, extrahiere und transkribiere den gesamten lesbaren Text im Bild genau.",
+            )),
+            plain: None,
+            markdown: Cow::Borrowed(
+                "Transkribiere jedes lesbare Wort im Bild mit Markdown-Formatierung und verwende Überschriften, Listen, Tabellen und andere Strukturelemente, wo es passt.",
+            ),
+            html: Cow::Borrowed("Transkribiere jedes lesbare Wort im Bild mit HTML-Markup."),
+            layout: Cow::Borrowed(
+                "Transkribiere jede lesbare Textzeile im Bild in Lesereihenfolge. \
+                    Gib jede Zeile als <point_box> aus, dessen mention-Attribut den genauen Text der Zeile enthält, \
+                    und schreibe doppelte Anführungszeichen als &quot;. Trenne Textblöcke durch eine Leerzeile.",
+            ),
+        },
+        detect: DetectPromptTemplate {
+            general: ModalityPrompt {
+                image: Cow::Borrowed("Deine Aufgabe ist es, die Objekte in der Szene zu segmentieren"),
+                video: Cow::Borrowed(
+                    "Deine Aufgabe ist es, die Objekte in der Szene zu segmentieren. Achte darauf, die Objekte zu verfolgen.",
+                ),
+            },
+            category_template: ModalityPrompt {
+                image: Cow::Borrowed("Deine Aufgabe ist es, die folgenden Kategorien zu segmentieren: {categories}"),
+                video: Cow::Borrowed(
+                    "Deine Aufgabe ist es, die folgenden Kategorien zu segmentieren: {categories}. Achte darauf, die Objekte zu verfolgen.",
+                ),
+            },
+            described_category: Cow::Borrowed("{category} ({description})"),
+            exclusions: Cow::Borrowed("Segmentiere die folgenden Kategorien nicht: {categories}."),
+            max_instances: Cow::Borrowed("Gib höchstens {count} Instanzen pro Kategorie zurück."),
+            min_size: Cow::Borrowed("Ignoriere Objekte, die in Ausgabekoordinaten kleiner als {size}x{size} sind."),
+        },
+        classify: ClassifyPromptTemplate {
+            single_label: ModalityPrompt {
+                image: Cow::Borrowed(
+                    "Ordne das Bild genau einem dieser Labels zu: {labels}. \
+                        Liste die wahrscheinlichen Labels absteigend nach Wahrscheinlichkeit auf, eines pro Zeile, als `label: confidence` mit einer Konfidenz zwischen 0 und 1. Übernimm die Labels unverändert.",
+                ),
+                video: Cow::Borrowed(
+                    "Ordne das Video genau einem dieser Labels zu: {labels}. \
+                        Liste die wahrscheinlichen Labels absteigend nach Wahrscheinlichkeit auf, eines pro Zeile, als `label: confidence` mit einer Konfidenz zwischen 0 und 1. Übernimm die Labels unverändert.",
+                ),
+            },
+            multi_label: ModalityPrompt {
+                image: Cow::Borrowed(
+                    "Welche dieser Labels treffen auf das Bild zu: {labels}? \
+                        Liste jedes zutreffende Label auf, eines pro Zeile, als `label: confidence` mit einer Konfidenz zwischen 0 und 1. Übernimm die Labels unverändert.",
+                ),
+                video: Cow::Borrowed(
+                    "Welche dieser Labels treffen auf das Video zu: {labels}? \
+                        Liste jedes zutreffende Label auf, eines pro Zeile, als `label: confidence` mit einer Konfidenz zwischen 0 und 1. Übernimm die Labels unverändert.",
+                ),
+            },
+        },
+        count: CountPromptTemplate {
+            instruction: ModalityPrompt {
+                image: Cow::Borrowed(
+                    "Zähle jede Instanz dieser Kategorien im Bild: {classes}. \
+                        Markiere jede Instanz mit einem <point>-Tag, dessen mention ihre Kategorie ist, \
+                        und gib dann die Gesamtzahl jeder Kategorie in einer eigenen Zeile als `category: number` an. Übernimm die Kategorien unverändert.",
+                ),
+                video: Cow::Borrowed(
+                    "Zähle jede einzelne Instanz dieser Kategorien im Video: {classes}. \
+                        Markiere jede Instanz einmal mit einem <point>-Tag, dessen mention ihre Kategorie ist, \
+                        und gib dann die Gesamtzahl jeder Kategorie in einer eigenen Zeile als `category: number` an. Übernimm die Kategorien unverändert.",
+                ),
+            },
+        },
+        ground: GroundPromptTemplate {
+            single: ModalityPrompt {
+                image: Cow::Borrowed(
+                    "Finde {expression}. Gib genau einen Bereich zurück; wenn mehrere Bereiche passen, wähle den besten.",
+                ),
+                video: Cow::Borrowed(
+                    "Finde {expression} im Video. Gib genau einen Bereich in dem Frame zurück, in dem es am deutlichsten zu sehen ist; \
+                        wenn mehrere Bereiche passen, wähle den besten.",
+                ),
+            },
+            all: ModalityPrompt {
+                image: Cow::Borrowed("Finde jeden Bereich, auf den Folgendes zutrifft: {expression}."),
+                video: Cow::Borrowed(
+                    "Finde im Video jeden Bereich, auf den Folgendes zutrifft: {expression}. Achte darauf, die Objekte zu verfolgen.",
+                ),
+            },
+        },
+        compare: ComparePromptTemplate {
+            before_label: Cow::Borrowed("Vorher:"),
+            after_label: Cow::Borrowed("Nachher:"),
+            instruction: Cow::Borrowed(
+                "Vergleiche das Vorher- und das Nachher-Bild. Fasse die Änderungen in ein oder zwei Sätzen zusammen \
+                    und markiere dann jede Änderung mit einem <point_box>. Verwende die mention `added: <object>` für Objekte, die nur im Nachher-Bild vorkommen, \
+                    `removed: <object>` für Objekte, die nur im Vorher-Bild vorkommen, und für ein verändertes Objekt \
+                    je eine Box in jedem Bild mit den mentions `changed (before): <object>` und `changed (after): <object>`.",
+            ),
+        },
+        document: DocumentPromptTemplate {
+            fields: Cow::Borrowed(
+                "Extrahiere diese Felder aus dem Dokument: {fields}. \
+                    Schreibe eine Zeile pro Feld als `field: value`, oder `field: null`, wenn das Feld nicht vorhanden ist, \
+                    und markiere die Stelle jedes Werts mit einem <point_box>, dessen mention der Feldname ist. \
+                    Schreibe Zahlen ohne Einheiten und Daten als YYYY-MM-DD.",
+            ),
+            all_fields: Cow::Borrowed(
+                "Extrahiere alle beschrifteten Felder aus dem Dokument. Schreibe eine Zeile pro Feld als `field: value` \
+                    und markiere die Stelle jedes Werts mit einem <point_box>, dessen mention der Feldname ist. \
+                    Schreibe Zahlen ohne Einheiten und Daten als YYYY-MM-DD.",
+            ),
+            tables: Cow::Borrowed("Gib danach jede Tabelle im Dokument als Markdown-Tabelle mit Kopfzeile wieder."),
+        },
+        video_summary: VideoSummaryPromptTemplate {
+            instruction: Cow::Borrowed(
+                "Fasse das Video in zwei oder drei Sätzen zusammen. Teile es dann in chronologischer Reihenfolge in Kapitel ein \
+                    und schreibe eine Zeile pro Kapitel: ein <clip>-Tag, dessen mention ein kurzer Kapiteltitel ist und dessen t \
+                    die Start- und Endzeit in Sekunden angibt, gefolgt von einer Beschreibung des Kapitels in einem Satz.",
+            ),
+            duration: Cow::Borrowed("Das Video ist {duration} Sekunden lang."),
+        },
+        locate: LocatePromptTemplate {
+            instruction: Cow::Borrowed(
+                "Finde jeden Zeitabschnitt im Video, in dem Folgendes passiert: {query}. \
+                    Schreibe eine Zeile pro Vorkommen mit einem <clip>-Tag, dessen t die Start- und Endzeit in Sekunden angibt \
+                    und dessen confidence-Attribut zwischen 0 und 1 liegt. Wenn es nie passiert, sage das.",
+            ),
+            key_point: Cow::Borrowed(
+                "Füge in derselben Zeile einen <point> auf dem Ereignis hinzu, dessen t der repräsentativste Moment ist.",
+            ),
+            key_box: Cow::Borrowed(
+                "Füge in derselben Zeile einen <point_box> um das Ereignis hinzu, dessen t der repräsentativste Moment ist.",
+            ),
+        },
+        tag: TagPromptTemplate {
+            instruction: ModalityPrompt {
+                image: Cow::Borrowed(
+                    "Verschlagworte das Bild. Verwende nur Tags aus dieser Liste: {tags}. Schreibe eine Zeile pro zutreffendem Tag und wähle den spezifischsten Tag. \
+                        Wenn sich der Tag verorten lässt, schreibe ihn als <point_box> um den Beleg, dessen mention der Tag ist; andernfalls schreibe nur den Tag.",
+                ),
+                video: Cow::Borrowed(
+                    "Verschlagworte das Video. Verwende nur Tags aus dieser Liste: {tags}. Schreibe eine Zeile pro zutreffendem Tag und wähle den spezifischsten Tag. \
+                        Wenn sich der Tag verorten lässt, schreibe ihn als <point_box> um den Beleg, dessen mention der Tag ist; andernfalls schreibe nur den Tag.",
+                ),
+            },
+        },
+        language: LanguagePromptTemplate {
+            instruction: Cow::Borrowed(
+                "Antworte auf {language}. Übernimm das mention-Attribut jedes Tags genau wie angegeben.",
+            ),
+        },
+    };
+
+    /// Isaac prompt templates translated to Japanese.
+    ///
+    /// Not selected by `output_language`; install it with
+    /// [`PerceptronClient::prompt_profile`](crate::PerceptronClient::prompt_profile) or a registry.
+    pub const ISAAC_JA: PromptProfile = PromptProfile {
+        question: QuestionPromptTemplate {
+            open_instruction: None,
+            grounded_instruction: None,
+        },
+        caption: CaptionPromptTemplate {
+            system: None,
+            concise: ModalityPrompt {
+                image: Cow::Borrowed("次の画像に、簡潔で分かりやすいキャプションを付けてください。"),
+                video: Cow::Borrowed("次の動画に、簡潔で分かりやすいキャプションを付けてください。"),
+            },
+            detailed: ModalityPrompt {
+                image: Cow::Borrowed(
+                    "次の画像について、主要な物体、それらの関係、文脈を説明する詳細なキャプションを付けてください。",
+                ),
+                video: Cow::Borrowed(
+                    "次の動画について、主要な物体、それらの関係、文脈を説明する詳細なキャプションを付けてください。",
+                ),
+            },
+            alt_text: ModalityPrompt {
+                image: Cow::Borrowed(
+                    "スクリーンリーダー利用者向けに、WCAGのガイドラインに沿って次の画像の代替テキストを書いてください。「画像:」などで始めず、重要な内容と目的を125文字未満で説明してください。",
+                ),
+                video: Cow::Borrowed(
+                    "スクリーンリーダー利用者向けに、WCAGのガイドラインに沿って次の動画の代替テキストを書いてください。「動画:」などで始めず、重要な内容と目的を125文字未満で説明してください。",
+                ),
+            },
+            product: ModalityPrompt {
+                image: Cow::Borrowed("次の画像について、商品の種類、色、素材、特徴を含む商品説明を書いてください。"),
+                video: Cow::Borrowed("次の動画について、商品の種類、色、素材、特徴を含む商品説明を書いてください。"),
+            },
+            social: ModalityPrompt {
+                image: Cow::Borrowed("次の画像に、SNS投稿向けの魅力的なキャプションを書いてください。"),
+                video: Cow::Borrowed("次の動画に、SNS投稿向けの魅力的なキャプションを書いてください。"),
+            },
+            dense_region: ModalityPrompt {
+                image: Cow::Borrowed("次の画像の各領域を1文ずつ説明し、目立つ物体や領域をすべて網羅してください。"),
+                video: Cow::Borrowed("次の動画の各領域を1文ずつ説明し、目立つ物体や領域をすべて網羅してください。"),
+            },
+            max_words: Cow::Borrowed("{max}語以内で書いてください。"),
+            word_range: Cow::Borrowed("{min}語以上{max}語以内で書いてください。"),
+            max_characters: Cow::Borrowed("{max}文字以内で書いてください。"),
+            character_range: Cow::Borrowed("{min}文字以上{max}文字以内で書いてください。"),
+        },
+        ocr: OcrPromptTemplate {
+            system: Some(Cow::Borrowed(
+                "あなたはOCR（光学文字認識）システムです。\
+                    画像内の読み取れるテキストをすべて正確に検出、抽出、書き起こしてください。",
+            )),
+            plain: None,
+            markdown: Cow::Borrowed(
+                "画像内の読み取れる単語をすべて、見出し、リスト、表などの構造要素を適宜用いたMarkdown形式で書き起こしてください。",
+            ),
+            html: Cow::Borrowed("画像内の読み取れる単語をすべてHTMLマークアップで書き起こしてください。"),
+            layout: Cow::Borrowed(
+                "画像内の読み取れるテキストを読む順に1行ずつ書き起こしてください。\
+                    各行を<point_box>として出力し、mention属性には行のテキストをそのまま記述し、\
+                    二重引用符は&quot;と書いてください。テキストブロックの間は空行で区切ってください。",
+            ),
+        },
+        detect: DetectPromptTemplate {
+            general: ModalityPrompt {
+                image: Cow::Borrowed("シーン内の物体をセグメント化してください"),
+                video: Cow::Borrowed("シーン内の物体をセグメント化してください。物体を追跡してください。"),
+            },
+            category_template: ModalityPrompt {
+                image: Cow::Borrowed("次のカテゴリをセグメント化してください: {categories}"),
+                video: Cow::Borrowed("次のカテゴリをセグメント化してください: {categories}。物体を追跡してください。"),
+            },
+            described_category: Cow::Borrowed("{category}（{description}）"),
+            exclusions: Cow::Borrowed("次のカテゴリはセグメント化しないでください: {categories}。"),
+            max_instances: Cow::Borrowed("各カテゴリにつき最大{count}個のインスタンスを返してください。"),
+            min_size: Cow::Borrowed("出力座標で{size}x{size}より小さい物体は無視してください。"),
+        },
+        classify: ClassifyPromptTemplate {
+            single_label: ModalityPrompt {
+                image: Cow::Borrowed(
+                    "画像を次のラベルのいずれか1つに分類してください: {labels}。\
+                        可能性の高いラベルから順に1行に1つずつ、`label: confidence`の形式（confidenceは0から1の値）で記述してください。ラベルは翻訳せずそのまま記述してください。",
+                ),
+                video: Cow::Borrowed(
+                    "動画を次のラベルのいずれか1つに分類してください: {labels}。\
+                        可能性の高いラベルから順に1行に1つずつ、`label: confidence`の形式（confidenceは0から1の値）で記述してください。ラベルは翻訳せずそのまま記述してください。",
+                ),
+            },
+            multi_label: ModalityPrompt {
+                image: Cow::Borrowed(
+                    "次のラベルのうち画像に当てはまるものはどれですか: {labels}。\
+                        当てはまるラベルをすべて1行に1つずつ、`label: confidence`の形式（confidenceは0から1の値）で記述してください。ラベルは翻訳せずそのまま記述してください。",
+                ),
+                video: Cow::Borrowed(
+                    "次のラベルのうち動画に当てはまるものはどれですか: {labels}。\
+                        当てはまるラベルをすべて1行に1つずつ、`label: confidence`の形式（confidenceは0から1の値）で記述してください。ラベルは翻訳せずそのまま記述してください。",
+                ),
+            },
+        },
+        count: CountPromptTemplate {
+            instruction: ModalityPrompt {
+                image: Cow::Borrowed(
+                    "画像内の次のカテゴリのインスタンスをすべて数えてください: {classes}。\
+                        各インスタンスをmentionにカテゴリを指定した<point>タグで示し、\
+                        その後、各カテゴリの合計を1行ずつ`category: number`の形式で記述してください。カテゴリは翻訳せずそのまま記述してください。",
+                ),
+                video: Cow::Borrowed(
+                    "動画内の次のカテゴリの個別のインスタンスをすべて数えてください: {classes}。\
+                        各インスタンスを一度ずつmentionにカテゴリを指定した<point>タグで示し、\
+                        その後、各カテゴリの合計を1行ずつ`category: number`の形式で記述してください。カテゴリは翻訳せずそのまま記述してください。",
+                ),
+            },
+        },
+        ground: GroundPromptTemplate {
+            single: ModalityPrompt {
+                image: Cow::Borrowed(
+                    "{expression}の位置を特定してください。領域は必ず1つだけ返し、複数の領域が該当する場合は最も適切なものを1つ選んでください。",
+                ),
+                video: Cow::Borrowed(
+                    "動画内で{expression}の位置を特定してください。最もはっきり見えるフレームで領域を必ず1つだけ返し、\
+                        複数の領域が該当する場合は最も適切なものを1つ選んでください。",
+                ),
+            },
+            all: ModalityPrompt {
+                image: Cow::Borrowed("{expression}に該当する領域をすべて特定してください。"),
+                video: Cow::Borrowed(
+                    "動画内で{expression}に該当する領域をすべて特定してください。物体を追跡してください。",
+                ),
+            },
+        },
+        compare: ComparePromptTemplate {
+            before_label: Cow::Borrowed("変更前:"),
+            after_label: Cow::Borrowed("変更後:"),
+            instruction: Cow::Borrowed(
+                "変更前と変更後の画像を比較してください。変更点を1〜2文で要約し、\
+                    その後、すべての変更を<point_box>で示してください。変更後の画像にのみある物体にはmention `added: <object>`、\
+                    変更前の画像にのみある物体には`removed: <object>`を使い、変化した物体には各画像に1つずつ\
+                    `changed (before): <object>`と`changed (after): <object>`のボックスを付けてください。",
+            ),
+        },
+        document: DocumentPromptTemplate {
+            fields: Cow::Borrowed(
+                "文書から次の項目を抽出してください: {fields}。\
+                    1行に1項目ずつ`field: value`の形式で書き、項目が存在しない場合は`field: null`と書いてください。\
+                    各値の位置を、mentionが項目名の<point_box>で示してください。\
+                    数値は単位なしで、日付はYYYY-MM-DD形式で書いてください。",
+            ),
+            all_fields: Cow::Borrowed(
+                "文書内のラベル付きの項目をすべて抽出してください。1行に1項目ずつ`field: value`の形式で書き、\
+                    各値の位置を、mentionが項目名の<point_box>で示してください。\
+                    数値は単位なしで、日付はYYYY-MM-DD形式で書いてください。",
+            ),
+            tables: Cow::Borrowed(
+                "続いて、文書内のすべての表をヘッダー行付きのMarkdownの表として書き起こしてください。",
+            ),
+        },
+        video_summary: VideoSummaryPromptTemplate {
+            instruction: Cow::Borrowed(
+                "動画を2〜3文で要約してください。その後、動画を時系列順にチャプターに分け、1行に1チャプターずつ書いてください。\
+                    各行は、mentionが短いチャプタータイトルでtが開始時刻と終了時刻（秒）の<clip>タグと、\
+                    それに続くチャプターの1文の説明です。",
+            ),
+            duration: Cow::Borrowed("動画の長さは{duration}秒です。"),
+        },
+        locate: LocatePromptTemplate {
+            instruction: Cow::Borrowed(
+                "動画の中で次の出来事が起きる時間範囲をすべて見つけてください: {query}。\
+                    1回の発生につき1行ずつ、tが開始時刻と終了時刻（秒）で、confidence属性が0から1の<clip>タグを書いてください。\
+                    一度も起きない場合はそう答えてください。",
+            ),
+            key_point: Cow::Borrowed("同じ行に、tが最も代表的な瞬間である<point>を出来事の上に追加してください。"),
+            key_box: Cow::Borrowed("同じ行に、tが最も代表的な瞬間である<point_box>を出来事の周りに追加してください。"),
+        },
+        tag: TagPromptTemplate {
+            instruction: ModalityPrompt {
+                image: Cow::Borrowed(
+                    "画像にタグを付けてください。次のリストのタグのみを使ってください: {tags}。当てはまるタグを1行に1つずつ、最も具体的なものを選んで書いてください。\
+                        位置を示せるタグは、mentionがタグ名の<point_box>で根拠を囲んで書き、そうでない場合はタグのみを書いてください。",
+                ),
+                video: Cow::Borrowed(
+                    "動画にタグを付けてください。次のリストのタグのみを使ってください: {tags}。当てはまるタグを1行に1つずつ、最も具体的なものを選んで書いてください。\
+                        位置を示せるタグは、mentionがタグ名の<point_box>で根拠を囲んで書き、そうでない場合はタグのみを書いてください。",
+                ),
+            },
+        },
+        language: LanguagePromptTemplate {
+            instruction: Cow::Borrowed(
+                "{language}で回答してください。タグのmention属性は翻訳せず、指定どおりそのまま記述してください。",
+            ),
+        },
+    };
+}
+
+impl Default for PromptProfile {
+    fn default() -> Self {
+        Self::ISAAC
+    }
+}
+
+/// Prompt profiles keyed by model id pattern, with a fallback default.
+///
+/// Patterns match the full model id and may contain `*` wildcards (e.g. `isaac-*`).
+/// When several patterns match, the most recently registered one wins.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PromptRegistry {
+    /// Registered `(pattern, profile)` pairs in registration order.
+    entries: Vec<(String, PromptProfile)>,
+    /// Profile used when no pattern matches.
+    fallback: PromptProfile,
+}
+
+impl PromptRegistry {
+    /// Create an empty registry that resolves every model to `fallback`.
+    pub fn new(fallback: PromptProfile) -> Self {
+        Self {
+            entries: Vec::new(),
+            fallback,
+        }
+    }
+
+    /// Register a profile for model ids matching `pattern`.
+    pub fn register(mut self, pattern: impl Into<String>, profile: PromptProfile) -> Self {
+        self.entries.push((pattern.into(), profile));
+        self
+    }
+
+    /// Set the profile used when no pattern matches.
+    pub fn fallback(mut self, profile: PromptProfile) -> Self {
+        self.fallback = profile;
+        self
+    }
+
+    /// Resolve the profile for the given model id.
+    pub fn resolve(&self, model: &str) -> &PromptProfile {
+        self.entries
+            .iter()
+            .rev()
+            .find(|(pattern, _)| matches_pattern(pattern, model))
+            .map_or(&self.fallback, |(_, profile)| profile)
+    }
+}
+
+impl Default for PromptRegistry {
+    fn default() -> Self {
+        Self::new(PromptProfile::ISAAC).register("isaac-*", PromptProfile::ISAAC)
+    }
+}
+
+/// Match `text` against a glob `pattern` where `*` matches any run of characters.
+fn matches_pattern(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts: Vec<&str> = parts.collect();
+    let Some(last) = parts.pop() else {
+        // No wildcard: the pattern must match exactly.
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pattern_matching() {
+        assert!(matches_pattern("isaac-*", "isaac-0.1"));
+        assert!(matches_pattern("isaac-*", "isaac-"));
+        assert!(!matches_pattern("isaac-*", "my-isaac-0.1"));
+        assert!(matches_pattern("*-ft", "isaac-0.1-ft"));
+        assert!(matches_pattern("acme-*-ft-*", "acme-isaac-ft-2"));
+        assert!(!matches_pattern("acme-*-ft-*", "acme-isaac-2"));
+        assert!(matches_pattern("exact", "exact"));
+        assert!(!matches_pattern("exact", "exactly"));
+        assert!(matches_pattern("*", "anything"));
+    }
+
+    #[test]
+    fn registry_prefers_latest_match() {
+        let mut tuned = PromptProfile::ISAAC;
+        tuned.detect.general = ModalityPrompt::uniform("tuned");
+        let mut fine_tuned = PromptProfile::ISAAC;
+        fine_tuned.detect.general = ModalityPrompt::uniform("fine-tuned");
+
+        let registry = PromptRegistry::default()
+            .register("isaac-*", tuned.clone())
+            .register("isaac-*-ft", fine_tuned.clone());
+
+        assert_eq!(registry.resolve("isaac-0.1"), &tuned);
+        assert_eq!(registry.resolve("isaac-0.1-ft"), &fine_tuned);
+        assert_eq!(registry.resolve("other"), &PromptProfile::ISAAC);
+    }
+}
@@ -0,0 +1,307 @@
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::Duration;
+
+use regex::Regex;
+use reqwest::header::HeaderMap;
+use tokio::time::Instant;
+
+use crate::api::chat_completions::*;
+
+/// Rough token cost of text, in characters per token.
+const CHARS_PER_TOKEN: usize = 4;
+/// Rough token cost of an image, used until the server reports actual usage.
+const IMAGE_TOKENS: u32 = 1_024;
+/// Rough token cost of a video, used until the server reports actual usage.
+const VIDEO_TOKENS: u32 = 8_192;
+
+static DURATION_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(\d+(?:\.\d+)?)(ms|h|m|s)").expect("regex creation should never fail here"));
+
+/// A refilling allowance of requests or tokens.
+#[derive(Debug)]
+struct Bucket {
+    capacity: f64,
+    available: f64,
+    per_second: f64,
+}
+
+impl Bucket {
+    fn per_minute(limit: u32) -> Self {
+        let capacity = f64::from(limit.max(1));
+        Self {
+            capacity,
+            available: capacity,
+            per_second: capacity / 60.0,
+        }
+    }
+
+    fn refill(&mut self, elapsed: Duration) {
+        self.available = (self.available + elapsed.as_secs_f64() * self.per_second).min(self.capacity);
+    }
+
+    /// Time until `amount` is available. Amounts over capacity only need a full bucket.
+    fn wait_for(&self, amount: f64) -> Duration {
+        let missing = amount.min(self.capacity) - self.available;
+        Duration::from_secs_f64((missing / self.per_second).max(0.0))
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    requests: Option<Bucket>,
+    tokens: Option<Bucket>,
+    blocked_until: Option<Instant>,
+    updated: Instant,
+}
+
+impl State {
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now - self.updated;
+        self.updated = now;
+        for bucket in [&mut self.requests, &mut self.tokens].into_iter().flatten() {
+            bucket.refill(elapsed);
+        }
+    }
+}
+
+/// Client-side token-bucket limits on requests and tokens per minute.
+///
+/// Requests wait for capacity instead of failing. Token costs are estimated from the request
+/// and corrected once the server reports usage. `x-ratelimit-remaining-*`,
+/// `x-ratelimit-reset-*` and `retry-after` response headers also throttle the limiter, and a
+/// request rejected with HTTP 429 and `retry-after` is sent again once the delay has passed.
+///
+/// Clones share the same limits, so one limiter can be given to several clients.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    state: Arc<Mutex<State>>,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RateLimiter {
+    /// Create a limiter with no limits, which only follows rate-limit response headers.
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(State {
+                requests: None,
+                tokens: None,
+                blocked_until: None,
+                updated: Instant::now(),
+            })),
+        }
+    }
+
+    /// Allow at most `limit` requests per minute, with bursts of up to `limit`.
+    pub fn requests_per_minute(self, limit: u32) -> Self {
+        self.lock().requests = Some(Bucket::per_minute(limit));
+        self
+    }
+
+    /// Allow at most `limit` estimated tokens per minute, prompt and completion combined.
+    pub fn tokens_per_minute(self, limit: u32) -> Self {
+        self.lock().tokens = Some(Bucket::per_minute(limit));
+        self
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Wait until one request costing `tokens` may be sent, then take it from the limits.
+    pub(crate) async fn acquire(&self, tokens: u32) {
+        loop {
+            let wait = {
+                let mut state = self.lock();
+                state.refill();
+                let blocked = state
+                    .blocked_until
+                    .map_or(Duration::ZERO, |until| until.saturating_duration_since(state.updated));
+                let wait = [
+                    Some(blocked),
+                    state.requests.as_ref().map(|b| b.wait_for(1.0)),
+                    state.tokens.as_ref().map(|b| b.wait_for(f64::from(tokens))),
+                ]
+                .into_iter()
+                .flatten()
+                .max()
+                .unwrap_or_default();
+                if wait.is_zero() {
+                    state.blocked_until = None;
+                    if let Some(bucket) = state.requests.as_mut() {
+                        bucket.available -= 1.0;
+                    }
+                    if let Some(bucket) = state.tokens.as_mut() {
+                        bucket.available -= f64::from(tokens);
+                    }
+                    return;
+                }
+                wait
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Correct the token limit once the actual cost of a request is known.
+    pub(crate) fn settle(&self, estimated: u32, actual: u32) {
+        if let Some(bucket) = self.lock().tokens.as_mut() {
+            bucket.available = (bucket.available + f64::from(estimated) - f64::from(actual)).min(bucket.capacity);
+        }
+    }
+
+    /// Follow the rate-limit headers of a response.
+    pub(crate) fn update(&self, headers: &HeaderMap) {
+        let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+        let mut guard = self.lock();
+        let state = &mut *guard;
+        state.refill();
+
+        let mut delays = vec![header("retry-after").and_then(parse_duration)];
+        for (kind, bucket) in [("requests", &mut state.requests), ("tokens", &mut state.tokens)] {
+            let Some(remaining) =
+                header(&format!("x-ratelimit-remaining-{kind}")).and_then(|value| value.trim().parse::<f64>().ok())
+            else {
+                continue;
+            };
+            if remaining < 1.0 {
+                delays.push(header(&format!("x-ratelimit-reset-{kind}")).and_then(parse_duration));
+            }
+            if let Some(bucket) = bucket.as_mut() {
+                bucket.available = bucket.available.min(remaining);
+            }
+        }
+        if let Some(delay) = delays.into_iter().flatten().max() {
+            let until = state.updated + delay;
+            state.blocked_until = Some(state.blocked_until.map_or(until, |current| current.max(until)));
+        }
+    }
+}
+
+/// Parse a header duration: plain seconds (`"20"`, `"0.5"`) or units (`"6m0s"`, `"250ms"`).
+fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<f64>() {
+        return Duration::try_from_secs_f64(seconds).ok();
+    }
+    let mut seconds = 0.0;
+    let mut matched = false;
+    for captures in DURATION_REGEX.captures_iter(value) {
+        let amount: f64 = captures[1].parse().ok()?;
+        seconds += amount
+            * match &captures[2] {
+                "h" => 3600.0,
+                "m" => 60.0,
+                "ms" => 0.001,
+                _ => 1.0,
+            };
+        matched = true;
+    }
+    matched.then(|| Duration::try_from_secs_f64(seconds).ok()).flatten()
+}
+
+/// Estimate the tokens a request will use: its text, a fixed cost per media item, and the
+/// completion budget for every choice.
+pub(crate) fn estimate_tokens(request: &CreateChatCompletionRequest) -> u32 {
+    let mut chars = 0;
+    let mut media = 0;
+    for message in &request.messages {
+        match message {
+            ChatCompletionMessage::System(ChatCompletionSystemMessage {
+                content: ChatCompletionSystemMessageContent::Text(text),
+            })
+            | ChatCompletionMessage::User(ChatCompletionUserMessage {
+                content: ChatCompletionUserMessageContent::Text(text),
+            })
+            | ChatCompletionMessage::Assistant(ChatCompletionAssistantMessage {
+                content: ChatCompletionAssistantMessageContent::Text(text),
+            }) => chars += text.len(),
+            ChatCompletionMessage::User(ChatCompletionUserMessage {
+                content: ChatCompletionUserMessageContent::Array(parts),
+            }) => {
+                for part in parts {
+                    match part {
+                        ChatCompletionContentPart::Text(part) => chars += part.text.len(),
+                        ChatCompletionContentPart::ImageUrl(_) => media = IMAGE_TOKENS.saturating_add(media),
+                        ChatCompletionContentPart::VideoUrl(_) => media = VIDEO_TOKENS.saturating_add(media),
+                    }
+                }
+            }
+        }
+    }
+    let completion = request
+        .max_completion_tokens
+        .unwrap_or(0)
+        .saturating_mul(request.n.unwrap_or(1));
+    u32::try_from(chars / CHARS_PER_TOKEN)
+        .unwrap_or(u32::MAX)
+        .saturating_add(media)
+        .saturating_add(completion)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_header_durations() {
+        assert_eq!(parse_duration("20"), Some(Duration::from_secs(20)));
+        assert_eq!(parse_duration("6m0s"), Some(Duration::from_secs(360)));
+        assert_eq!(parse_duration("1.5s"), Some(Duration::from_millis(1500)));
+        assert_eq!(parse_duration("250ms"), Some(Duration::from_millis(250)));
+        assert_eq!(parse_duration("soon"), None);
+    }
+
+    #[test]
+    fn estimates_saturate() {
+        let request: CreateChatCompletionRequest = serde_json::from_value(serde_json::json!({
+            "model": "isaac",
+            "messages": [{"role": "user", "content": "Describe this."}],
+            "max_completion_tokens": u32::MAX,
+            "n": 4
+        }))
+        .expect("request");
+        assert_eq!(estimate_tokens(&request), u32::MAX);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn waits_for_requests_to_refill() {
+        let limiter = RateLimiter::new().requests_per_minute(2);
+        let start = Instant::now();
+        limiter.acquire(0).await;
+        limiter.acquire(0).await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+        limiter.acquire(0).await;
+        assert!(start.elapsed() >= Duration::from_secs(30));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn settles_token_estimates() {
+        let limiter = RateLimiter::new().tokens_per_minute(600);
+        let start = Instant::now();
+        limiter.acquire(500).await;
+        limiter.settle(500, 100);
+        limiter.acquire(500).await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+        limiter.acquire(300).await;
+        assert!(start.elapsed() >= Duration::from_secs(30));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn follows_rate_limit_headers() {
+        let limiter = RateLimiter::new();
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-remaining-requests", "0".parse().unwrap());
+        headers.insert("x-ratelimit-reset-requests", "2s".parse().unwrap());
+        limiter.update(&headers);
+        let start = Instant::now();
+        limiter.acquire(0).await;
+        assert!(start.elapsed() >= Duration::from_secs(2));
+        limiter.acquire(0).await;
+        assert!(start.elapsed() < Duration::from_secs(3));
+    }
+}
@@ -0,0 +1,327 @@
+use serde::{Deserialize, Serialize};
+
+use crate::classification::{label_key, match_exact};
+use crate::parsing;
+use crate::pointing::{BoundingBox, Pointing};
+
+/// Separator between levels when a taxonomy path is written out.
+const PATH_SEPARATOR: &str = " > ";
+
+/// A label in a [`Taxonomy`], with optional synonyms and narrower labels.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TaxonomyNode {
+    /// Canonical label.
+    pub label: String,
+    /// Other names the model may use for this label.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// Narrower labels.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<TaxonomyNode>,
+}
+
+impl TaxonomyNode {
+    /// Create a label with no aliases or children.
+    pub fn new(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            aliases: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Add a synonym that maps to this label.
+    pub fn alias(mut self, alias: impl Into<String>) -> Self {
+        self.aliases.push(alias.into());
+        self
+    }
+
+    /// Add a narrower label.
+    pub fn child(mut self, child: TaxonomyNode) -> Self {
+        self.children.push(child);
+        self
+    }
+}
+
+/// A controlled vocabulary of labels organized as a tree.
+///
+/// Serializes as a list of root nodes, so it can be loaded from JSON with `serde_json`
+/// (or YAML with a serde YAML crate):
+///
+/// ```json
+/// [{"label": "animal", "children": [{"label": "dog", "aliases": ["puppy"]}]}]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Taxonomy {
+    /// Top-level labels.
+    pub roots: Vec<TaxonomyNode>,
+}
+
+impl Taxonomy {
+    /// Create a taxonomy from its top-level labels.
+    pub fn new(roots: Vec<TaxonomyNode>) -> Self {
+        Self { roots }
+    }
+
+    /// Every label path in depth-first order, e.g. `animal > dog`.
+    pub fn paths(&self) -> Vec<String> {
+        flatten(self)
+            .into_iter()
+            .map(|node| node.path.join(PATH_SEPARATOR))
+            .collect()
+    }
+}
+
+/// A taxonomy tag that applies to the media.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Tag {
+    /// Canonical taxonomy label.
+    pub label: String,
+    /// Labels from the root down to this one.
+    pub path: Vec<String>,
+    /// Whether the tag was only implied as the ancestor of another tag.
+    pub inferred: bool,
+    /// Region showing the tag, if the model marked one. The box mention is cleared.
+    pub evidence: Option<BoundingBox>,
+}
+
+struct FlatNode<'a> {
+    node: &'a TaxonomyNode,
+    path: Vec<String>,
+    parent: Option<usize>,
+}
+
+fn flatten(taxonomy: &Taxonomy) -> Vec<FlatNode<'_>> {
+    fn visit<'a>(node: &'a TaxonomyNode, parent: Option<usize>, flat: &mut Vec<FlatNode<'a>>) {
+        let mut path = parent.map(|p| flat[p].path.clone()).unwrap_or_default();
+        path.push(node.label.clone());
+        let index = flat.len();
+        flat.push(FlatNode { node, path, parent });
+        for child in &node.children {
+            visit(child, Some(index), flat);
+        }
+    }
+    let mut flat = Vec::new();
+    for root in &taxonomy.roots {
+        visit(root, None, &mut flat);
+    }
+    flat
+}
+
+/// Find the taxonomy node a tag names, by label, alias or path (`animal > dog`, `animal/dog`).
+/// Only case, punctuation and plurals may differ; anything else is no match.
+///
+/// The whole tag is matched against labels and aliases first, so labels containing a separator
+/// (`Food/Drink`) resolve as themselves. Only then is the tag read as a path, split on `>`, or
+/// on `/` when it has no `>`.
+fn resolve(flat: &[FlatNode<'_>], tag: &str) -> Option<usize> {
+    let (owners, candidates): (Vec<usize>, Vec<String>) = flat
+        .iter()
+        .enumerate()
+        .flat_map(|(i, node)| {
+            std::iter::once(&node.node.label)
+                .chain(&node.node.aliases)
+                .map(move |l| (i, l.clone()))
+        })
+        .unzip();
+    if let Some(matched) = match_exact(tag, &candidates) {
+        return candidates.iter().position(|c| c == matched).map(|i| owners[i]);
+    }
+
+    let separator = if tag.contains('>') { '>' } else { '/' };
+    let segments: Vec<String> = tag.split(separator).map(label_key).filter(|s| !s.is_empty()).collect();
+    if segments.len() < 2 {
+        return None;
+    }
+    flat.iter().position(|node| {
+        node.path.len() >= segments.len()
+            && node.path[node.path.len() - segments.len()..]
+                .iter()
+                .zip(&segments)
+                .all(|(label, segment)| label_key(label) == *segment)
+    })
+}
+
+/// Map the tags in a tagging answer onto the taxonomy.
+///
+/// Tags come from box mentions, or from plain lines when a line has no box. Tags that do not
+/// name a label, alias or path exactly (up to case, punctuation and plurals), including
+/// prose lines, are returned as rejected. Ancestors of matched tags are added as
+/// inferred tags. Tags are returned in taxonomy order.
+pub(crate) fn assign_tags(text: &str, pointing: Option<&Pointing>, taxonomy: &Taxonomy) -> (Vec<Tag>, Vec<String>) {
+    let flat = flatten(taxonomy);
+    let mut evidence: Vec<Option<Option<BoundingBox>>> = vec![None; flat.len()];
+    let mut rejected: Vec<String> = Vec::new();
+    let mut assign = |tag: &str, bbox: Option<&BoundingBox>| match resolve(&flat, tag) {
+        Some(index) => {
+            let slot = evidence[index].get_or_insert(None);
+            if slot.is_none() {
+                *slot = bbox.map(|b| BoundingBox {
+                    mention: None,
+                    ..b.clone()
+                });
+            }
+        }
+        None if !rejected.iter().any(|r| r == tag) => rejected.push(tag.to_string()),
+        None => {}
+    };
+
+    for b in pointing.map(|p| p.boxes.as_slice()).unwrap_or_default() {
+        if let Some(mention) = b.mention.as_deref() {
+            assign(mention.trim(), Some(b));
+        }
+    }
+    for line in text.lines().filter(|line| !line.contains("<point_box")) {
+        let line = parsing::strip_tags(line);
+        let tag = line
+            .trim_start_matches(|c: char| matches!(c, '-' | '*' | '•') || c.is_whitespace())
+            .trim_matches(|c: char| matches!(c, '*' | '`' | '.' | ','));
+        if !tag.is_empty() && !tag.ends_with(':') {
+            assign(tag, None);
+        }
+    }
+
+    let mut applied: Vec<Option<bool>> = evidence.iter().map(|e| e.as_ref().map(|_| false)).collect();
+    for index in 0..flat.len() {
+        if applied[index] == Some(false) {
+            let mut parent = flat[index].parent;
+            while let Some(p) = parent {
+                applied[p].get_or_insert(true);
+                parent = flat[p].parent;
+            }
+        }
+    }
+    let tags = flat
+        .iter()
+        .zip(applied)
+        .zip(evidence)
+        .filter_map(|((node, inferred), evidence)| {
+            Some(Tag {
+                label: node.node.label.clone(),
+                path: node.path.clone(),
+                inferred: inferred?,
+                evidence: evidence.flatten(),
+            })
+        })
+        .collect();
+    (tags, rejected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::OutputFormat;
+
+    fn taxonomy() -> Taxonomy {
+        serde_json::from_str(
+            r#"[
+                {"label": "animal", "children": [
+                    {"label": "dog", "aliases": ["puppy"], "children": [{"label": "golden retriever"}]},
+                    {"label": "cat"}
+                ]},
+                {"label": "vehicle", "children": [{"label": "car"}, {"label": "bicycle"}]},
+                {"label": "outdoor"}
+            ]"#,
+        )
+        .expect("taxonomy")
+    }
+
+    #[test]
+    fn lists_paths() {
+        assert_eq!(
+            taxonomy().paths(),
+            [
+                "animal",
+                "animal > dog",
+                "animal > dog > golden retriever",
+                "animal > cat",
+                "vehicle",
+                "vehicle > car",
+                "vehicle > bicycle",
+                "outdoor"
+            ]
+        );
+    }
+
+    #[test]
+    fn maps_tags_and_infers_ancestors() {
+        let text = r#"<point_box mention="Golden Retrievers"> (10,10) (50,50) </point_box>
+<point_box mention="vehicle/car"> (60,10) (90,40) </point_box>
+- puppy
+- Outdoor
+- skateboard"#;
+        let pointing = parsing::extract(text, Some(&OutputFormat::Box));
+        let (tags, rejected) = assign_tags(text, pointing.as_ref(), &taxonomy());
+
+        let summary: Vec<_> = tags
+            .iter()
+            .map(|t| (t.label.as_str(), t.inferred, t.evidence.as_ref().map(|b| b.x1)))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("animal", true, None),
+                ("dog", false, None),
+                ("golden retriever", false, Some(10)),
+                ("vehicle", true, None),
+                ("car", false, Some(60)),
+                ("outdoor", false, None),
+            ]
+        );
+        assert_eq!(tags[2].path, ["animal", "dog", "golden retriever"]);
+        assert_eq!(tags[2].evidence.as_ref().and_then(|b| b.mention.clone()), None);
+        assert_eq!(rejected, ["skateboard"]);
+    }
+
+    #[test]
+    fn resolves_labels_containing_slashes() {
+        let taxonomy: Taxonomy = serde_json::from_str(
+            r#"[
+                {"label": "Food/Drink", "children": [{"label": "coffee"}]},
+                {"label": "music", "children": [{"label": "R&B/Soul"}]}
+            ]"#,
+        )
+        .expect("taxonomy");
+        let text = "- food/drink\n- Music > R&B/Soul\n- Food/Drink > Coffee";
+        let (tags, rejected) = assign_tags(text, None, &taxonomy);
+
+        let labels: Vec<_> = tags.iter().map(|t| (t.label.as_str(), t.inferred)).collect();
+        assert_eq!(
+            labels,
+            [
+                ("Food/Drink", false),
+                ("coffee", false),
+                ("music", true),
+                ("R&B/Soul", false)
+            ]
+        );
+        assert!(rejected.is_empty(), "{rejected:?}");
+    }
+
+    #[test]
+    fn rejects_phrases_containing_labels() {
+        let text = r#"<point_box mention="hot dog"> (10,10) (50,50) </point_box>
+The image shows a dog in a car.
+- dog bowl
+- animal > cat > kitten
+- cats"#;
+        let pointing = parsing::extract(text, Some(&OutputFormat::Box));
+        let (tags, rejected) = assign_tags(text, pointing.as_ref(), &taxonomy());
+
+        let labels: Vec<_> = tags.iter().map(|t| (t.label.as_str(), t.inferred)).collect();
+        assert_eq!(labels, [("animal", true), ("cat", false)]);
+        assert_eq!(
+            rejected,
+            [
+                "hot dog",
+                "The image shows a dog in a car",
+                "dog bowl",
+                "animal > cat > kitten"
+            ]
+        );
+    }
+}
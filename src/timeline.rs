@@ -0,0 +1,389 @@
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::pointing::{Clip, ClipTimestamp};
+
+/// Default length in seconds given to moment clips when they are turned into ranges.
+const DEFAULT_MOMENT_DURATION: f32 = 1.0;
+
+/// A labeled time range on a [`Timeline`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TimelineEntry {
+    /// Optional label.
+    pub mention: Option<String>,
+    /// Start of the range, in seconds.
+    pub start: f32,
+    /// End of the range, in seconds.
+    pub end: f32,
+}
+
+impl TimelineEntry {
+    /// Duration of the entry, in seconds.
+    pub fn duration(&self) -> f32 {
+        self.end - self.start
+    }
+
+    /// Whether the entry covers time `t`.
+    pub fn contains(&self, t: f32) -> bool {
+        self.start <= t && t <= self.end
+    }
+
+    /// Frame index of the start of the entry at the given frame rate.
+    pub fn start_frame(&self, fps: f32) -> u64 {
+        frame_index(self.start, fps)
+    }
+
+    /// Frame index of the end of the entry at the given frame rate.
+    pub fn end_frame(&self, fps: f32) -> u64 {
+        frame_index(self.end, fps)
+    }
+}
+
+/// Convert a time in seconds to a zero-based frame index at the given frame rate.
+pub fn frame_index(t: f32, fps: f32) -> u64 {
+    (t.max(0.0) * fps).floor() as u64
+}
+
+/// Sorted, merged time ranges built from video clip annotations.
+///
+/// Deserialized timelines are sorted and merged the same way.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(from = "TimelineEntries")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Timeline {
+    entries: Vec<TimelineEntry>,
+}
+
+/// Serialized form of a [`Timeline`], whose entries may be unsorted or overlapping.
+#[derive(Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+struct TimelineEntries {
+    entries: Vec<TimelineEntry>,
+}
+
+impl From<TimelineEntries> for Timeline {
+    fn from(timeline: TimelineEntries) -> Self {
+        Self::from_entries(timeline.entries)
+    }
+}
+
+impl Timeline {
+    /// Build a timeline from clips, turning moments into one-second ranges.
+    pub fn from_clips(clips: &[Clip]) -> Self {
+        Self::from_clips_with(clips, DEFAULT_MOMENT_DURATION)
+    }
+
+    /// Build a timeline from clips, turning moments into ranges of `moment_duration` seconds.
+    ///
+    /// Overlapping or touching ranges with the same mention are merged into one entry.
+    pub fn from_clips_with(clips: &[Clip], moment_duration: f32) -> Self {
+        let entries = clips
+            .iter()
+            .map(|clip| {
+                let (start, end) = match clip.timestamp {
+                    ClipTimestamp::Moment(t) => (t, t + moment_duration),
+                    ClipTimestamp::Range { start, end } => (start, end),
+                };
+                TimelineEntry {
+                    mention: clip.mention.clone(),
+                    start,
+                    end,
+                }
+            })
+            .collect();
+        Self::from_entries(entries)
+    }
+
+    /// Order each entry's bounds, merge overlapping or touching entries with the same
+    /// mention, and sort by start time.
+    fn from_entries(mut entries: Vec<TimelineEntry>) -> Self {
+        for entry in &mut entries {
+            if entry.start > entry.end {
+                std::mem::swap(&mut entry.start, &mut entry.end);
+            }
+        }
+        entries.sort_by(|a, b| a.mention.cmp(&b.mention).then(a.start.total_cmp(&b.start)));
+        let mut merged: Vec<TimelineEntry> = Vec::with_capacity(entries.len());
+        for entry in entries {
+            match merged.last_mut() {
+                Some(last) if last.mention == entry.mention && entry.start <= last.end => {
+                    last.end = last.end.max(entry.end);
+                }
+                _ => merged.push(entry),
+            }
+        }
+        merged.sort_by(|a, b| a.start.total_cmp(&b.start).then(a.end.total_cmp(&b.end)));
+
+        Self { entries: merged }
+    }
+
+    /// Entries sorted by start time.
+    pub fn entries(&self) -> &[TimelineEntry] {
+        &self.entries
+    }
+
+    /// Whether the timeline has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Entries covering time `t`.
+    pub fn at(&self, t: f32) -> impl Iterator<Item = &TimelineEntry> {
+        self.entries.iter().filter(move |e| e.contains(t))
+    }
+
+    /// Entries overlapping the range from `start` to `end`.
+    pub fn between(&self, start: f32, end: f32) -> impl Iterator<Item = &TimelineEntry> {
+        self.entries.iter().filter(move |e| e.start <= end && e.end >= start)
+    }
+
+    /// Entries with a mention and its text, for exports that need text on every entry.
+    fn labeled(&self) -> impl Iterator<Item = (&TimelineEntry, &str)> {
+        self.entries
+            .iter()
+            .filter_map(|e| e.mention.as_deref().filter(|m| !m.trim().is_empty()).map(|m| (e, m)))
+    }
+
+    /// Export as SubRip (SRT) subtitles. Entries without a mention have no text to show and
+    /// are left out.
+    pub fn to_srt(&self) -> String {
+        let mut out = String::new();
+        for (i, (entry, mention)) in self.labeled().enumerate() {
+            let _ = writeln!(
+                out,
+                "{}\n{} --> {}\n{}\n",
+                i + 1,
+                subtitle_time(entry.start, ','),
+                subtitle_time(entry.end, ','),
+                mention
+            );
+        }
+        out
+    }
+
+    /// Export as WebVTT subtitles. Entries without a mention have no text to show and are
+    /// left out.
+    pub fn to_webvtt(&self) -> String {
+        let mut out = String::from("WEBVTT\n\n");
+        for (entry, mention) in self.labeled() {
+            let _ = writeln!(
+                out,
+                "{} --> {}\n{}\n",
+                subtitle_time(entry.start, '.'),
+                subtitle_time(entry.end, '.'),
+                mention
+            );
+        }
+        out
+    }
+
+    /// Export as a YouTube-style chapter list, one `m:ss Title` line per entry.
+    ///
+    /// YouTube requires the first chapter to start at `0:00`, so an `Intro` chapter is
+    /// inserted when the first entry starts later.
+    pub fn to_chapters(&self) -> String {
+        let mut out = String::new();
+        if self.entries.first().is_some_and(|e| chapter_time(e.start) != "0:00") {
+            out.push_str("0:00 Intro\n");
+        }
+        for entry in &self.entries {
+            let _ = writeln!(
+                out,
+                "{} {}",
+                chapter_time(entry.start),
+                entry.mention.as_deref().unwrap_or("Untitled")
+            );
+        }
+        out
+    }
+
+    /// Export as a CMX3600 edit decision list at the given frame rate.
+    ///
+    /// Each entry becomes a video cut from reel `AX`, laid back to back on the record side.
+    pub fn to_edl(&self, title: &str, fps: f32) -> String {
+        let mut out = format!("TITLE: {title}\nFCM: NON-DROP FRAME\n\n");
+        let mut record = 0;
+        for (i, entry) in self.entries.iter().enumerate() {
+            let source_in = entry.start_frame(fps);
+            let source_out = entry.end_frame(fps);
+            let record_out = record + (source_out - source_in);
+            let _ = writeln!(
+                out,
+                "{:03}  AX       V     C        {} {} {} {}",
+                i + 1,
+                timecode(source_in, fps),
+                timecode(source_out, fps),
+                timecode(record, fps),
+                timecode(record_out, fps)
+            );
+            if let Some(mention) = &entry.mention {
+                let _ = writeln!(out, "* FROM CLIP NAME: {mention}");
+            }
+            out.push('\n');
+            record = record_out;
+        }
+        out
+    }
+}
+
+/// Format seconds as `HH:MM:SS,mmm` (SRT) or `HH:MM:SS.mmm` (WebVTT).
+fn subtitle_time(t: f32, separator: char) -> String {
+    let millis = (t.max(0.0) * 1000.0).round() as u64;
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        separator,
+        millis % 1000
+    )
+}
+
+/// Format seconds as `m:ss`, or `h:mm:ss` past the first hour.
+fn chapter_time(t: f32) -> String {
+    let secs = t.max(0.0).floor() as u64;
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+/// Format a frame index as a non-drop-frame `HH:MM:SS:FF` timecode.
+fn timecode(frame: u64, fps: f32) -> String {
+    let rate = (fps.round() as u64).max(1);
+    let secs = frame / rate;
+    format!(
+        "{:02}:{:02}:{:02}:{:02}",
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60,
+        frame % rate
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clip(mention: &str, timestamp: ClipTimestamp) -> Clip {
+        Clip {
+            mention: Some(mention.to_string()),
+            timestamp,
+        }
+    }
+
+    fn sample() -> Timeline {
+        Timeline::from_clips(&[
+            clip("goal", ClipTimestamp::Range { start: 10.0, end: 20.0 }),
+            clip("intro", ClipTimestamp::Range { start: 0.0, end: 5.0 }),
+            clip("goal", ClipTimestamp::Range { start: 15.0, end: 25.0 }),
+            clip("whistle", ClipTimestamp::Moment(18.0)),
+        ])
+    }
+
+    #[test]
+    fn merges_and_sorts() {
+        let timeline = sample();
+        let summary: Vec<_> = timeline
+            .entries()
+            .iter()
+            .map(|e| (e.mention.as_deref().unwrap(), e.start, e.end))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![("intro", 0.0, 5.0), ("goal", 10.0, 25.0), ("whistle", 18.0, 19.0)]
+        );
+    }
+
+    #[test]
+    fn queries_by_time() {
+        let timeline = sample();
+        assert_eq!(timeline.at(18.5).count(), 2);
+        assert_eq!(timeline.at(7.0).count(), 0);
+        assert_eq!(timeline.between(4.0, 11.0).count(), 2);
+    }
+
+    #[test]
+    fn frame_indices() {
+        let timeline = sample();
+        let entry = &timeline.entries()[1];
+        assert_eq!(entry.start_frame(30.0), 300);
+        assert_eq!(entry.end_frame(30.0), 750);
+        assert_eq!(frame_index(1.5, 24.0), 36);
+    }
+
+    #[test]
+    fn srt_and_webvtt() {
+        let timeline = Timeline::from_clips(&[clip(
+            "goal",
+            ClipTimestamp::Range {
+                start: 1.5,
+                end: 3661.25,
+            },
+        )]);
+        assert_eq!(timeline.to_srt(), "1\n00:00:01,500 --> 01:01:01,250\ngoal\n\n");
+        assert_eq!(
+            timeline.to_webvtt(),
+            "WEBVTT\n\n00:00:01.500 --> 01:01:01.250\ngoal\n\n"
+        );
+    }
+
+    #[test]
+    fn subtitles_skip_unlabeled_entries() {
+        let timeline = Timeline::from_clips(&[
+            Clip {
+                mention: None,
+                timestamp: ClipTimestamp::Range { start: 0.0, end: 1.0 },
+            },
+            clip("goal", ClipTimestamp::Range { start: 2.0, end: 3.0 }),
+        ]);
+        assert_eq!(timeline.to_srt(), "1\n00:00:02,000 --> 00:00:03,000\ngoal\n\n");
+        assert_eq!(
+            timeline.to_webvtt(),
+            "WEBVTT\n\n00:00:02.000 --> 00:00:03.000\ngoal\n\n"
+        );
+    }
+
+    #[test]
+    fn deserializes_sorted_and_merged() {
+        let timeline: Timeline = serde_json::from_str(
+            r#"{"entries": [
+                {"mention": "goal", "start": 15.0, "end": 25.0},
+                {"mention": "intro", "start": 5.0, "end": 0.0},
+                {"mention": "goal", "start": 10.0, "end": 20.0}
+            ]}"#,
+        )
+        .expect("timeline");
+        let summary: Vec<_> = timeline
+            .entries()
+            .iter()
+            .map(|e| (e.mention.as_deref().unwrap(), e.start, e.end))
+            .collect();
+        assert_eq!(summary, vec![("intro", 0.0, 5.0), ("goal", 10.0, 25.0)]);
+        assert_eq!(
+            serde_json::from_value::<Timeline>(serde_json::to_value(&timeline).unwrap()).unwrap(),
+            timeline
+        );
+    }
+
+    #[test]
+    fn chapters() {
+        assert_eq!(sample().to_chapters(), "0:00 intro\n0:10 goal\n0:18 whistle\n");
+        let late = Timeline::from_clips(&[clip("kickoff", ClipTimestamp::Moment(3725.0))]);
+        assert_eq!(late.to_chapters(), "0:00 Intro\n1:02:05 kickoff\n");
+    }
+
+    #[test]
+    fn edl() {
+        let timeline = Timeline::from_clips(&[clip("goal", ClipTimestamp::Range { start: 1.0, end: 2.5 })]);
+        assert_eq!(
+            timeline.to_edl("Match", 24.0),
+            "TITLE: Match\nFCM: NON-DROP FRAME\n\n\
+             001  AX       V     C        00:00:01:00 00:00:02:12 00:00:00:00 00:00:01:12\n\
+             * FROM CLIP NAME: goal\n\n"
+        );
+    }
+}
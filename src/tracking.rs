@@ -0,0 +1,361 @@
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::pointing::{BoundingBox, Point, Pointing};
+
+/// A single timestamped observation of a tracked object.
+///
+/// Points are represented as degenerate boxes where `x1 == x2` and `y1 == y2`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Observation {
+    /// Timestamp in seconds.
+    pub timestamp: f32,
+    /// Top-left X coordinate.
+    pub x1: u32,
+    /// Top-left Y coordinate.
+    pub y1: u32,
+    /// Bottom-right X coordinate.
+    pub x2: u32,
+    /// Bottom-right Y coordinate.
+    pub y2: u32,
+}
+
+impl Observation {
+    /// Center of the observation as `(x, y)`.
+    pub fn center(&self) -> (f32, f32) {
+        ((self.x1 + self.x2) as f32 / 2.0, (self.y1 + self.y2) as f32 / 2.0)
+    }
+
+    /// Width of the observation (zero for points).
+    pub fn width(&self) -> u32 {
+        self.x2.saturating_sub(self.x1)
+    }
+
+    /// Height of the observation (zero for points).
+    pub fn height(&self) -> u32 {
+        self.y2.saturating_sub(self.y1)
+    }
+
+    fn is_point(&self) -> bool {
+        self.width() == 0 && self.height() == 0
+    }
+
+    fn area(&self) -> f32 {
+        self.width() as f32 * self.height() as f32
+    }
+
+    /// Intersection over union with another observation. Always zero for points.
+    pub fn iou(&self, other: &Observation) -> f32 {
+        let ix = self.x2.min(other.x2).saturating_sub(self.x1.max(other.x1)) as f32;
+        let iy = self.y2.min(other.y2).saturating_sub(self.y1.max(other.y1)) as f32;
+        let intersection = ix * iy;
+        let union = self.area() + other.area() - intersection;
+        if union > 0.0 { intersection / union } else { 0.0 }
+    }
+
+    fn distance(&self, other: &Observation) -> f32 {
+        let (ax, ay) = self.center();
+        let (bx, by) = other.center();
+        ((ax - bx).powi(2) + (ay - by).powi(2)).sqrt()
+    }
+
+    fn from_box(b: &BoundingBox) -> Option<Self> {
+        Some(Self {
+            timestamp: b.timestamp?,
+            x1: b.x1,
+            y1: b.y1,
+            x2: b.x2,
+            y2: b.y2,
+        })
+    }
+
+    fn from_point(p: &Point) -> Option<Self> {
+        Some(Self {
+            timestamp: p.timestamp?,
+            x1: p.x,
+            y1: p.y,
+            x2: p.x,
+            y2: p.y,
+        })
+    }
+}
+
+/// An object followed across time, built from timestamped video annotations.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Track {
+    /// Label shared by every observation in the track.
+    pub label: Option<String>,
+    /// Observations ordered by timestamp.
+    pub observations: Vec<Observation>,
+}
+
+impl Track {
+    /// Timestamp of the first observation, in seconds.
+    pub fn start(&self) -> Option<f32> {
+        self.observations.first().map(|o| o.timestamp)
+    }
+
+    /// Timestamp of the last observation, in seconds.
+    pub fn end(&self) -> Option<f32> {
+        self.observations.last().map(|o| o.timestamp)
+    }
+
+    /// Linearly interpolate the track position at time `t`.
+    /// Returns `None` when `t` falls outside the track's time span.
+    pub fn at(&self, t: f32) -> Option<Observation> {
+        let next = self.observations.iter().position(|o| o.timestamp >= t)?;
+        let b = &self.observations[next];
+        if b.timestamp == t {
+            return Some(b.clone());
+        }
+        let a = &self.observations[next.checked_sub(1)?];
+        let ratio = (t - a.timestamp) / (b.timestamp - a.timestamp);
+        let lerp = |from: u32, to: u32| (from as f32 + (to as f32 - from as f32) * ratio).round() as u32;
+        Some(Observation {
+            timestamp: t,
+            x1: lerp(a.x1, b.x1),
+            y1: lerp(a.y1, b.y1),
+            x2: lerp(a.x2, b.x2),
+            y2: lerp(a.y2, b.y2),
+        })
+    }
+}
+
+/// Thresholds used to decide whether an annotation continues an existing track.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackOptions {
+    /// Minimum IoU for a box to continue a track.
+    pub min_iou: f32,
+    /// Maximum center distance for a point to continue a track, in model coordinates.
+    pub max_distance: f32,
+    /// Maximum time gap in seconds before a track is considered lost.
+    pub max_gap: Option<f32>,
+}
+
+impl Default for TrackOptions {
+    fn default() -> Self {
+        Self {
+            min_iou: 0.3,
+            max_distance: 100.0,
+            max_gap: None,
+        }
+    }
+}
+
+impl TrackOptions {
+    /// Set the minimum IoU for a box to continue a track.
+    pub fn min_iou(mut self, min_iou: f32) -> Self {
+        self.min_iou = min_iou;
+        self
+    }
+
+    /// Set the maximum center distance for a point to continue a track.
+    pub fn max_distance(mut self, max_distance: f32) -> Self {
+        self.max_distance = max_distance;
+        self
+    }
+
+    /// Set the maximum time gap before a track is considered lost.
+    pub fn max_gap(mut self, max_gap: f32) -> Self {
+        self.max_gap = Some(max_gap);
+        self
+    }
+
+    /// Score how well `candidate` continues from `last`, or `None` if it does not.
+    fn score(&self, last: &Observation, candidate: &Observation) -> Option<f32> {
+        if candidate.timestamp <= last.timestamp {
+            return None;
+        }
+        if self
+            .max_gap
+            .is_some_and(|gap| candidate.timestamp - last.timestamp > gap)
+        {
+            return None;
+        }
+        if last.is_point() || candidate.is_point() {
+            let distance = last.distance(candidate);
+            (distance <= self.max_distance).then_some(self.max_distance - distance)
+        } else {
+            let iou = last.iou(candidate);
+            (iou >= self.min_iou).then_some(iou)
+        }
+    }
+}
+
+impl Pointing {
+    /// Group timestamped boxes and points into tracks using the default [`TrackOptions`].
+    pub fn tracks(&self) -> Vec<Track> {
+        self.tracks_with(&TrackOptions::default())
+    }
+
+    /// Group timestamped boxes and points into tracks by mention and spatial continuity.
+    ///
+    /// Annotations without a timestamp are ignored. Boxes and points never share a track.
+    pub fn tracks_with(&self, options: &TrackOptions) -> Vec<Track> {
+        let boxes = self
+            .boxes
+            .iter()
+            .filter_map(|b| Some((b.mention.clone(), Observation::from_box(b)?)));
+        let points = self
+            .points
+            .iter()
+            .filter_map(|p| Some((p.mention.clone(), Observation::from_point(p)?)));
+        let mut tracks = associate(boxes.collect(), options);
+        tracks.extend(associate(points.collect(), options));
+        tracks
+    }
+}
+
+/// Greedily link observations into tracks, visiting them in time order.
+fn associate(mut items: Vec<(Option<String>, Observation)>, options: &TrackOptions) -> Vec<Track> {
+    items.sort_by(|a, b| a.1.timestamp.total_cmp(&b.1.timestamp));
+
+    let mut tracks: Vec<Track> = Vec::new();
+    for (label, observation) in items {
+        let best = tracks
+            .iter()
+            .enumerate()
+            .filter(|(_, track)| track.label == label)
+            .filter_map(|(i, track)| {
+                let last = track.observations.last()?;
+                Some((i, options.score(last, &observation)?))
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        match best {
+            Some((i, _)) => tracks[i].observations.push(observation),
+            None => tracks.push(Track {
+                label,
+                observations: vec![observation],
+            }),
+        }
+    }
+    tracks
+}
+
+/// Export tracks in the MOT Challenge CSV format.
+///
+/// Each row is `frame,id,bb_left,bb_top,bb_width,bb_height,conf,x,y,z`, with 1-based frame
+/// numbers computed from `fps` and 1-based track ids following the order of `tracks`.
+pub fn to_mot_csv(tracks: &[Track], fps: f32) -> String {
+    let mut out = String::new();
+    for (i, track) in tracks.iter().enumerate() {
+        for o in &track.observations {
+            let frame = (o.timestamp * fps).round() as u64 + 1;
+            // Writing to a String cannot fail.
+            let _ = writeln!(
+                out,
+                "{},{},{},{},{},{},1,-1,-1,-1",
+                frame,
+                i + 1,
+                o.x1,
+                o.y1,
+                o.width(),
+                o.height()
+            );
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bbox(x1: u32, y1: u32, x2: u32, y2: u32, mention: &str, t: f32) -> BoundingBox {
+        BoundingBox {
+            x1,
+            y1,
+            x2,
+            y2,
+            mention: Some(mention.to_string()),
+            timestamp: Some(t),
+        }
+    }
+
+    #[test]
+    fn tracks_split_by_mention_and_continuity() {
+        let pointing = Pointing {
+            boxes: vec![
+                bbox(0, 0, 100, 100, "car", 0.0),
+                bbox(500, 500, 600, 600, "car", 0.0),
+                bbox(10, 0, 110, 100, "car", 1.0),
+                bbox(510, 500, 610, 600, "car", 1.0),
+                bbox(0, 0, 100, 100, "person", 1.0),
+            ],
+            ..Default::default()
+        };
+        let tracks = pointing.tracks();
+        assert_eq!(tracks.len(), 3);
+        assert_eq!(tracks[0].observations.len(), 2);
+        assert_eq!(tracks[0].observations[1].x1, 10);
+        assert_eq!(tracks[1].observations[1].x1, 510);
+        assert_eq!(tracks[2].label.as_deref(), Some("person"));
+    }
+
+    #[test]
+    fn untimed_annotations_are_ignored() {
+        let pointing = Pointing {
+            points: vec![Point {
+                x: 1,
+                y: 2,
+                mention: None,
+                timestamp: None,
+            }],
+            ..Default::default()
+        };
+        assert!(pointing.tracks().is_empty());
+    }
+
+    #[test]
+    fn max_gap_breaks_tracks() {
+        let pointing = Pointing {
+            boxes: vec![bbox(0, 0, 100, 100, "car", 0.0), bbox(0, 0, 100, 100, "car", 5.0)],
+            ..Default::default()
+        };
+        assert_eq!(pointing.tracks().len(), 1);
+        assert_eq!(pointing.tracks_with(&TrackOptions::default().max_gap(2.0)).len(), 2);
+    }
+
+    #[test]
+    fn interpolation_and_span() {
+        let track = Track {
+            label: None,
+            observations: vec![
+                Observation {
+                    timestamp: 1.0,
+                    x1: 0,
+                    y1: 0,
+                    x2: 100,
+                    y2: 100,
+                },
+                Observation {
+                    timestamp: 3.0,
+                    x1: 100,
+                    y1: 50,
+                    x2: 200,
+                    y2: 150,
+                },
+            ],
+        };
+        assert_eq!(track.start(), Some(1.0));
+        assert_eq!(track.end(), Some(3.0));
+        let mid = track.at(2.0).expect("inside span");
+        assert_eq!((mid.x1, mid.y1, mid.x2, mid.y2), (50, 25, 150, 125));
+        assert_eq!(track.at(3.0), Some(track.observations[1].clone()));
+        assert_eq!(track.at(0.5), None);
+        assert_eq!(track.at(3.5), None);
+    }
+
+    #[test]
+    fn mot_csv_export() {
+        let pointing = Pointing {
+            boxes: vec![bbox(10, 20, 110, 220, "car", 0.0), bbox(12, 20, 112, 220, "car", 0.5)],
+            ..Default::default()
+        };
+        let csv = to_mot_csv(&pointing.tracks(), 30.0);
+        assert_eq!(csv, "1,1,10,20,100,200,1,-1,-1,-1\n16,1,12,20,100,200,1,-1,-1,-1\n");
+    }
+}
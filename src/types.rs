@@ -1,7 +1,17 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
-use crate::media::{Image, Media};
-use crate::pointing::Pointing;
+use crate::chapters::Chapter;
+use crate::classification::LabelScore;
+use crate::comparison::Change;
+use crate::counting::ClassCount;
+use crate::document::{DocumentField, Table};
+use crate::ensemble::{Ensemble, EnsembleStats};
+use crate::events::{EventHit, KeyFrameFormat};
+use crate::media::{Image, Media, Video};
+use crate::pointing::{Pointing, Region};
+use crate::tagging::{Tag, Taxonomy};
 
 /// Output format for model responses. `None` on a request means a plain text response;
 /// any variant here triggers spatial or temporal annotation extraction.
@@ -29,6 +39,110 @@ pub enum CaptionStyle {
     Concise,
     /// A thorough, detailed description.
     Detailed,
+    /// Accessibility alt text following WCAG guidance. Defaults to under 125 characters.
+    AltText,
+    /// A product-listing description covering type, color, material and features.
+    Product,
+    /// An engaging caption for social media posts.
+    Social,
+    /// One sentence per distinct region of the media.
+    DenseRegion,
+}
+
+/// Unit used to measure caption length.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum LengthUnit {
+    /// Whitespace-separated words.
+    Words,
+    /// Unicode characters.
+    Characters,
+}
+
+/// Target length range for a caption, measured on the prose with annotation tags removed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CaptionLength {
+    /// Unit the bounds are measured in.
+    pub unit: LengthUnit,
+    /// Minimum length (inclusive).
+    pub min: u32,
+    /// Maximum length (inclusive).
+    pub max: u32,
+}
+
+impl CaptionLength {
+    /// A range measured in words.
+    pub fn words(min: u32, max: u32) -> Self {
+        Self {
+            unit: LengthUnit::Words,
+            min,
+            max,
+        }
+    }
+
+    /// A range measured in characters.
+    pub fn characters(min: u32, max: u32) -> Self {
+        Self {
+            unit: LengthUnit::Characters,
+            min,
+            max,
+        }
+    }
+
+    /// Measure `text` in this range's unit.
+    pub fn measure(&self, text: &str) -> u32 {
+        let len = match self.unit {
+            LengthUnit::Words => text.split_whitespace().count(),
+            LengthUnit::Characters => text.chars().count(),
+        };
+        u32::try_from(len).unwrap_or(u32::MAX)
+    }
+
+    /// Whether `text` falls within the range.
+    pub fn contains(&self, text: &str) -> bool {
+        (self.min..=self.max).contains(&self.measure(text))
+    }
+
+    /// Shorten `text` to at most `max`, cutting at a word boundary.
+    pub fn truncate(&self, text: &str) -> String {
+        let max = self.max as usize;
+        match self.unit {
+            LengthUnit::Words => text.split_whitespace().take(max).collect::<Vec<_>>().join(" "),
+            LengthUnit::Characters => {
+                let text = text.trim();
+                if text.chars().count() <= max {
+                    return text.to_string();
+                }
+                // Byte offset just past the last character that fits.
+                let end = text.char_indices().nth(max).map_or(text.len(), |(i, _)| i);
+                let cut = &text[..end];
+                // Drop the partial word at the cut unless the cut falls on a word boundary.
+                let cut = if text[end..].starts_with(char::is_whitespace) {
+                    cut
+                } else {
+                    cut.rfind(char::is_whitespace).map_or(cut, |i| &cut[..i])
+                };
+                cut.trim_end().to_string()
+            }
+        }
+    }
+}
+
+/// What the SDK does when a caption falls outside its [`CaptionLength`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum LengthPolicy {
+    /// Truncate captions that are too long (default). Short captions are returned as-is.
+    #[default]
+    Truncate,
+    /// Re-request up to `attempts` more times, then truncate if still too long.
+    Retry {
+        /// Maximum number of additional requests.
+        attempts: u32,
+    },
 }
 
 /// Output mode for OCR requests.
@@ -43,6 +157,73 @@ pub enum OcrMode {
     Markdown,
     /// HTML markup output.
     Html,
+    /// One bounding box per text line, for [`Perceptron::ocr_layout`](crate::Perceptron::ocr_layout).
+    Layout,
+}
+
+/// Expected answer for a [`FewShotExample`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum FewShotAnswer {
+    /// A plain text answer, sent verbatim.
+    Text(String),
+    /// Annotations, sent in the model's tag syntax (e.g. `<point_box>`).
+    Pointing(Pointing),
+}
+
+impl From<String> for FewShotAnswer {
+    fn from(text: String) -> Self {
+        FewShotAnswer::Text(text)
+    }
+}
+
+impl From<&str> for FewShotAnswer {
+    fn from(text: &str) -> Self {
+        FewShotAnswer::Text(text.to_string())
+    }
+}
+
+impl From<Pointing> for FewShotAnswer {
+    fn from(pointing: Pointing) -> Self {
+        FewShotAnswer::Pointing(pointing)
+    }
+}
+
+impl FewShotAnswer {
+    /// Render the answer as assistant message text.
+    pub fn to_text(&self) -> String {
+        match self {
+            FewShotAnswer::Text(text) => text.clone(),
+            FewShotAnswer::Pointing(pointing) => pointing.to_tags(),
+        }
+    }
+}
+
+/// A worked example shown to the model before the actual request.
+///
+/// Each example is sent as a user turn (the media, then the prompt if non-empty)
+/// followed by an assistant turn containing the expected answer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct FewShotExample {
+    /// Example media.
+    pub media: Media,
+    /// Example user prompt. Empty prompts send the media alone.
+    pub prompt: String,
+    /// Expected answer for the example.
+    pub answer: FewShotAnswer,
+}
+
+impl FewShotExample {
+    /// Create a new few-shot example.
+    pub fn new(media: impl Into<Media>, prompt: impl Into<String>, answer: impl Into<FewShotAnswer>) -> Self {
+        Self {
+            media: media.into(),
+            prompt: prompt.into(),
+            answer: answer.into(),
+        }
+    }
 }
 
 /// Generate generation parameter setter methods on a request struct.
@@ -92,6 +273,32 @@ macro_rules! generation_param_setters {
     };
 }
 
+/// Generate prompt customization setter methods on a request struct.
+macro_rules! prompt_setters {
+    () => {
+        /// Replace the default system instruction for this request.
+        ///
+        /// The `<hint>` system message derived from the output format and reasoning
+        /// setting is still sent first.
+        pub fn system_prompt(mut self, prompt: impl Into<String>) -> Self {
+            self.system_prompt = Some(prompt.into());
+            self
+        }
+
+        /// Append a system message after the default or overridden instruction.
+        pub fn extra_instructions(mut self, instructions: impl Into<String>) -> Self {
+            self.extra_instructions = Some(instructions.into());
+            self
+        }
+
+        /// Show worked examples to the model before the request.
+        pub fn few_shot(mut self, examples: Vec<FewShotExample>) -> Self {
+            self.few_shot = examples;
+            self
+        }
+    };
+}
+
 /// Parameters for a visual question answering request.
 ///
 /// Use [`QuestionRequest::new`] to create a request with required fields,
@@ -105,6 +312,21 @@ pub struct QuestionRequest {
     pub media: Media,
     /// Output format for the response.
     pub output_format: Option<OutputFormat>,
+    /// Language for the response prose (e.g. `"Japanese"`). Tag mention labels stay canonical.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_language: Option<String>,
+    /// Sample the model several times and keep the consensus.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ensemble: Option<Ensemble>,
+    /// System instruction replacing the profile default for this request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    /// Additional system instructions appended after the default or overridden instruction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extra_instructions: Option<String>,
+    /// Worked examples sent as prior conversation turns.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub few_shot: Vec<FewShotExample>,
     /// Model to use for the request.
     pub model: String,
     /// Whether to enable chain-of-thought reasoning.
@@ -130,6 +352,11 @@ impl QuestionRequest {
             question: question.into(),
             media: media.into(),
             output_format: None,
+            output_language: None,
+            ensemble: None,
+            system_prompt: None,
+            extra_instructions: None,
+            few_shot: Vec::new(),
             model: model.into(),
             reasoning: None,
             temperature: None,
@@ -147,6 +374,24 @@ impl QuestionRequest {
         self
     }
 
+    /// Set the language for the response prose. Tag mention labels are kept canonical.
+    ///
+    /// This only adds a language instruction; it does not switch to a translated prompt
+    /// profile such as [`PromptProfile::ISAAC_DE`](crate::PromptProfile::ISAAC_DE).
+    pub fn output_language(mut self, language: impl Into<String>) -> Self {
+        self.output_language = Some(language.into());
+        self
+    }
+
+    /// Sample the model several times and keep what the samples agree on.
+    /// Pass a sample count or an [`Ensemble`] for finer control.
+    pub fn ensemble(mut self, ensemble: impl Into<Ensemble>) -> Self {
+        self.ensemble = Some(ensemble.into());
+        self
+    }
+
+    prompt_setters!();
+
     generation_param_setters!();
 }
 
@@ -163,6 +408,15 @@ pub struct AnalyzeRequest {
     pub media: Media,
     /// Output format for the response.
     pub output_format: Option<OutputFormat>,
+    /// System instruction replacing the profile default for this request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    /// Additional system instructions appended after the default or overridden instruction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extra_instructions: Option<String>,
+    /// Worked examples sent as prior conversation turns.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub few_shot: Vec<FewShotExample>,
     /// Model to use for the request.
     pub model: String,
     /// Whether to enable chain-of-thought reasoning.
@@ -188,6 +442,9 @@ impl AnalyzeRequest {
             message: message.into(),
             media: media.into(),
             output_format: None,
+            system_prompt: None,
+            extra_instructions: None,
+            few_shot: Vec::new(),
             model: model.into(),
             reasoning: None,
             temperature: None,
@@ -205,6 +462,8 @@ impl AnalyzeRequest {
         self
     }
 
+    prompt_setters!();
+
     generation_param_setters!();
 }
 
@@ -221,6 +480,30 @@ pub struct CaptionRequest {
     pub style: CaptionStyle,
     /// Output format for the response (defaults to Box).
     pub output_format: Option<OutputFormat>,
+    /// Skip pointing tags and return a pure-text caption.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_only: Option<bool>,
+    /// Target caption length. [`CaptionStyle::AltText`] defaults to under 125 characters.
+    ///
+    /// When a length applies, the response `content` is the caption prose with annotation
+    /// tags removed; `pointing` still holds the annotations.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub length: Option<CaptionLength>,
+    /// How the target length is enforced.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub length_policy: Option<LengthPolicy>,
+    /// Language for the response prose (e.g. `"Japanese"`). Tag mention labels stay canonical.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_language: Option<String>,
+    /// System instruction replacing the profile default for this request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    /// Additional system instructions appended after the default or overridden instruction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extra_instructions: Option<String>,
+    /// Worked examples sent as prior conversation turns.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub few_shot: Vec<FewShotExample>,
     /// Model to use for the request.
     pub model: String,
     /// Whether to enable chain-of-thought reasoning.
@@ -246,6 +529,13 @@ impl CaptionRequest {
             media: media.into(),
             style: CaptionStyle::default(),
             output_format: None,
+            text_only: None,
+            length: None,
+            length_policy: None,
+            output_language: None,
+            system_prompt: None,
+            extra_instructions: None,
+            few_shot: Vec::new(),
             model: model.into(),
             reasoning: None,
             temperature: None,
@@ -269,6 +559,36 @@ impl CaptionRequest {
         self
     }
 
+    /// Skip pointing tags and return a pure-text caption. The `<hint>` system message
+    /// then only carries the reasoning flag, as for other text-only requests.
+    pub fn text_only(mut self, enable: bool) -> Self {
+        self.text_only = Some(enable);
+        self
+    }
+
+    /// Set the target caption length.
+    pub fn length(mut self, length: CaptionLength) -> Self {
+        self.length = Some(length);
+        self
+    }
+
+    /// Set how the target length is enforced.
+    pub fn length_policy(mut self, policy: LengthPolicy) -> Self {
+        self.length_policy = Some(policy);
+        self
+    }
+
+    /// Set the language for the response prose. Tag mention labels are kept canonical.
+    ///
+    /// This only adds a language instruction; it does not switch to a translated prompt
+    /// profile such as [`PromptProfile::ISAAC_DE`](crate::PromptProfile::ISAAC_DE).
+    pub fn output_language(mut self, language: impl Into<String>) -> Self {
+        self.output_language = Some(language.into());
+        self
+    }
+
+    prompt_setters!();
+
     generation_param_setters!();
 }
 
@@ -286,6 +606,15 @@ pub struct OcrRequest {
     /// Custom prompt to override the default OCR instruction.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub prompt: Option<String>,
+    /// System instruction replacing the profile default for this request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    /// Additional system instructions appended after the default or overridden instruction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extra_instructions: Option<String>,
+    /// Worked examples sent as prior conversation turns.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub few_shot: Vec<FewShotExample>,
     /// Model to use for the request.
     pub model: String,
     /// Whether to enable chain-of-thought reasoning.
@@ -311,6 +640,9 @@ impl OcrRequest {
             image,
             mode: OcrMode::default(),
             prompt: None,
+            system_prompt: None,
+            extra_instructions: None,
+            few_shot: Vec::new(),
             model: model.into(),
             reasoning: None,
             temperature: None,
@@ -334,6 +666,8 @@ impl OcrRequest {
         self
     }
 
+    prompt_setters!();
+
     generation_param_setters!();
 }
 
@@ -348,6 +682,36 @@ pub struct DetectRequest {
     pub media: Media,
     /// Optional list of object categories to detect.
     pub classes: Option<Vec<String>>,
+    /// Descriptions for requested categories, keyed by category name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub class_descriptions: BTreeMap<String, String>,
+    /// Categories the model should not return.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub excluded_classes: Vec<String>,
+    /// Hint for the maximum number of instances to return per category.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_instances_per_class: Option<u32>,
+    /// Hint for the smallest object size to return, in output coordinates.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_size: Option<u32>,
+    /// Whether to drop returned boxes whose mention is not a requested category.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strict_classes: Option<bool>,
+    /// Language for the response prose (e.g. `"Japanese"`). Tag mention labels stay canonical.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_language: Option<String>,
+    /// Sample the model several times and keep the consensus.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ensemble: Option<Ensemble>,
+    /// System instruction replacing the profile default for this request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    /// Additional system instructions appended after the default or overridden instruction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extra_instructions: Option<String>,
+    /// Worked examples sent as prior conversation turns.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub few_shot: Vec<FewShotExample>,
     /// Model to use for the request.
     pub model: String,
     /// Whether to enable chain-of-thought reasoning.
@@ -372,6 +736,16 @@ impl DetectRequest {
         Self {
             media: media.into(),
             classes: None,
+            class_descriptions: BTreeMap::new(),
+            excluded_classes: Vec::new(),
+            max_instances_per_class: None,
+            min_size: None,
+            strict_classes: None,
+            output_language: None,
+            ensemble: None,
+            system_prompt: None,
+            extra_instructions: None,
+            few_shot: Vec::new(),
             model: model.into(),
             reasoning: None,
             temperature: None,
@@ -389,27 +763,977 @@ impl DetectRequest {
         self
     }
 
+    /// Add a category with a description (e.g. `"helmet"`, `"hard hat worn on head"`).
+    /// The category is appended to [`Self::classes`] if not already present.
+    pub fn describe_class(mut self, class: impl Into<String>, description: impl Into<String>) -> Self {
+        let class = class.into();
+        let classes = self.classes.get_or_insert_with(Vec::new);
+        if !classes.contains(&class) {
+            classes.push(class.clone());
+        }
+        self.class_descriptions.insert(class, description.into());
+        self
+    }
+
+    /// Set categories the model should not return.
+    pub fn excluded_classes(mut self, classes: Vec<String>) -> Self {
+        self.excluded_classes = classes;
+        self
+    }
+
+    /// Hint the maximum number of instances to return per category.
+    pub fn max_instances_per_class(mut self, count: u32) -> Self {
+        self.max_instances_per_class = Some(count);
+        self
+    }
+
+    /// Hint the smallest object size to return, in output coordinates.
+    pub fn min_size(mut self, size: u32) -> Self {
+        self.min_size = Some(size);
+        self
+    }
+
+    /// Drop returned boxes whose mention is not one of the requested classes
+    /// (compared case-insensitively), as well as boxes for excluded classes.
+    pub fn strict_classes(mut self, enable: bool) -> Self {
+        self.strict_classes = Some(enable);
+        self
+    }
+
+    /// Set the language for the response prose. Tag mention labels are kept canonical.
+    ///
+    /// This only adds a language instruction; it does not switch to a translated prompt
+    /// profile such as [`PromptProfile::ISAAC_DE`](crate::PromptProfile::ISAAC_DE).
+    pub fn output_language(mut self, language: impl Into<String>) -> Self {
+        self.output_language = Some(language.into());
+        self
+    }
+
+    /// Sample the model several times and keep what the samples agree on.
+    /// Pass a sample count or an [`Ensemble`] for finer control.
+    pub fn ensemble(mut self, ensemble: impl Into<Ensemble>) -> Self {
+        self.ensemble = Some(ensemble.into());
+        self
+    }
+
+    prompt_setters!();
+
     generation_param_setters!();
 }
 
-/// Response for text-only methods (ocr, question).
+/// Parameters for a zero-shot classification request.
+///
+/// Use [`ClassifyRequest::new`] to create a request with required fields,
+/// then chain optional setters using the builder pattern.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
-pub struct TextResponse {
-    /// The main response content from the model.
-    pub content: Option<String>,
-    /// Chain-of-thought reasoning content (if reasoning was enabled).
-    pub reasoning: Option<String>,
+pub struct ClassifyRequest {
+    /// Media to classify.
+    pub media: Media,
+    /// Candidate labels. Results only ever contain these labels or
+    /// [`UNKNOWN_LABEL`](crate::UNKNOWN_LABEL), which is not allowed as a candidate.
+    pub labels: Vec<String>,
+    /// Whether more than one label may apply.
+    pub multi_label: Option<bool>,
+    /// System instruction replacing the profile default for this request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    /// Additional system instructions appended after the default or overridden instruction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extra_instructions: Option<String>,
+    /// Worked examples sent as prior conversation turns.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub few_shot: Vec<FewShotExample>,
+    /// Model to use for the request.
+    pub model: String,
+    /// Whether to enable chain-of-thought reasoning.
+    pub reasoning: Option<bool>,
+    /// Sampling temperature.
+    pub temperature: Option<f32>,
+    /// Nucleus sampling probability.
+    pub top_p: Option<f32>,
+    /// Top-k sampling value.
+    pub top_k: Option<u32>,
+    /// Frequency penalty.
+    pub frequency_penalty: Option<f32>,
+    /// Presence penalty.
+    pub presence_penalty: Option<f32>,
+    /// Maximum number of tokens to generate.
+    pub max_tokens: Option<u32>,
 }
 
-/// Response for spatial methods (analyze, caption, detect).
+impl ClassifyRequest {
+    /// Create a new classification request.
+    pub fn new(model: impl Into<String>, media: impl Into<Media>, labels: Vec<String>) -> Self {
+        Self {
+            media: media.into(),
+            labels,
+            multi_label: None,
+            system_prompt: None,
+            extra_instructions: None,
+            few_shot: Vec::new(),
+            model: model.into(),
+            reasoning: None,
+            temperature: None,
+            top_p: None,
+            top_k: None,
+            frequency_penalty: None,
+            presence_penalty: None,
+            max_tokens: None,
+        }
+    }
+
+    /// Allow more than one label to apply.
+    pub fn multi_label(mut self, enable: bool) -> Self {
+        self.multi_label = Some(enable);
+        self
+    }
+
+    prompt_setters!();
+
+    generation_param_setters!();
+}
+
+/// Parameters for a counting request.
+///
+/// Use [`CountRequest::new`] to create a request with required fields,
+/// then chain optional setters using the builder pattern.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
-pub struct PointingResponse {
-    /// The main response content from the model.
-    pub content: Option<String>,
-    /// Chain-of-thought reasoning content (if reasoning was enabled).
-    pub reasoning: Option<String>,
-    /// Extracted spatial pointing data.
+pub struct CountRequest {
+    /// Media to count objects in.
+    pub media: Media,
+    /// Categories to count.
+    pub classes: Vec<String>,
+    /// Sample the model several times and keep the consensus.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ensemble: Option<Ensemble>,
+    /// System instruction replacing the profile default for this request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    /// Additional system instructions appended after the default or overridden instruction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extra_instructions: Option<String>,
+    /// Worked examples sent as prior conversation turns.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub few_shot: Vec<FewShotExample>,
+    /// Model to use for the request.
+    pub model: String,
+    /// Whether to enable chain-of-thought reasoning.
+    pub reasoning: Option<bool>,
+    /// Sampling temperature.
+    pub temperature: Option<f32>,
+    /// Nucleus sampling probability.
+    pub top_p: Option<f32>,
+    /// Top-k sampling value.
+    pub top_k: Option<u32>,
+    /// Frequency penalty.
+    pub frequency_penalty: Option<f32>,
+    /// Presence penalty.
+    pub presence_penalty: Option<f32>,
+    /// Maximum number of tokens to generate.
+    pub max_tokens: Option<u32>,
+}
+
+impl CountRequest {
+    /// Create a new counting request.
+    pub fn new(model: impl Into<String>, media: impl Into<Media>, classes: Vec<String>) -> Self {
+        Self {
+            media: media.into(),
+            classes,
+            ensemble: None,
+            system_prompt: None,
+            extra_instructions: None,
+            few_shot: Vec::new(),
+            model: model.into(),
+            reasoning: None,
+            temperature: None,
+            top_p: None,
+            top_k: None,
+            frequency_penalty: None,
+            presence_penalty: None,
+            max_tokens: None,
+        }
+    }
+
+    /// Sample the model several times and keep what the samples agree on.
+    /// Pass a sample count or an [`Ensemble`] for finer control.
+    pub fn ensemble(mut self, ensemble: impl Into<Ensemble>) -> Self {
+        self.ensemble = Some(ensemble.into());
+        self
+    }
+
+    prompt_setters!();
+
+    generation_param_setters!();
+}
+
+/// Parameters for a referring-expression grounding request.
+///
+/// Use [`GroundRequest::new`] to create a request with required fields,
+/// then chain optional setters using the builder pattern.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct GroundRequest {
+    /// Media to search.
+    pub media: Media,
+    /// Natural-language description of the target (e.g. `"the mug left of the laptop"`).
+    pub expression: String,
+    /// Whether to return polygons instead of bounding boxes.
+    pub polygon: Option<bool>,
+    /// Whether to ask for every matching region instead of the single best one.
+    pub all_matches: Option<bool>,
+    /// System instruction replacing the profile default for this request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    /// Additional system instructions appended after the default or overridden instruction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extra_instructions: Option<String>,
+    /// Worked examples sent as prior conversation turns.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub few_shot: Vec<FewShotExample>,
+    /// Model to use for the request.
+    pub model: String,
+    /// Whether to enable chain-of-thought reasoning.
+    pub reasoning: Option<bool>,
+    /// Sampling temperature.
+    pub temperature: Option<f32>,
+    /// Nucleus sampling probability.
+    pub top_p: Option<f32>,
+    /// Top-k sampling value.
+    pub top_k: Option<u32>,
+    /// Frequency penalty.
+    pub frequency_penalty: Option<f32>,
+    /// Presence penalty.
+    pub presence_penalty: Option<f32>,
+    /// Maximum number of tokens to generate.
+    pub max_tokens: Option<u32>,
+}
+
+impl GroundRequest {
+    /// Create a new grounding request.
+    pub fn new(model: impl Into<String>, media: impl Into<Media>, expression: impl Into<String>) -> Self {
+        Self {
+            media: media.into(),
+            expression: expression.into(),
+            polygon: None,
+            all_matches: None,
+            system_prompt: None,
+            extra_instructions: None,
+            few_shot: Vec::new(),
+            model: model.into(),
+            reasoning: None,
+            temperature: None,
+            top_p: None,
+            top_k: None,
+            frequency_penalty: None,
+            presence_penalty: None,
+            max_tokens: None,
+        }
+    }
+
+    /// Return polygons instead of bounding boxes.
+    pub fn polygon(mut self, enable: bool) -> Self {
+        self.polygon = Some(enable);
+        self
+    }
+
+    /// Ask for every matching region instead of the single best one.
+    pub fn all_matches(mut self, enable: bool) -> Self {
+        self.all_matches = Some(enable);
+        self
+    }
+
+    /// Output format implied by [`Self::polygon`].
+    pub(crate) fn output_format(&self) -> OutputFormat {
+        match self.polygon {
+            Some(true) => OutputFormat::Polygon,
+            _ => OutputFormat::Box,
+        }
+    }
+
+    prompt_setters!();
+
+    generation_param_setters!();
+}
+
+/// Parameters for a two-image comparison request.
+///
+/// Use [`CompareRequest::new`] to create a request with required fields,
+/// then chain optional setters using the builder pattern.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CompareRequest {
+    /// The earlier image.
+    pub before: Media,
+    /// The later image.
+    pub after: Media,
+    /// System instruction replacing the profile default for this request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    /// Additional system instructions appended after the default or overridden instruction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extra_instructions: Option<String>,
+    /// Worked examples sent as prior conversation turns.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub few_shot: Vec<FewShotExample>,
+    /// Model to use for the request.
+    pub model: String,
+    /// Whether to enable chain-of-thought reasoning.
+    pub reasoning: Option<bool>,
+    /// Sampling temperature.
+    pub temperature: Option<f32>,
+    /// Nucleus sampling probability.
+    pub top_p: Option<f32>,
+    /// Top-k sampling value.
+    pub top_k: Option<u32>,
+    /// Frequency penalty.
+    pub frequency_penalty: Option<f32>,
+    /// Presence penalty.
+    pub presence_penalty: Option<f32>,
+    /// Maximum number of tokens to generate.
+    pub max_tokens: Option<u32>,
+}
+
+impl CompareRequest {
+    /// Create a new comparison request.
+    pub fn new(model: impl Into<String>, before: impl Into<Media>, after: impl Into<Media>) -> Self {
+        Self {
+            before: before.into(),
+            after: after.into(),
+            system_prompt: None,
+            extra_instructions: None,
+            few_shot: Vec::new(),
+            model: model.into(),
+            reasoning: None,
+            temperature: None,
+            top_p: None,
+            top_k: None,
+            frequency_penalty: None,
+            presence_penalty: None,
+            max_tokens: None,
+        }
+    }
+
+    prompt_setters!();
+
+    generation_param_setters!();
+}
+
+/// Parameters for a document field extraction request.
+///
+/// Use [`DocumentRequest::new`] to create a request with required fields,
+/// then chain optional setters using the builder pattern.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DocumentRequest {
+    /// Image of the document (receipt, invoice, form).
+    pub image: Image,
+    /// Field names to extract. When empty, every labeled field is returned.
+    pub fields: Vec<String>,
+    /// Whether to also transcribe tables.
+    pub tables: Option<bool>,
+    /// System instruction replacing the profile default for this request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    /// Additional system instructions appended after the default or overridden instruction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extra_instructions: Option<String>,
+    /// Worked examples sent as prior conversation turns.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub few_shot: Vec<FewShotExample>,
+    /// Model to use for the request.
+    pub model: String,
+    /// Whether to enable chain-of-thought reasoning.
+    pub reasoning: Option<bool>,
+    /// Sampling temperature.
+    pub temperature: Option<f32>,
+    /// Nucleus sampling probability.
+    pub top_p: Option<f32>,
+    /// Top-k sampling value.
+    pub top_k: Option<u32>,
+    /// Frequency penalty.
+    pub frequency_penalty: Option<f32>,
+    /// Presence penalty.
+    pub presence_penalty: Option<f32>,
+    /// Maximum number of tokens to generate.
+    pub max_tokens: Option<u32>,
+}
+
+impl DocumentRequest {
+    /// Create a new document request.
+    pub fn new(model: impl Into<String>, image: Image, fields: Vec<String>) -> Self {
+        Self {
+            image,
+            fields,
+            tables: None,
+            system_prompt: None,
+            extra_instructions: None,
+            few_shot: Vec::new(),
+            model: model.into(),
+            reasoning: None,
+            temperature: None,
+            top_p: None,
+            top_k: None,
+            frequency_penalty: None,
+            presence_penalty: None,
+            max_tokens: None,
+        }
+    }
+
+    /// Also transcribe the document's tables into [`DocumentResponse::tables`].
+    pub fn tables(mut self, enable: bool) -> Self {
+        self.tables = Some(enable);
+        self
+    }
+
+    prompt_setters!();
+
+    generation_param_setters!();
+}
+
+/// Parameters for a video summary and chaptering request.
+///
+/// Use [`VideoSummaryRequest::new`] to create a request with required fields,
+/// then chain optional setters using the builder pattern.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct VideoSummaryRequest {
+    /// Video to summarize.
+    pub video: Video,
+    /// Video length in seconds, if known. Chapters are clamped to it.
+    pub duration: Option<f32>,
+    /// System instruction replacing the profile default for this request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    /// Additional system instructions appended after the default or overridden instruction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extra_instructions: Option<String>,
+    /// Worked examples sent as prior conversation turns.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub few_shot: Vec<FewShotExample>,
+    /// Model to use for the request.
+    pub model: String,
+    /// Whether to enable chain-of-thought reasoning.
+    pub reasoning: Option<bool>,
+    /// Sampling temperature.
+    pub temperature: Option<f32>,
+    /// Nucleus sampling probability.
+    pub top_p: Option<f32>,
+    /// Top-k sampling value.
+    pub top_k: Option<u32>,
+    /// Frequency penalty.
+    pub frequency_penalty: Option<f32>,
+    /// Presence penalty.
+    pub presence_penalty: Option<f32>,
+    /// Maximum number of tokens to generate.
+    pub max_tokens: Option<u32>,
+}
+
+impl VideoSummaryRequest {
+    /// Create a new video summary request.
+    pub fn new(model: impl Into<String>, video: Video) -> Self {
+        Self {
+            video,
+            duration: None,
+            system_prompt: None,
+            extra_instructions: None,
+            few_shot: Vec::new(),
+            model: model.into(),
+            reasoning: None,
+            temperature: None,
+            top_p: None,
+            top_k: None,
+            frequency_penalty: None,
+            presence_penalty: None,
+            max_tokens: None,
+        }
+    }
+
+    /// Set the video length in seconds.
+    pub fn duration(mut self, seconds: f32) -> Self {
+        self.duration = Some(seconds);
+        self
+    }
+
+    prompt_setters!();
+
+    generation_param_setters!();
+}
+
+/// Parameters for a temporal event search in a video.
+///
+/// Use [`LocateRequest::new`] to create a request with required fields,
+/// then chain optional setters using the builder pattern.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct LocateRequest {
+    /// Video to search.
+    pub video: Video,
+    /// Event to find (e.g. `"person enters the room"`).
+    pub query: String,
+    /// Key frame format for each hit.
+    pub key_frames: Option<KeyFrameFormat>,
+    /// Merge hits separated by at most this many seconds (default 1).
+    pub merge_gap: Option<f32>,
+    /// System instruction replacing the profile default for this request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    /// Additional system instructions appended after the default or overridden instruction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extra_instructions: Option<String>,
+    /// Worked examples sent as prior conversation turns.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub few_shot: Vec<FewShotExample>,
+    /// Model to use for the request.
+    pub model: String,
+    /// Whether to enable chain-of-thought reasoning.
+    pub reasoning: Option<bool>,
+    /// Sampling temperature.
+    pub temperature: Option<f32>,
+    /// Nucleus sampling probability.
+    pub top_p: Option<f32>,
+    /// Top-k sampling value.
+    pub top_k: Option<u32>,
+    /// Frequency penalty.
+    pub frequency_penalty: Option<f32>,
+    /// Presence penalty.
+    pub presence_penalty: Option<f32>,
+    /// Maximum number of tokens to generate.
+    pub max_tokens: Option<u32>,
+}
+
+impl LocateRequest {
+    /// Create a new locate request.
+    pub fn new(model: impl Into<String>, video: Video, query: impl Into<String>) -> Self {
+        Self {
+            video,
+            query: query.into(),
+            key_frames: None,
+            merge_gap: None,
+            system_prompt: None,
+            extra_instructions: None,
+            few_shot: Vec::new(),
+            model: model.into(),
+            reasoning: None,
+            temperature: None,
+            top_p: None,
+            top_k: None,
+            frequency_penalty: None,
+            presence_penalty: None,
+            max_tokens: None,
+        }
+    }
+
+    /// Ask for a key frame per hit, located as a point or a box.
+    pub fn key_frames(mut self, format: KeyFrameFormat) -> Self {
+        self.key_frames = Some(format);
+        self
+    }
+
+    /// Set the largest gap, in seconds, between hits that are merged into one.
+    pub fn merge_gap(mut self, seconds: f32) -> Self {
+        self.merge_gap = Some(seconds);
+        self
+    }
+
+    prompt_setters!();
+
+    generation_param_setters!();
+}
+
+/// Parameters for a taxonomy-constrained tagging request.
+///
+/// Use [`TagRequest::new`] to create a request with required fields,
+/// then chain optional setters using the builder pattern.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TagRequest {
+    /// Media to tag.
+    pub media: Media,
+    /// Controlled vocabulary the tags must come from.
+    pub taxonomy: Taxonomy,
+    /// System instruction replacing the profile default for this request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    /// Additional system instructions appended after the default or overridden instruction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extra_instructions: Option<String>,
+    /// Worked examples sent as prior conversation turns.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub few_shot: Vec<FewShotExample>,
+    /// Model to use for the request.
+    pub model: String,
+    /// Whether to enable chain-of-thought reasoning.
+    pub reasoning: Option<bool>,
+    /// Sampling temperature.
+    pub temperature: Option<f32>,
+    /// Nucleus sampling probability.
+    pub top_p: Option<f32>,
+    /// Top-k sampling value.
+    pub top_k: Option<u32>,
+    /// Frequency penalty.
+    pub frequency_penalty: Option<f32>,
+    /// Presence penalty.
+    pub presence_penalty: Option<f32>,
+    /// Maximum number of tokens to generate.
+    pub max_tokens: Option<u32>,
+}
+
+impl TagRequest {
+    /// Create a new tagging request.
+    pub fn new(model: impl Into<String>, media: impl Into<Media>, taxonomy: Taxonomy) -> Self {
+        Self {
+            media: media.into(),
+            taxonomy,
+            system_prompt: None,
+            extra_instructions: None,
+            few_shot: Vec::new(),
+            model: model.into(),
+            reasoning: None,
+            temperature: None,
+            top_p: None,
+            top_k: None,
+            frequency_penalty: None,
+            presence_penalty: None,
+            max_tokens: None,
+        }
+    }
+
+    prompt_setters!();
+
+    generation_param_setters!();
+}
+
+/// A content part of a user message in a [`ChatRequest`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum ChatContentPart {
+    /// Text.
+    Text(String),
+    /// An image.
+    Image(Image),
+    /// A video.
+    Video(Video),
+}
+
+impl From<&str> for ChatContentPart {
+    fn from(text: &str) -> Self {
+        Self::Text(text.to_string())
+    }
+}
+
+impl From<String> for ChatContentPart {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+impl From<Image> for ChatContentPart {
+    fn from(image: Image) -> Self {
+        Self::Image(image)
+    }
+}
+
+impl From<Video> for ChatContentPart {
+    fn from(video: Video) -> Self {
+        Self::Video(video)
+    }
+}
+
+impl From<Media> for ChatContentPart {
+    fn from(media: Media) -> Self {
+        match media {
+            Media::Image(image) => Self::Image(image),
+            Media::Video(video) => Self::Video(video),
+        }
+    }
+}
+
+/// A message in a [`ChatRequest`] conversation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "role", content = "content", rename_all = "snake_case")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum ChatMessage {
+    /// A system message.
+    System(String),
+    /// A user message made of text, image and video parts, sent in order.
+    User(Vec<ChatContentPart>),
+    /// An assistant message, e.g. a prior model answer.
+    Assistant(String),
+}
+
+impl ChatMessage {
+    /// Create a system message.
+    pub fn system(text: impl Into<String>) -> Self {
+        Self::System(text.into())
+    }
+
+    /// Create a user message from content parts.
+    pub fn user(parts: Vec<ChatContentPart>) -> Self {
+        Self::User(parts)
+    }
+
+    /// Create an assistant message.
+    pub fn assistant(text: impl Into<String>) -> Self {
+        Self::Assistant(text.into())
+    }
+}
+
+/// Parameters for a raw chat completion request.
+///
+/// Messages are sent as given. Setting an output format or enabling reasoning prepends the
+/// matching `<hint>` system message; leave both unset for full control over the conversation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ChatRequest {
+    /// Conversation to send.
+    pub messages: Vec<ChatMessage>,
+    /// Output format to hint and extract from the response.
+    pub output_format: Option<OutputFormat>,
+    /// Model to use for the request.
+    pub model: String,
+    /// Whether to enable chain-of-thought reasoning.
+    pub reasoning: Option<bool>,
+    /// Sampling temperature.
+    pub temperature: Option<f32>,
+    /// Nucleus sampling probability.
+    pub top_p: Option<f32>,
+    /// Top-k sampling value.
+    pub top_k: Option<u32>,
+    /// Frequency penalty.
+    pub frequency_penalty: Option<f32>,
+    /// Presence penalty.
+    pub presence_penalty: Option<f32>,
+    /// Maximum number of tokens to generate.
+    pub max_tokens: Option<u32>,
+}
+
+impl ChatRequest {
+    /// Create a new chat request.
+    pub fn new(model: impl Into<String>, messages: Vec<ChatMessage>) -> Self {
+        Self {
+            messages,
+            output_format: None,
+            model: model.into(),
+            reasoning: None,
+            temperature: None,
+            top_p: None,
+            top_k: None,
+            frequency_penalty: None,
+            presence_penalty: None,
+            max_tokens: None,
+        }
+    }
+
+    /// Append a message to the conversation.
+    pub fn message(mut self, message: ChatMessage) -> Self {
+        self.messages.push(message);
+        self
+    }
+
+    /// Set the output format.
+    pub fn output_format(mut self, format: OutputFormat) -> Self {
+        self.output_format = Some(format);
+        self
+    }
+
+    generation_param_setters!();
+}
+
+/// Response for text-only methods (ocr, question).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TextResponse {
+    /// The main response content from the model.
+    pub content: Option<String>,
+    /// Chain-of-thought reasoning content (if reasoning was enabled).
+    pub reasoning: Option<String>,
+    /// Whether the response was served from the client's response cache.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cached: bool,
+}
+
+/// Response for spatial methods (analyze, caption, detect).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PointingResponse {
+    /// The main response content from the model.
+    pub content: Option<String>,
+    /// Chain-of-thought reasoning content (if reasoning was enabled).
+    pub reasoning: Option<String>,
+    /// Whether the response was served from the client's response cache.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cached: bool,
+    /// Extracted spatial pointing data.
+    pub pointing: Option<Pointing>,
+    /// Agreement between samples, when the request was ensembled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ensemble: Option<EnsembleStats>,
+}
+
+/// Response for [`Perceptron::classify`](crate::Perceptron::classify).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ClassifyResponse {
+    /// The main response content from the model.
+    pub content: Option<String>,
+    /// Chain-of-thought reasoning content (if reasoning was enabled).
+    pub reasoning: Option<String>,
+    /// Whether the response was served from the client's response cache.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cached: bool,
+    /// Matched labels, highest score first.
+    pub labels: Vec<LabelScore>,
+}
+
+/// Response for [`Perceptron::count`](crate::Perceptron::count).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CountResponse {
+    /// The main response content from the model.
+    pub content: Option<String>,
+    /// Chain-of-thought reasoning content (if reasoning was enabled).
+    pub reasoning: Option<String>,
+    /// Whether the response was served from the client's response cache.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cached: bool,
+    /// One entry per requested class, in request order.
+    pub counts: Vec<ClassCount>,
+    /// Agreement between samples, when the request was ensembled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ensemble: Option<EnsembleStats>,
+}
+
+/// Response for [`Perceptron::ground`](crate::Perceptron::ground).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct GroundResponse {
+    /// The main response content from the model.
+    pub content: Option<String>,
+    /// Chain-of-thought reasoning content (if reasoning was enabled).
+    pub reasoning: Option<String>,
+    /// Whether the response was served from the client's response cache.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cached: bool,
+    /// The best matching region: the first one the model returned, or `None` if the
+    /// expression matched nothing.
+    pub region: Option<Region>,
+    /// Every region the model returned, in answer order.
+    pub matches: Vec<Region>,
+}
+
+/// Response for [`Perceptron::compare`](crate::Perceptron::compare).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CompareResponse {
+    /// The main response content from the model.
+    pub content: Option<String>,
+    /// Chain-of-thought reasoning content (if reasoning was enabled).
+    pub reasoning: Option<String>,
+    /// Whether the response was served from the client's response cache.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cached: bool,
+    /// Prose summary of the differences, without annotation tags.
+    pub summary: Option<String>,
+    /// Changed regions, each located in the before or after image.
+    pub changes: Vec<Change>,
+}
+
+/// Response for [`Perceptron::document`](crate::Perceptron::document).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DocumentResponse {
+    /// The main response content from the model.
+    pub content: Option<String>,
+    /// Chain-of-thought reasoning content (if reasoning was enabled).
+    pub reasoning: Option<String>,
+    /// Whether the response was served from the client's response cache.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cached: bool,
+    /// Extracted fields, in request order when fields were requested.
+    pub fields: Vec<DocumentField>,
+    /// Transcribed tables.
+    pub tables: Vec<Table>,
+}
+
+/// Response for [`Perceptron::summarize_video`](crate::Perceptron::summarize_video).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct VideoSummaryResponse {
+    /// The main response content from the model.
+    pub content: Option<String>,
+    /// Chain-of-thought reasoning content (if reasoning was enabled).
+    pub reasoning: Option<String>,
+    /// Whether the response was served from the client's response cache.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cached: bool,
+    /// Prose summary of the whole video.
+    pub summary: Option<String>,
+    /// Chapters in chronological order, without overlaps.
+    pub chapters: Vec<Chapter>,
+}
+
+/// Response for [`Perceptron::locate`](crate::Perceptron::locate).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct LocateResponse {
+    /// The main response content from the model.
+    pub content: Option<String>,
+    /// Chain-of-thought reasoning content (if reasoning was enabled).
+    pub reasoning: Option<String>,
+    /// Whether the response was served from the client's response cache.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cached: bool,
+    /// Time ranges where the event happens, in chronological order.
+    pub hits: Vec<EventHit>,
+}
+
+/// Response for [`Perceptron::tag`](crate::Perceptron::tag).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TagResponse {
+    /// The main response content from the model.
+    pub content: Option<String>,
+    /// Chain-of-thought reasoning content (if reasoning was enabled).
+    pub reasoning: Option<String>,
+    /// Whether the response was served from the client's response cache.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cached: bool,
+    /// Applied tags and their ancestors, in taxonomy order.
+    pub tags: Vec<Tag>,
+    /// Tags the model returned that match nothing in the taxonomy.
+    pub rejected: Vec<String>,
+}
+
+/// Token usage reported for a request.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Usage {
+    /// Tokens in the prompt, including media.
+    pub prompt_tokens: u32,
+    /// Tokens generated by the model.
+    pub completion_tokens: u32,
+    /// Total tokens billed for the request.
+    pub total_tokens: u32,
+}
+
+/// Response for [`Perceptron::chat`](crate::Perceptron::chat).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ChatResponse {
+    /// The main response content from the model.
+    pub content: Option<String>,
+    /// Chain-of-thought reasoning content (if reasoning was enabled).
+    pub reasoning: Option<String>,
+    /// Whether the response was served from the client's response cache.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cached: bool,
+    /// Extracted annotations, when the request set an output format.
     pub pointing: Option<Pointing>,
+    /// Why the model stopped generating (e.g. `"stop"` or `"length"`).
+    pub finish_reason: Option<String>,
+    /// Token usage, when reported by the server.
+    pub usage: Option<Usage>,
 }
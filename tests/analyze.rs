@@ -403,3 +403,29 @@ async fn all_generation_params() {
         })
     );
 }
+
+#[tokio::test]
+async fn system_prompt_after_hint() {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({
+            "messages": [
+                {"role": "system", "content": "<hint>POINT</hint>"},
+                {"role": "system", "content": "You are a wildlife spotter."},
+                {"role": "user", "content": [
+                    {"type": "image_url"},
+                    {"type": "text", "text": "Describe this"}
+                ]}
+            ]
+        })),
+        common::response(r#"<point mention="cat"> (50,60) </point>"#, None),
+    )
+    .await;
+
+    let request = test_request("test-model")
+        .output_format(OutputFormat::Point)
+        .system_prompt("You are a wildlife spotter.");
+    let response = client.analyze(request).await.unwrap();
+    assert!(response.pointing.is_some());
+}
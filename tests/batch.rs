@@ -0,0 +1,119 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use perceptron_ai::{BatchProgress, CancelToken, DetectRequest, Image, PerceptronError};
+use serde_json::json;
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+fn request(model: &str) -> DetectRequest {
+    DetectRequest::new(model, Image::url("https://example.com/img.jpg"))
+}
+
+async fn mock_delayed(server: &MockServer, model: &str, content: &str, delay_ms: u64) {
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(body_partial_json(json!({"model": model})))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(common::response(content, None))
+                .set_delay(Duration::from_millis(delay_ms)),
+        )
+        .expect(1)
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn results_in_input_order() {
+    let (server, client) = common::setup().await;
+    mock_delayed(&server, "slow", "first", 200).await;
+    mock_delayed(&server, "fast", "third", 0).await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(body_partial_json(json!({"model": "broken"})))
+        .respond_with(ResponseTemplate::new(500).set_body_json(json!({
+            "error": {"message": "boom", "type": "server_error"}
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let progress = Arc::new(Mutex::new(Vec::new()));
+    let seen = progress.clone();
+    let results = client
+        .batch([request("slow"), request("broken"), request("fast")])
+        .concurrency(3)
+        .on_progress(move |p| seen.lock().unwrap().push(p))
+        .run()
+        .await;
+
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].as_ref().unwrap().content.as_deref(), Some("first"));
+    assert!(matches!(
+        &results[1],
+        Err(PerceptronError::ApiError { status: 500, .. })
+    ));
+    assert_eq!(results[2].as_ref().unwrap().content.as_deref(), Some("third"));
+
+    let progress = progress.lock().unwrap();
+    assert_eq!(progress.len(), 3);
+    assert_eq!(
+        progress[2],
+        BatchProgress {
+            index: 0,
+            completed: 3,
+            failed: 1,
+            total: 3
+        }
+    );
+}
+
+#[tokio::test]
+async fn limits_requests_in_flight() {
+    let (server, client) = common::setup().await;
+    mock_delayed(&server, "m0", "a", 100).await;
+    mock_delayed(&server, "m1", "b", 100).await;
+
+    let started = std::time::Instant::now();
+    let results = client.batch(["m0", "m1"].map(request)).concurrency(1).run().await;
+    assert!(started.elapsed() >= Duration::from_millis(200));
+    assert!(results.iter().all(Result::is_ok));
+}
+
+#[tokio::test]
+async fn cancel_skips_remaining_requests() {
+    let (server, client) = common::setup().await;
+    mock_delayed(&server, "m0", "done", 0).await;
+
+    let token = CancelToken::new();
+    let cancel = token.clone();
+    let results = client
+        .batch(["m0", "m1", "m2"].map(request))
+        .concurrency(1)
+        .cancel_token(token)
+        .on_progress(move |_| cancel.cancel())
+        .run()
+        .await;
+
+    assert_eq!(results[0].as_ref().unwrap().content.as_deref(), Some("done"));
+    assert!(matches!(results[1], Err(PerceptronError::Cancelled)));
+    assert!(matches!(results[2], Err(PerceptronError::Cancelled)));
+}
+
+#[tokio::test]
+async fn cancel_drops_requests_in_flight() {
+    let (server, client) = common::setup().await;
+    mock_delayed(&server, "m0", "late", 5_000).await;
+
+    let token = CancelToken::new();
+    let cancel = token.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        cancel.cancel();
+    });
+    let results = client.batch([request("m0")]).cancel_token(token).run().await;
+    assert!(matches!(results[0], Err(PerceptronError::Cancelled)));
+}
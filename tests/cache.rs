@@ -0,0 +1,131 @@
+use std::time::Duration;
+
+use perceptron_ai::{
+    CaptionLength, CaptionRequest, DetectRequest, DiskCache, Image, LengthPolicy, MemoryCache, Perceptron,
+};
+use serde_json::json;
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+mod common;
+
+fn detect() -> DetectRequest {
+    DetectRequest::new("isaac-test", Image::url("https://example.com/img.jpg"))
+}
+
+async fn mock_detect(server: &wiremock::MockServer, expected_calls: u64) {
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(common::response(
+            r#"<point_box mention="cat"> (1,2) (3,4) </point_box>"#,
+            None,
+        )))
+        .expect(expected_calls)
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn repeated_requests_hit_memory_cache() {
+    let (server, client) = common::setup().await;
+    mock_detect(&server, 1).await;
+
+    let cache = MemoryCache::new(16);
+    let client = client.cache(cache.clone());
+    let first = client.detect(detect()).await.unwrap();
+    let second = client.detect(detect()).await.unwrap();
+    assert!(!first.cached);
+    assert!(second.cached);
+    assert_eq!(second.pointing, first.pointing);
+    assert_eq!(cache.len(), 1);
+}
+
+#[tokio::test]
+async fn bypass_skips_lookup_but_stores() {
+    let (server, client) = common::setup().await;
+    mock_detect(&server, 2).await;
+
+    let client = client.cache(MemoryCache::new(16));
+    client.detect(detect()).await.unwrap();
+    let refreshed = client.clone().bypass_cache(true).detect(detect()).await.unwrap();
+    assert!(!refreshed.cached);
+    assert!(client.detect(detect()).await.unwrap().cached);
+}
+
+#[tokio::test]
+async fn caption_retries_skip_cached_answer() {
+    let (server, client) = common::setup().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(common::response(
+            "Sunday mornings are for lazy cats and warm windowsills",
+            None,
+        )))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(common::response("A cat on a windowsill", None)))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = client.cache(MemoryCache::new(16));
+    let caption = || {
+        CaptionRequest::new("isaac-test", Image::url("https://example.com/img.jpg"))
+            .length(CaptionLength::words(3, 6))
+            .length_policy(LengthPolicy::Retry { attempts: 2 })
+    };
+    let first = client.caption(caption()).await.unwrap();
+    assert_eq!(first.content.as_deref(), Some("A cat on a windowsill"));
+    assert!(!first.cached);
+    let second = client.caption(caption()).await.unwrap();
+    assert_eq!(second.content.as_deref(), Some("A cat on a windowsill"));
+    assert!(second.cached);
+}
+
+#[tokio::test]
+async fn sampled_requests_are_cached_only_when_forced() {
+    let (server, client) = common::setup().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(body_partial_json(json!({"temperature": 0.7})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(common::response("A cat on a mat.", None)))
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    let caption = || CaptionRequest::new("isaac-test", Image::url("https://example.com/img.jpg")).temperature(0.7);
+    let client = client.cache(MemoryCache::new(16));
+    assert!(!client.caption(caption()).await.unwrap().cached);
+    assert!(!client.caption(caption()).await.unwrap().cached);
+
+    let forced = client.force_cache(true);
+    forced.caption(caption()).await.unwrap();
+    let cached = forced.caption(caption()).await.unwrap();
+    assert!(cached.cached);
+    assert_eq!(cached.content.as_deref(), Some("A cat on a mat."));
+}
+
+#[tokio::test]
+async fn disk_cache_persists_across_clients_and_expires() {
+    let (server, client) = common::setup().await;
+    mock_detect(&server, 3).await;
+
+    let dir = std::env::temp_dir().join(format!("perceptron-cache-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let first = client.clone().cache(DiskCache::new(&dir));
+    first.detect(detect()).await.unwrap();
+    let second = client.clone().cache(DiskCache::new(&dir));
+    assert!(second.detect(detect()).await.unwrap().cached);
+
+    let expiring = client
+        .cache(DiskCache::new(&dir))
+        .cache_ttl(Duration::ZERO)
+        .bypass_cache(true);
+    expiring.detect(detect()).await.unwrap();
+    assert!(!expiring.bypass_cache(false).detect(detect()).await.unwrap().cached);
+    std::fs::remove_dir_all(dir).unwrap();
+}
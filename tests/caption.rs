@@ -1,9 +1,11 @@
 use perceptron_ai::{
-    BoundingBox, CaptionRequest, CaptionStyle, Image, ImageFormat, OutputFormat, Perceptron, Point, Pointing, Video,
+    BoundingBox, CaptionLength, CaptionRequest, CaptionStyle, Image, ImageFormat, LengthPolicy, OutputFormat,
+    Perceptron, Point, Pointing, PromptProfile, Video,
 };
 use rstest::rstest;
 use serde_json::json;
-use wiremock::matchers::body_partial_json;
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, ResponseTemplate};
 
 mod common;
 
@@ -220,3 +222,127 @@ async fn point_format() {
         })
     );
 }
+
+#[tokio::test]
+async fn output_language() {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({
+            "messages": [
+                {"role": "system", "content": "<hint>BOX</hint>"},
+                {"role": "system", "content": "Respond in German. Keep the mention attribute of every tag exactly as given."},
+                {"role": "user", "content": [
+                    {"type": "image_url"},
+                    {"type": "text", "text": "Provide a concise, human-friendly caption for the upcoming image."}
+                ]}
+            ]
+        })),
+        common::response(r#"Eine Katze auf der Fensterbank <point_box mention="cat"> (10,20) (300,400) </point_box>"#, None),
+    )
+    .await;
+
+    let request =
+        CaptionRequest::new("isaac-test", Image::url("https://example.com/img.jpg")).output_language("German");
+    let response = client.caption(request).await.unwrap();
+    assert_single_cat_box(&response);
+}
+
+#[tokio::test]
+async fn localized_profile() {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({
+            "messages": [
+                {"role": "system", "content": "<hint>BOX</hint>"},
+                {"role": "system", "content": "日本語で回答してください。タグのmention属性は翻訳せず、指定どおりそのまま記述してください。"},
+                {"role": "user", "content": [
+                    {"type": "image_url"},
+                    {"type": "text", "text": "次の画像に、簡潔で分かりやすいキャプションを付けてください。"}
+                ]}
+            ]
+        })),
+        common::response(r#"窓辺の猫 <point_box mention="cat"> (10,20) (300,400) </point_box>"#, None),
+    )
+    .await;
+
+    let client = client.prompt_profile(PromptProfile::ISAAC_JA);
+    let request =
+        CaptionRequest::new("isaac-test", Image::url("https://example.com/img.jpg")).output_language("日本語");
+    let response = client.caption(request).await.unwrap();
+    assert_single_cat_box(&response);
+}
+
+#[tokio::test]
+async fn alt_text_text_only_truncates_to_125_characters() {
+    let (server, client) = common::setup().await;
+    let long = "A ginger cat with white paws sleeps curled up on a sunny wooden windowsill beside a potted basil plant \
+                and a half-open lace curtain.";
+    common::mock_response(
+        &server,
+        body_partial_json(json!({
+            "messages": [
+                {"role": "system", "content": "<hint>THINK</hint>"},
+                {"role": "user", "content": [
+                    {"type": "image_url"},
+                    {"type": "text", "text": "Write alt text for the upcoming image for screen reader users, following WCAG guidance: describe its essential content and purpose in under 125 characters, without starting with \"image of\". Use at most 124 characters."}
+                ]}
+            ]
+        })),
+        common::response(long, None),
+    )
+    .await;
+
+    let request = CaptionRequest::new("isaac-test", Image::url("https://example.com/img.jpg"))
+        .style(CaptionStyle::AltText)
+        .text_only(true)
+        .reasoning(true);
+    let response = client.caption(request).await.unwrap();
+
+    let content = response.content.unwrap();
+    assert!(content.chars().count() < 125);
+    assert_eq!(
+        content,
+        "A ginger cat with white paws sleeps curled up on a sunny wooden windowsill beside a potted basil plant and a half-open lace"
+    );
+    assert_eq!(response.pointing, None);
+}
+
+#[tokio::test]
+async fn word_range_retries_until_in_range() {
+    let (server, client) = common::setup().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(body_partial_json(json!({
+            "messages": [
+                {"role": "system", "content": "<hint>BOX</hint>"},
+                {"role": "user", "content": [
+                    {"type": "image_url"},
+                    {"type": "text", "text": "Write an engaging social media caption for the upcoming image. Use between 3 and 6 words."}
+                ]}
+            ]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(common::response(
+            "Sunday mornings are for lazy cats and warm windowsills",
+            None,
+        )))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({})),
+        common::response(box_content(), None),
+    )
+    .await;
+
+    let request = CaptionRequest::new("isaac-test", Image::url("https://example.com/img.jpg"))
+        .style(CaptionStyle::Social)
+        .length(CaptionLength::words(3, 6))
+        .length_policy(LengthPolicy::Retry { attempts: 2 });
+    let response = client.caption(request).await.unwrap();
+    assert_single_cat_box(&response);
+    assert_eq!(response.content.as_deref(), Some("A cat on a windowsill"));
+}
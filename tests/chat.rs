@@ -0,0 +1,117 @@
+use perceptron_ai::{ChatContentPart, ChatMessage, ChatRequest, Image, OutputFormat, Perceptron, Usage, Video};
+use rstest::rstest;
+use serde_json::json;
+use wiremock::matchers::body_partial_json;
+use wiremock::{Mock, ResponseTemplate};
+
+mod common;
+
+#[tokio::test]
+async fn messages_sent_as_given() {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({
+            "model": "isaac-test",
+            "messages": [
+                {"role": "system", "content": "You compare frames."},
+                {"role": "user", "content": [
+                    {"type": "text", "text": "First:"},
+                    {"type": "image_url", "image_url": {"url": "https://example.com/a.jpg"}},
+                    {"type": "text", "text": "Then:"},
+                    {"type": "video_url", "video_url": {"url": "https://example.com/b.mp4"}}
+                ]},
+                {"role": "assistant", "content": "Ready."},
+                {"role": "user", "content": [{"type": "text", "text": "What changed?"}]}
+            ],
+            "temperature": 0.25,
+            "top_k": 10,
+            "max_completion_tokens": 64
+        })),
+        common::response("The car left.", None),
+    )
+    .await;
+
+    let request = ChatRequest::new(
+        "isaac-test",
+        vec![
+            ChatMessage::system("You compare frames."),
+            ChatMessage::user(vec![
+                "First:".into(),
+                Image::url("https://example.com/a.jpg").into(),
+                "Then:".into(),
+                ChatContentPart::Video(Video::url("https://example.com/b.mp4")),
+            ]),
+            ChatMessage::assistant("Ready."),
+        ],
+    )
+    .message(ChatMessage::user(vec!["What changed?".into()]))
+    .temperature(0.25)
+    .top_k(10)
+    .max_tokens(64);
+    let response = client.chat(request).await.unwrap();
+    assert_eq!(response.content, Some("The car left.".to_string()));
+    assert_eq!(response.pointing, None);
+}
+
+#[rstest]
+#[case::point(OutputFormat::Point, "<hint>POINT</hint>", r#"<point mention="cat"> (1,2) </point>"#)]
+#[case::polygon(
+    OutputFormat::Polygon,
+    "<hint>POLYGON</hint>",
+    r#"<polygon mention="cat"> (1,2) (3,4) (5,6) </polygon>"#
+)]
+#[tokio::test]
+async fn output_format_hints_and_extracts(#[case] format: OutputFormat, #[case] hint: &str, #[case] content: &str) {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({
+            "messages": [
+                {"role": "system", "content": hint},
+                {"role": "user"}
+            ]
+        })),
+        common::response(content, None),
+    )
+    .await;
+
+    let request = ChatRequest::new(
+        "isaac-test",
+        vec![ChatMessage::user(vec![
+            Image::url("https://example.com/img.jpg").into(),
+            "Where is the cat?".into(),
+        ])],
+    )
+    .output_format(format);
+    let response = client.chat(request).await.unwrap();
+    assert!(response.pointing.is_some());
+}
+
+#[tokio::test]
+async fn finish_reason_and_usage() {
+    let (server, client) = common::setup().await;
+    Mock::given(wiremock::matchers::path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "choices": [{
+                "message": {"content": "A cat", "reasoning_content": null},
+                "finish_reason": "length"
+            }],
+            "usage": {"prompt_tokens": 300, "completion_tokens": 2, "total_tokens": 302}
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let request = ChatRequest::new("isaac-test", vec![ChatMessage::user(vec!["Hi".into()])]);
+    let response = client.chat(request).await.unwrap();
+    assert_eq!(response.finish_reason.as_deref(), Some("length"));
+    assert_eq!(
+        response.usage,
+        Some(Usage {
+            prompt_tokens: 300,
+            completion_tokens: 2,
+            total_tokens: 302,
+        })
+    );
+}
@@ -0,0 +1,94 @@
+use perceptron_ai::{ClassifyRequest, Image, LabelScore, Perceptron, PerceptronError, UNKNOWN_LABEL, Video};
+use rstest::rstest;
+use serde_json::json;
+use wiremock::matchers::body_partial_json;
+
+mod common;
+
+fn labels() -> Vec<String> {
+    vec!["cat".to_string(), "dog".to_string(), "bird".to_string()]
+}
+
+#[rstest]
+#[case::single_label(
+    false,
+    "Classify the image as one of these labels: cat, dog, bird. List the likely labels from most to least likely, one per line, as `label: confidence` with a confidence between 0 and 1."
+)]
+#[case::multi_label(
+    true,
+    "Which of these labels apply to the image: cat, dog, bird? List every label that applies, one per line, as `label: confidence` with a confidence between 0 and 1."
+)]
+#[tokio::test]
+async fn prompt(#[case] multi_label: bool, #[case] expected_text: &str) {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({
+            "messages": [
+                {"role": "user", "content": [
+                    {"type": "image_url"},
+                    {"type": "text", "text": expected_text}
+                ]}
+            ]
+        })),
+        common::response("cat: 0.9", None),
+    )
+    .await;
+
+    let request = ClassifyRequest::new("isaac-test", Image::url("https://example.com/img.jpg"), labels())
+        .multi_label(multi_label);
+    let response = client.classify(request).await.unwrap();
+    assert_eq!(
+        response.labels,
+        vec![LabelScore {
+            label: "cat".to_string(),
+            score: 0.9
+        }]
+    );
+}
+
+#[tokio::test]
+async fn paraphrased_answer_maps_to_labels() {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({"messages": [{"role": "user"}]})),
+        common::response("1. Dogs: 0.6\n2. a small Bird: 0.3\n3. squirrel: 0.1", None),
+    )
+    .await;
+
+    let request = ClassifyRequest::new("isaac-test", Video::url("https://example.com/clip.mp4"), labels());
+    let response = client.classify(request).await.unwrap();
+    let ranked: Vec<_> = response.labels.iter().map(|l| l.label.as_str()).collect();
+    assert_eq!(ranked, vec!["dog", "bird", UNKNOWN_LABEL]);
+}
+
+#[tokio::test]
+async fn unrelated_answer_is_unknown() {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({"messages": [{"role": "user"}]})),
+        common::response("The image is too dark to tell.", None),
+    )
+    .await;
+
+    let request = ClassifyRequest::new("isaac-test", Image::url("https://example.com/img.jpg"), labels());
+    let response = client.classify(request).await.unwrap();
+    assert_eq!(
+        response.labels,
+        vec![LabelScore {
+            label: UNKNOWN_LABEL.to_string(),
+            score: 1.0
+        }]
+    );
+}
+
+#[tokio::test]
+async fn rejects_unknown_as_a_label() {
+    let (_server, client) = common::setup().await;
+    let labels = vec!["cat".to_string(), "Unknown".to_string()];
+    let request = ClassifyRequest::new("isaac-test", Image::url("https://example.com/img.jpg"), labels);
+    let error = client.classify(request).await.unwrap_err();
+    assert!(matches!(error, PerceptronError::InvalidRequest(_)), "{error:?}");
+}
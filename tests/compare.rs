@@ -0,0 +1,109 @@
+use perceptron_ai::{ChangeKind, CompareRequest, ComparedImage, Image, Perceptron, PromptProfile};
+use serde_json::json;
+use wiremock::matchers::body_partial_json;
+
+mod common;
+
+#[tokio::test]
+async fn attributes_changes_to_images() {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({
+            "messages": [
+                {"role": "system", "content": "<hint>BOX</hint>"},
+                {"role": "user", "content": [
+                    {"type": "text", "text": "Before:"},
+                    {"type": "image_url", "image_url": {"url": "https://example.com/before.jpg"}},
+                    {"type": "text", "text": "After:"},
+                    {"type": "image_url", "image_url": {"url": "https://example.com/after.jpg"}},
+                    {"type": "text"}
+                ]}
+            ]
+        })),
+        common::response(
+            r#"The car is gone and a bicycle appeared.
+<point_box mention="removed: car"> (10,10) (50,40) </point_box>
+<point_box mention="added: bicycle"> (60,10) (80,40) </point_box>"#,
+            None,
+        ),
+    )
+    .await;
+
+    let request = CompareRequest::new(
+        "isaac-test",
+        Image::url("https://example.com/before.jpg"),
+        Image::url("https://example.com/after.jpg"),
+    );
+    let response = client.compare(request).await.unwrap();
+    assert_eq!(
+        response.summary.as_deref(),
+        Some("The car is gone and a bicycle appeared.")
+    );
+    let changes: Vec<_> = response
+        .changes
+        .iter()
+        .map(|c| (c.kind, c.image, c.label.as_deref()))
+        .collect();
+    assert_eq!(
+        changes,
+        vec![
+            (ChangeKind::Removed, ComparedImage::Before, Some("car")),
+            (ChangeKind::Added, ComparedImage::After, Some("bicycle")),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn no_changes() {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({"messages": [{"role": "system", "content": "<hint>BOX</hint>"}]})),
+        common::response("The images are identical.", None),
+    )
+    .await;
+
+    let request = CompareRequest::new(
+        "isaac-test",
+        Image::url("https://example.com/a.jpg"),
+        Image::url("https://example.com/b.jpg"),
+    );
+    let response = client.compare(request).await.unwrap();
+    assert_eq!(response.summary.as_deref(), Some("The images are identical."));
+    assert!(response.changes.is_empty());
+}
+
+#[tokio::test]
+async fn german_profile() {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({
+            "messages": [
+                {"role": "system", "content": "<hint>BOX</hint>"},
+                {"role": "user", "content": [
+                    {"type": "text", "text": "Vorher:"},
+                    {"type": "image_url"},
+                    {"type": "text", "text": "Nachher:"},
+                    {"type": "image_url"},
+                    {"type": "text", "text": "Vergleiche das Vorher- und das Nachher-Bild. Fasse die Änderungen in ein oder zwei Sätzen zusammen \
+                        und markiere dann jede Änderung mit einem <point_box>. Verwende die mention `added: <object>` für Objekte, die nur im Nachher-Bild vorkommen, \
+                        `removed: <object>` für Objekte, die nur im Vorher-Bild vorkommen, und für ein verändertes Objekt \
+                        je eine Box in jedem Bild mit den mentions `changed (before): <object>` und `changed (after): <object>`."}
+                ]}
+            ]
+        })),
+        common::response("Die Bilder sind identisch.", None),
+    )
+    .await;
+
+    let client = client.prompt_profile(PromptProfile::ISAAC_DE);
+    let request = CompareRequest::new(
+        "isaac-test",
+        Image::url("https://example.com/a.jpg"),
+        Image::url("https://example.com/b.jpg"),
+    );
+    let response = client.compare(request).await.unwrap();
+    assert_eq!(response.summary.as_deref(), Some("Die Bilder sind identisch."));
+}
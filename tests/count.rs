@@ -0,0 +1,69 @@
+use perceptron_ai::{CountRequest, Image, Perceptron};
+use serde_json::json;
+use wiremock::matchers::body_partial_json;
+
+mod common;
+
+#[tokio::test]
+async fn counts_from_points() {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({
+            "messages": [
+                {"role": "system", "content": "<hint>POINT</hint>"},
+                {"role": "user", "content": [
+                    {"type": "image_url"},
+                    {"type": "text", "text": "Count every instance of these categories in the image: apple, pear. Point to each instance with a <point> tag whose mention is its category, then state the total for each category on its own line as `category: number`."}
+                ]}
+            ]
+        })),
+        common::response(
+            "<point mention=\"apple\"> (10,10) </point> <point mention=\"apple\"> (50,10) </point>\n\
+             <point mention=\"pear\"> (90,40) </point>\n\
+             apple: 2\npear: 1",
+            None,
+        ),
+    )
+    .await;
+
+    let request = CountRequest::new(
+        "isaac-test",
+        Image::url("https://example.com/fruit.jpg"),
+        vec!["apple".to_string(), "pear".to_string()],
+    );
+    let response = client.count(request).await.unwrap();
+    let summary: Vec<_> = response
+        .counts
+        .iter()
+        .map(|c| (c.class.as_str(), c.count, c.stated_count, c.mismatch))
+        .collect();
+    assert_eq!(summary, vec![("apple", 2, Some(2), false), ("pear", 1, Some(1), false)]);
+    assert_eq!(response.counts[1].points[0].x, 90);
+}
+
+#[tokio::test]
+async fn flags_stated_count_mismatch() {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({"messages": [{"role": "system", "content": "<hint>POINT</hint>"}]})),
+        common::response(
+            "<point mention=\"sheep\"> (10,10) </point> <point mention=\"sheep\"> (20,10) </point>\n\
+             There are five sheep in the field.",
+            None,
+        ),
+    )
+    .await;
+
+    let request = CountRequest::new(
+        "isaac-test",
+        Image::url("https://example.com/field.jpg"),
+        vec!["sheep".to_string()],
+    );
+    let response = client.count(request).await.unwrap();
+    let sheep = &response.counts[0];
+    assert_eq!(sheep.count, 2);
+    assert_eq!(sheep.stated_count, Some(5));
+    assert!(sheep.mismatch);
+}
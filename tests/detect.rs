@@ -1,4 +1,4 @@
-use perceptron_ai::{BoundingBox, DetectRequest, Image, ImageFormat, Perceptron, Pointing};
+use perceptron_ai::{BoundingBox, DetectRequest, FewShotExample, Image, ImageFormat, Perceptron, Pointing};
 use rstest::rstest;
 use serde_json::json;
 use wiremock::matchers::body_partial_json;
@@ -234,3 +234,153 @@ async fn with_reasoning() {
     assert_eq!(response.reasoning, Some("I see a cat in the image".to_string()));
     assert_single_cat_box(&response);
 }
+
+#[tokio::test]
+async fn system_prompt_override_and_extra_instructions() {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({
+            "messages": [
+                {"role": "system", "content": "<hint>BOX</hint>"},
+                {"role": "system", "content": "Segment every helmet"},
+                {"role": "system", "content": "Ignore reflections in windows"},
+                {"role": "user", "content": [
+                    {"type": "image_url", "image_url": {"url": "https://example.com/img.jpg"}}
+                ]}
+            ]
+        })),
+        common::response(single_box_content(), None),
+    )
+    .await;
+
+    let request = DetectRequest::new("isaac-test", Image::url("https://example.com/img.jpg"))
+        .system_prompt("Segment every helmet")
+        .extra_instructions("Ignore reflections in windows");
+    let response = client.detect(request).await.unwrap();
+    assert_single_cat_box(&response);
+}
+
+#[tokio::test]
+async fn few_shot_examples_precede_request() {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({
+            "messages": [
+                {"role": "system", "content": "<hint>BOX</hint>"},
+                {"role": "system", "content": "Your goal is to segment out the following categories: cat"},
+                {"role": "user", "content": [
+                    {"type": "image_url", "image_url": {"url": "https://example.com/example.jpg"}}
+                ]},
+                {"role": "assistant", "content": r#"<point_box mention="cat"> (1,2) (3,4) </point_box>"#},
+                {"role": "user", "content": [
+                    {"type": "image_url", "image_url": {"url": "https://example.com/img.jpg"}}
+                ]}
+            ]
+        })),
+        common::response(single_box_content(), None),
+    )
+    .await;
+
+    let example = FewShotExample::new(
+        Image::url("https://example.com/example.jpg"),
+        "",
+        Pointing {
+            boxes: vec![BoundingBox {
+                x1: 1,
+                y1: 2,
+                x2: 3,
+                y2: 4,
+                mention: Some("cat".to_string()),
+                timestamp: None,
+            }],
+            ..Default::default()
+        },
+    );
+    let request = DetectRequest::new("isaac-test", Image::url("https://example.com/img.jpg"))
+        .classes(vec!["cat".to_string()])
+        .few_shot(vec![example]);
+    let response = client.detect(request).await.unwrap();
+    assert_single_cat_box(&response);
+}
+
+#[tokio::test]
+async fn output_language_keeps_categories_canonical() {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({
+            "messages": [
+                {"role": "system", "content": "<hint>BOX</hint>"},
+                {"role": "system", "content": "Your goal is to segment out the following categories: cat"},
+                {"role": "system", "content": "Respond in Japanese. Keep the mention attribute of every tag exactly as given."}
+            ]
+        })),
+        common::response(single_box_content(), None),
+    )
+    .await;
+
+    let request = DetectRequest::new("isaac-test", Image::url("https://example.com/img.jpg"))
+        .classes(vec!["cat".to_string()])
+        .output_language("Japanese");
+    let response = client.detect(request).await.unwrap();
+    assert_single_cat_box(&response);
+}
+
+#[tokio::test]
+async fn class_descriptions_and_constraints() {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({
+            "messages": [
+                {"role": "system", "content": "<hint>BOX</hint>"},
+                {"role": "system", "content": "Your goal is to segment out the following categories: helmet (hard hat worn on head), vest"},
+                {"role": "system", "content": "Do not segment the following categories: person. Return at most 5 instances per category. Ignore objects smaller than 20x20 in output coordinates."}
+            ]
+        })),
+        common::response(single_box_content(), None),
+    )
+    .await;
+
+    let request = DetectRequest::new("isaac-test", Image::url("https://example.com/img.jpg"))
+        .describe_class("helmet", "hard hat worn on head")
+        .classes(vec!["helmet".to_string(), "vest".to_string()])
+        .excluded_classes(vec!["person".to_string()])
+        .max_instances_per_class(5)
+        .min_size(20);
+    client.detect(request).await.unwrap();
+}
+
+#[rstest]
+#[case::strict(true, vec!["Helmet"])]
+#[case::lenient(false, vec!["Helmet", "person", "ladder"])]
+#[tokio::test]
+async fn strict_classes_filters_mentions(#[case] strict: bool, #[case] expected: Vec<&str>) {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({"messages": [{"role": "system", "content": "<hint>BOX</hint>"}]})),
+        common::response(
+            r#"<point_box mention="Helmet"> (1,2) (3,4) </point_box><point_box mention="person"> (5,6) (7,8) </point_box><point_box mention="ladder"> (9,10) (11,12) </point_box>"#,
+            None,
+        ),
+    )
+    .await;
+
+    let request = DetectRequest::new("isaac-test", Image::url("https://example.com/img.jpg"))
+        .classes(vec!["helmet".to_string(), "person".to_string()])
+        .excluded_classes(vec!["person".to_string()])
+        .strict_classes(strict);
+    let response = client.detect(request).await.unwrap();
+
+    let mentions: Vec<_> = response
+        .pointing
+        .unwrap()
+        .boxes
+        .into_iter()
+        .map(|b| b.mention.unwrap())
+        .collect();
+    assert_eq!(mentions, expected);
+}
@@ -0,0 +1,83 @@
+use perceptron_ai::{DocumentRequest, FieldValue, Image, Perceptron};
+use rstest::rstest;
+use serde_json::json;
+use wiremock::matchers::body_partial_json;
+
+mod common;
+
+fn fields(names: &[&str]) -> Vec<String> {
+    names.iter().map(|s| s.to_string()).collect()
+}
+
+#[tokio::test]
+async fn typed_fields_with_boxes() {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({
+            "messages": [
+                {"role": "system", "content": "<hint>BOX</hint>"},
+                {"role": "user", "content": [
+                    {"type": "image_url"},
+                    {"type": "text", "text": "Extract these fields from the document: vendor, total, due date. Write one line per field as `field: value`, or `field: null` if the field is not present, and mark where each value appears with a <point_box> whose mention is the field name. Write numbers without units and dates as YYYY-MM-DD."}
+                ]}
+            ]
+        })),
+        common::response(
+            r#"vendor: ACME Corp. <point_box mention="vendor"> (10,10) (90,20) </point_box>
+total: 42.50 <point_box mention="total"> (300,400) (380,420) </point_box>
+due date: null"#,
+            None,
+        ),
+    )
+    .await;
+
+    let request = DocumentRequest::new(
+        "isaac-test",
+        Image::url("https://example.com/receipt.jpg"),
+        fields(&["vendor", "total", "due date"]),
+    );
+    let response = client.document(request).await.unwrap();
+    let values: Vec<_> = response.fields.iter().map(|f| f.value.clone()).collect();
+    assert_eq!(
+        values,
+        vec![
+            Some(FieldValue::Text("ACME Corp.".to_string())),
+            Some(FieldValue::Number(42.5)),
+            None,
+        ]
+    );
+    assert_eq!(response.fields[1].bbox.as_ref().map(|b| b.x1), Some(300));
+    assert!(response.tables.is_empty());
+}
+
+#[rstest]
+#[case::markdown("| Item | Price |\n|---|---|\n| Widget | 3.50 |")]
+#[case::html("<table><tr><th>Item</th><th>Price</th></tr><tr><td>Widget</td><td>3.50</td></tr></table>")]
+#[tokio::test]
+async fn tables(#[case] table: &str) {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({
+            "messages": [
+                {"role": "system", "content": "<hint>BOX</hint>"},
+                {"role": "user", "content": [
+                    {"type": "image_url"},
+                    {"type": "text", "text": "Extract every labeled field from the document. Write one line per field as `field: value` and mark where each value appears with a <point_box> whose mention is the field name. Write numbers without units and dates as YYYY-MM-DD. Then transcribe every table in the document as a Markdown table with a header row."}
+                ]}
+            ]
+        })),
+        common::response(&format!("Invoice number: 1001\n\n{table}"), None),
+    )
+    .await;
+
+    let request =
+        DocumentRequest::new("isaac-test", Image::url("https://example.com/invoice.jpg"), Vec::new()).tables(true);
+    let response = client.document(request).await.unwrap();
+    assert_eq!(response.fields.len(), 1);
+    assert_eq!(response.fields[0].key, "Invoice number");
+    assert_eq!(response.fields[0].value, Some(FieldValue::Number(1001.0)));
+    assert_eq!(response.tables.len(), 1);
+    assert_eq!(response.tables[0].to_csv(), "Item,Price\nWidget,3.50\n");
+}
@@ -0,0 +1,120 @@
+use perceptron_ai::{CountRequest, DetectRequest, Ensemble, Image, Perceptron, QuestionRequest};
+use serde_json::{Value, json};
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+/// Mount one response per sample; each is served once, in mount order.
+async fn mock_samples(server: &MockServer, contents: &[&str]) {
+    for content in contents {
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(common::response(content, None)))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(server)
+            .await;
+    }
+}
+
+fn choices(contents: &[&str]) -> Value {
+    let choices: Vec<Value> = contents
+        .iter()
+        .map(|content| json!({"message": {"content": content, "reasoning_content": null}}))
+        .collect();
+    json!({ "choices": choices })
+}
+
+#[tokio::test]
+async fn detect_keeps_majority_boxes() {
+    let (server, client) = common::setup().await;
+    mock_samples(
+        &server,
+        &[
+            r#"<point_box mention="car"> (100,100) (200,200) </point_box>"#,
+            r#"<point_box mention="car"> (104,100) (204,200) </point_box> <point_box mention="car"> (700,700) (800,800) </point_box>"#,
+            r#"<point_box mention="car"> (102,100) (202,200) </point_box>"#,
+        ],
+    )
+    .await;
+
+    let request = DetectRequest::new("isaac-test", Image::url("https://example.com/street.jpg"))
+        .temperature(0.8)
+        .ensemble(3);
+    let response = client.detect(request).await.unwrap();
+    let boxes = response.pointing.unwrap().boxes;
+    assert_eq!(boxes.len(), 1);
+    assert_eq!((boxes[0].x1, boxes[0].x2), (102, 202));
+    let stats = response.ensemble.unwrap();
+    assert_eq!(stats.samples, 3);
+    assert_eq!(stats.box_agreement, [1.0]);
+}
+
+#[tokio::test]
+async fn question_votes_with_choices() {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({"n": 3})),
+        choices(&["Red.", "Blue.", "red"]),
+    )
+    .await;
+
+    let request = QuestionRequest::new(
+        "isaac-test",
+        "What color is the car?",
+        Image::url("https://example.com/car.jpg"),
+    )
+    .ensemble(Ensemble::new(3).use_choices(true));
+    let response = client.question(request).await.unwrap();
+    assert_eq!(response.content.as_deref(), Some("Red."));
+    assert_eq!(response.ensemble.unwrap().answer_agreement, 2.0 / 3.0);
+}
+
+#[tokio::test]
+async fn missing_choices_are_sampled_separately() {
+    let (server, client) = common::setup().await;
+    common::mock_response(&server, body_partial_json(json!({"n": 2})), choices(&["yes"])).await;
+    let sample = Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(common::response("no", None)))
+        .expect(1);
+    sample.mount(&server).await;
+
+    let request = QuestionRequest::new(
+        "isaac-test",
+        "Is it raining?",
+        Image::url("https://example.com/sky.jpg"),
+    )
+    .ensemble(Ensemble::new(2).use_choices(true));
+    let response = client.question(request).await.unwrap();
+    assert_eq!(response.content.as_deref(), Some("yes"));
+    assert_eq!(response.ensemble.unwrap().samples, 2);
+}
+
+#[tokio::test]
+async fn count_uses_consensus_points() {
+    let (server, client) = common::setup().await;
+    mock_samples(
+        &server,
+        &[
+            "<point> (100,100) </point> <point> (300,300) </point> There are 2 apples.",
+            "<point> (105,100) </point> <point> (302,298) </point> <point> (900,900) </point> There are 3 apples.",
+            "<point> (98,102) </point> <point> (299,301) </point> There are 2 apples.",
+        ],
+    )
+    .await;
+
+    let request = CountRequest::new(
+        "isaac-test",
+        Image::url("https://example.com/apples.jpg"),
+        vec!["apple".to_string()],
+    )
+    .ensemble(Ensemble::new(3).min_votes(2));
+    let response = client.count(request).await.unwrap();
+    assert_eq!(response.counts[0].count, 2);
+    assert_eq!(response.counts[0].stated_count, Some(2));
+    assert!(!response.counts[0].mismatch);
+    assert_eq!(response.ensemble.unwrap().point_agreement, [1.0, 1.0]);
+}
@@ -0,0 +1,120 @@
+use perceptron_ai::{BoundingBox, GroundRequest, Image, ModalityPrompt, Perceptron, PromptProfile, Region, Video};
+use rstest::rstest;
+use serde_json::json;
+use wiremock::matchers::body_partial_json;
+
+mod common;
+
+#[tokio::test]
+async fn single_best_box() {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({
+            "messages": [
+                {"role": "system", "content": "<hint>BOX</hint>"},
+                {"role": "user", "content": [
+                    {"type": "image_url"},
+                    {"type": "text", "text": "Locate the mug left of the laptop. Return exactly one region; if several regions could match, choose the single best match."}
+                ]}
+            ]
+        })),
+        common::response(r#"<point_box mention="mug"> (10,20) (30,40) </point_box>"#, None),
+    )
+    .await;
+
+    let request = GroundRequest::new(
+        "isaac-test",
+        Image::url("https://example.com/desk.jpg"),
+        "the mug left of the laptop",
+    );
+    let response = client.ground(request).await.unwrap();
+    assert_eq!(
+        response.region,
+        Some(Region::Box(BoundingBox {
+            x1: 10,
+            y1: 20,
+            x2: 30,
+            y2: 40,
+            mention: Some("mug".to_string()),
+            timestamp: None,
+        }))
+    );
+    assert_eq!(response.matches.len(), 1);
+}
+
+#[tokio::test]
+async fn uses_grounded_instruction() {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({
+            "messages": [
+                {"role": "system", "content": "<hint>POLYGON</hint>"},
+                {"role": "system", "content": "Outline objects precisely."},
+                {"role": "user"}
+            ]
+        })),
+        common::response(r#"<polygon> (1,1) (5,1) (5,5) </polygon>"#, None),
+    )
+    .await;
+
+    let mut profile = PromptProfile::ISAAC;
+    profile.question.grounded_instruction = Some(ModalityPrompt::uniform("Outline objects precisely."));
+    let client = client.prompt_profile(profile);
+    let request =
+        GroundRequest::new("isaac-test", Image::url("https://example.com/desk.jpg"), "the red cup").polygon(true);
+    let response = client.ground(request).await.unwrap();
+    assert!(matches!(response.region, Some(Region::Polygon(p)) if p.hull.len() == 3));
+}
+
+#[rstest]
+#[case::all_matches(r#"<point_box> (1,1) (2,2) </point_box> <point_box> (3,3) (4,4) </point_box>"#, 2)]
+#[case::no_match("There is no chair in the image.", 0)]
+#[tokio::test]
+async fn all_matches_mode(#[case] content: &str, #[case] expected_matches: usize) {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({
+            "messages": [
+                {"role": "system", "content": "<hint>BOX</hint>"},
+                {"role": "user", "content": [
+                    {"type": "image_url"},
+                    {"type": "text", "text": "Locate every region matching every chair."}
+                ]}
+            ]
+        })),
+        common::response(content, None),
+    )
+    .await;
+
+    let request =
+        GroundRequest::new("isaac-test", Image::url("https://example.com/room.jpg"), "every chair").all_matches(true);
+    let response = client.ground(request).await.unwrap();
+    assert_eq!(response.matches.len(), expected_matches);
+    assert_eq!(response.region.is_some(), expected_matches > 0);
+}
+
+#[tokio::test]
+async fn video_prompt() {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({
+            "messages": [
+                {"role": "system", "content": "<hint>BOX</hint>"},
+                {"role": "user", "content": [
+                    {"type": "video_url"},
+                    {"type": "text", "text": "Locate the referee in the video. Return exactly one region on the frame where it is clearest; if several regions could match, choose the single best match."}
+                ]}
+            ]
+        })),
+        common::response(r#"<point_box mention="referee" t=3.5> (5,5) (20,40) </point_box>"#, None),
+    )
+    .await;
+
+    let request = GroundRequest::new("isaac-test", Video::url("https://example.com/match.mp4"), "the referee");
+    let response = client.ground(request).await.unwrap();
+    assert!(matches!(response.region, Some(Region::Box(b)) if b.timestamp == Some(3.5)));
+}
@@ -0,0 +1,183 @@
+use std::fs;
+use std::path::PathBuf;
+
+use perceptron_ai::{
+    BatchLine, BatchOutcome, BatchRecord, BatchSummary, DetectRequest, Image, QuestionRequest, TaskRequest,
+};
+use serde_json::json;
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+mod common;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("perceptron-jsonl-{}-{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn input_lines() -> String {
+    let lines = [
+        BatchLine {
+            id: Some("street".to_string()),
+            request: TaskRequest::Detect(DetectRequest::new(
+                "detect-model",
+                Image::url("https://example.com/a.jpg"),
+            )),
+        },
+        BatchLine {
+            id: None,
+            request: TaskRequest::Question(QuestionRequest::new(
+                "question-model",
+                "What is this?",
+                Image::url("https://example.com/b.jpg"),
+            )),
+        },
+    ];
+    let mut input: String = lines
+        .iter()
+        .map(|line| serde_json::to_string(line).unwrap() + "\n")
+        .collect();
+    input.push_str("\n{\"id\": \"broken\", \"task\": \"paint\"}\n");
+    input
+}
+
+fn records(path: &PathBuf) -> Vec<BatchRecord> {
+    fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn writes_records_and_summary() {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({"model": "detect-model"})),
+        common::response("<point_box> (1,2) (3,4) </point_box>", None),
+    )
+    .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(body_partial_json(json!({"model": "question-model"})))
+        .respond_with(ResponseTemplate::new(500).set_body_json(json!({"error": {"message": "boom"}})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let dir = temp_dir("run");
+    let (input, output) = (dir.join("input.jsonl"), dir.join("output.jsonl"));
+    fs::write(&input, input_lines()).unwrap();
+    let summary = client.run_jsonl(&input, &output).concurrency(2).run().await.unwrap();
+
+    let expected = BatchSummary {
+        total: 3,
+        succeeded: 1,
+        failed: 2,
+        resumed: 0,
+        pending: 0,
+    };
+    assert_eq!(summary, expected);
+    let written: BatchSummary =
+        serde_json::from_str(&fs::read_to_string(dir.join("output.summary.json")).unwrap()).unwrap();
+    assert_eq!(written, expected);
+
+    let mut records = records(&output);
+    records.sort_by(|a, b| a.id.cmp(&b.id));
+    let ids: Vec<_> = records.iter().map(|r| r.id.as_str()).collect();
+    assert_eq!(ids, ["2", "broken", "street"]);
+    assert!(matches!(&records[0].outcome, BatchOutcome::Error { error } if error.contains("boom")));
+    assert!(matches!(&records[1].outcome, BatchOutcome::Error { error } if error.starts_with("Invalid batch line")));
+    assert!(matches!(&records[2].outcome, BatchOutcome::Response(_)));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn resumes_from_partial_output() {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({"model": "question-model"})),
+        common::response("A cat.", None),
+    )
+    .await;
+
+    let dir = temp_dir("resume");
+    let (input, output) = (dir.join("input.jsonl"), dir.join("output.jsonl"));
+    fs::write(&input, input_lines()).unwrap();
+    fs::write(
+        &output,
+        "{\"id\":\"street\",\"task\":\"detect\",\"response\":{\"content\":\"\",\"reasoning\":null,\"pointing\":null}}\n\
+         {\"id\":\"broken\",\"error\":\"Invalid batch line\"}\n\
+         {\"id\":\"2\",\"task\":\"quest",
+    )
+    .unwrap();
+    let summary = client
+        .run_jsonl(&input, &output)
+        .summary_path(dir.join("summary.json"))
+        .run()
+        .await
+        .unwrap();
+
+    assert_eq!(
+        summary,
+        BatchSummary {
+            total: 3,
+            succeeded: 2,
+            failed: 1,
+            resumed: 2,
+            pending: 0,
+        }
+    );
+    assert!(dir.join("summary.json").exists());
+    let records = records(&output);
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[2].id, "2");
+    assert!(matches!(&records[2].outcome, BatchOutcome::Response(_)));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn retries_failed_lines() {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({"model": "question-model"})),
+        common::response("A cat.", None),
+    )
+    .await;
+
+    let dir = temp_dir("retry");
+    let (input, output) = (dir.join("input.jsonl"), dir.join("output.jsonl"));
+    fs::write(&input, input_lines()).unwrap();
+    fs::write(
+        &output,
+        "{\"id\":\"street\",\"task\":\"detect\",\"response\":{\"content\":\"\",\"reasoning\":null,\"pointing\":null}}\n\
+         {\"id\":\"2\",\"error\":\"API error (500): boom\"}\n",
+    )
+    .unwrap();
+    let summary = client
+        .run_jsonl(&input, &output)
+        .retry_failed(true)
+        .run()
+        .await
+        .unwrap();
+
+    assert_eq!(
+        summary,
+        BatchSummary {
+            total: 3,
+            succeeded: 2,
+            failed: 1,
+            resumed: 1,
+            pending: 0,
+        }
+    );
+    let records = records(&output);
+    let last = records.iter().rfind(|r| r.id == "2").unwrap();
+    assert!(matches!(&last.outcome, BatchOutcome::Response(_)));
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,95 @@
+use perceptron_ai::{ClipTimestamp, KeyFrame, KeyFrameFormat, LocateRequest, Perceptron, Video};
+use rstest::rstest;
+use serde_json::json;
+use wiremock::matchers::body_partial_json;
+
+mod common;
+
+const INSTRUCTION: &str = "Find every time range in the video where this happens: a person enters the room. Write one line per occurrence with a <clip> tag whose t is the start and end time in seconds and whose confidence attribute is between 0 and 1. If it never happens, say so.";
+
+#[tokio::test]
+async fn merges_hits() {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({
+            "messages": [
+                {"role": "system", "content": "<hint>CLIP</hint>"},
+                {"role": "user", "content": [
+                    {"type": "video_url", "video_url": {"url": "https://example.com/room.mp4"}},
+                    {"type": "text", "text": INSTRUCTION}
+                ]}
+            ]
+        })),
+        common::response(
+            r#"<clip t="3 5" confidence="0.7"/>
+<clip t="5.5 8" confidence="0.9"/>
+<clip t="42 44"/>"#,
+            None,
+        ),
+    )
+    .await;
+
+    let request = LocateRequest::new(
+        "isaac-test",
+        Video::url("https://example.com/room.mp4"),
+        "a person enters the room",
+    );
+    let response = client.locate(request).await.unwrap();
+    let hits: Vec<_> = response
+        .hits
+        .iter()
+        .map(|h| (h.timestamp.clone(), h.confidence))
+        .collect();
+    assert_eq!(
+        hits,
+        vec![
+            (ClipTimestamp::Range { start: 3.0, end: 8.0 }, Some(0.9)),
+            (ClipTimestamp::Range { start: 42.0, end: 44.0 }, None),
+        ]
+    );
+}
+
+#[rstest]
+#[case::point(
+    KeyFrameFormat::Point,
+    "On the same line, add a <point> on the event whose t is its most representative moment.",
+    r#"<clip t="3 5"/> <point t=4> (10,20) </point>"#
+)]
+#[case::bbox(
+    KeyFrameFormat::Box,
+    "On the same line, add a <point_box> around the event whose t is its most representative moment.",
+    r#"<clip t="3 5"/> <point_box t=4> (10,20) (30,40) </point_box>"#
+)]
+#[tokio::test]
+async fn key_frames(#[case] format: KeyFrameFormat, #[case] key_frame_text: &str, #[case] content: &str) {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({
+            "messages": [
+                {"role": "system", "content": "<hint>CLIP</hint>"},
+                {"role": "user", "content": [
+                    {"type": "video_url"},
+                    {"type": "text", "text": format!("{INSTRUCTION} {key_frame_text}")}
+                ]}
+            ]
+        })),
+        common::response(content, None),
+    )
+    .await;
+
+    let request = LocateRequest::new(
+        "isaac-test",
+        Video::url("https://example.com/room.mp4"),
+        "a person enters the room",
+    )
+    .key_frames(format);
+    let response = client.locate(request).await.unwrap();
+    assert_eq!(response.hits.len(), 1);
+    match &response.hits[0].key_frame {
+        Some(KeyFrame::Point(p)) => assert_eq!((p.x, p.timestamp), (10, Some(4.0))),
+        Some(KeyFrame::Box(b)) => assert_eq!((b.x2, b.timestamp), (30, Some(4.0))),
+        None => panic!("expected a key frame"),
+    }
+}
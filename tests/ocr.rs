@@ -1,4 +1,7 @@
-use perceptron_ai::{Image, ImageFormat, OcrMode, OcrRequest, Perceptron};
+use perceptron_ai::{
+    AnalyzeRequest, CaptionRequest, DetectRequest, Image, ImageFormat, Model, OcrMode, OcrRequest, Perceptron,
+    PerceptronError, PointingResponse, QuestionRequest, TextResponse,
+};
 use rstest::rstest;
 use serde_json::json;
 use wiremock::matchers::body_partial_json;
@@ -168,3 +171,116 @@ async fn custom_prompt() {
     let response = client.ocr(request).await.unwrap();
     assert_eq!(response.content, Some("2024-01-15".to_string()));
 }
+
+#[tokio::test]
+async fn extra_instructions_follow_default_system() {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({
+            "messages": [
+                {"role": "system", "content": "<hint>THINK</hint>"},
+                {"role": "system", "content": "You are an OCR (Optical Character Recognition) system. Accurately detect, extract, and transcribe all readable text from the image."},
+                {"role": "system", "content": "Preserve the original line breaks."},
+                {"role": "user"}
+            ]
+        })),
+        common::response("Hello", None),
+    )
+    .await;
+
+    let request = OcrRequest::new("isaac-test", Image::url("https://example.com/doc.jpg"))
+        .reasoning(true)
+        .extra_instructions("Preserve the original line breaks.");
+    let response = client.ocr(request).await.unwrap();
+    assert_eq!(response.content, Some("Hello".to_string()));
+}
+
+#[tokio::test]
+async fn layout_mode() {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({
+            "messages": [
+                {"role": "system", "content": "<hint>BOX</hint>"},
+                {"role": "system", "content": "You are an OCR (Optical Character Recognition) system. Accurately detect, extract, and transcribe all readable text from the image."},
+                {"role": "user", "content": [
+                    {"type": "image_url"},
+                    {"type": "text", "text": "Transcribe every readable line of text in the image in reading order. Output each line as a <point_box> whose mention attribute is the exact text of the line, writing double quotes as &quot;. Separate text blocks with a blank line."}
+                ]}
+            ]
+        })),
+        common::response(
+            "<point_box mention=\"Invoice\"> (10,10) (90,30) </point_box>\n\n\
+             <point_box mention=\"Total 9.99\"> (10,100) (110,120) </point_box>",
+            None,
+        ),
+    )
+    .await;
+
+    // The request's own mode is replaced by layout mode.
+    let request = OcrRequest::new("isaac-test", Image::url("https://example.com/doc.jpg")).mode(OcrMode::Markdown);
+    let response = client.ocr_layout(request).await.unwrap();
+    let layout = response.layout.expect("layout");
+    assert_eq!(layout.blocks.len(), 2);
+    assert_eq!(layout.lines[1].text, "Total 9.99");
+    assert_eq!(layout.lines[1].parent, Some(1));
+    let words: Vec<_> = layout.words.iter().map(|w| w.text.as_str()).collect();
+    assert_eq!(words, vec!["Invoice", "Total", "9.99"]);
+}
+
+/// An implementation with only the original methods, answering OCR with fixed layout output.
+struct FixedOcr;
+
+fn no_points() -> PointingResponse {
+    PointingResponse {
+        content: None,
+        reasoning: None,
+        cached: false,
+        pointing: None,
+        ensemble: None,
+    }
+}
+
+impl Perceptron for FixedOcr {
+    async fn models(&self) -> Result<Vec<Model>, PerceptronError> {
+        Ok(Vec::new())
+    }
+
+    async fn model(&self, id: &str) -> Result<Model, PerceptronError> {
+        Err(PerceptronError::RequestFailed(format!("no model {id}")))
+    }
+
+    async fn question(&self, _request: QuestionRequest) -> Result<PointingResponse, PerceptronError> {
+        Ok(no_points())
+    }
+
+    async fn analyze(&self, _request: AnalyzeRequest) -> Result<PointingResponse, PerceptronError> {
+        Ok(no_points())
+    }
+
+    async fn caption(&self, _request: CaptionRequest) -> Result<PointingResponse, PerceptronError> {
+        Ok(no_points())
+    }
+
+    async fn ocr(&self, request: OcrRequest) -> Result<TextResponse, PerceptronError> {
+        assert_eq!(request.mode, OcrMode::Layout);
+        Ok(TextResponse {
+            content: Some(r#"<point_box mention="Hello world"> (0,0) (100,20) </point_box>"#.to_string()),
+            reasoning: None,
+            cached: false,
+        })
+    }
+
+    async fn detect(&self, _request: DetectRequest) -> Result<PointingResponse, PerceptronError> {
+        Ok(no_points())
+    }
+}
+
+#[tokio::test]
+async fn layout_defaults_to_ocr() {
+    let request = OcrRequest::new("isaac-test", Image::url("https://example.com/doc.jpg"));
+    let response = FixedOcr.ocr_layout(request).await.unwrap();
+    assert_eq!(response.layout.expect("layout").lines[0].text, "Hello world");
+}
@@ -0,0 +1,43 @@
+use perceptron_ai::{CaptionRequest, DetectRequest, Image, Perceptron, PerceptronClient};
+use serde_json::json;
+
+#[test]
+fn preview_detect() {
+    let client = PerceptronClient::new();
+    let request = DetectRequest::new("isaac-test", Image::url("https://example.com/img.jpg"))
+        .classes(vec!["car".to_string()])
+        .temperature(0.0);
+    let wire_request = client.preview(request);
+    assert_eq!(
+        serde_json::to_value(&wire_request).unwrap(),
+        json!({
+            "messages": [
+                {"role": "system", "content": "<hint>BOX</hint>"},
+                {"role": "system", "content": "Your goal is to segment out the following categories: car"},
+                {"role": "user", "content": [
+                    {"type": "image_url", "image_url": {"url": "https://example.com/img.jpg"}}
+                ]}
+            ],
+            "model": "isaac-test",
+            "temperature": 0.0
+        })
+    );
+}
+
+#[tokio::test]
+async fn dry_run_records_instead_of_sending() {
+    // No server is listening, so any request actually sent would fail.
+    let client = PerceptronClient::new().base_url("http://127.0.0.1:9").dry_run();
+    let request = CaptionRequest::new("isaac-test", Image::url("https://example.com/img.jpg"));
+    let expected = client.preview(request.clone());
+
+    let response = client.clone().caption(request).await.unwrap();
+    assert_eq!(response.content, None);
+    assert_eq!(response.pointing, None);
+    assert_eq!(client.recorded_requests(), vec![expected]);
+}
+
+#[test]
+fn recorded_requests_empty_without_dry_run() {
+    assert!(PerceptronClient::new().recorded_requests().is_empty());
+}
@@ -0,0 +1,144 @@
+use perceptron_ai::{
+    CaptionRequest, DetectRequest, Image, ModalityPrompt, OcrMode, OcrRequest, Perceptron, PromptProfile,
+    QuestionRequest,
+};
+use rstest::rstest;
+use serde_json::json;
+use wiremock::matchers::body_partial_json;
+
+mod common;
+
+fn retail_profile() -> PromptProfile {
+    let mut profile = PromptProfile::ISAAC;
+    profile.caption.concise = ModalityPrompt::uniform("Describe the item for a product listing.".to_string());
+    profile.detect.category_template = ModalityPrompt::uniform("Find every {categories} on the shelf".to_string());
+    profile.ocr.system = None;
+    profile.ocr.markdown = "Transcribe the price tag as Markdown.".into();
+    profile.question.open_instruction = Some(ModalityPrompt::uniform("Answer as a retail assistant."));
+    profile
+}
+
+#[tokio::test]
+async fn custom_caption_prompt() {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({
+            "messages": [
+                {"role": "system", "content": "<hint>BOX</hint>"},
+                {"role": "user", "content": [
+                    {"type": "image_url"},
+                    {"type": "text", "text": "Describe the item for a product listing."}
+                ]}
+            ]
+        })),
+        common::response("A red shoe", None),
+    )
+    .await;
+
+    let client = client.prompt_profile(retail_profile());
+    let request = CaptionRequest::new("isaac-test", Image::url("https://example.com/img.jpg"));
+    let response = client.caption(request).await.unwrap();
+    assert_eq!(response.content, Some("A red shoe".to_string()));
+}
+
+#[tokio::test]
+async fn custom_detect_prompt() {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({
+            "messages": [
+                {"role": "system", "content": "<hint>BOX</hint>"},
+                {"role": "system", "content": "Find every shoe, bag on the shelf"}
+            ]
+        })),
+        common::response(r#"<point_box mention="shoe"> (1,2) (3,4) </point_box>"#, None),
+    )
+    .await;
+
+    let client = client.prompt_profile(retail_profile());
+    let request = DetectRequest::new("isaac-test", Image::url("https://example.com/img.jpg"))
+        .classes(vec!["shoe".to_string(), "bag".to_string()]);
+    let response = client.detect(request).await.unwrap();
+    assert!(response.pointing.is_some());
+}
+
+#[tokio::test]
+async fn custom_ocr_prompt_without_system() {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({
+            "messages": [
+                {"role": "user", "content": [
+                    {"type": "image_url"},
+                    {"type": "text", "text": "Transcribe the price tag as Markdown."}
+                ]}
+            ]
+        })),
+        common::response("**$9.99**", None),
+    )
+    .await;
+
+    let client = client.prompt_profile(retail_profile());
+    let request = OcrRequest::new("isaac-test", Image::url("https://example.com/tag.jpg")).mode(OcrMode::Markdown);
+    let response = client.ocr(request).await.unwrap();
+    assert_eq!(response.content, Some("**$9.99**".to_string()));
+}
+
+#[tokio::test]
+async fn custom_question_instruction() {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({
+            "messages": [
+                {"role": "system", "content": "Answer as a retail assistant."},
+                {"role": "user", "content": [
+                    {"type": "image_url"},
+                    {"type": "text", "text": "Is this on sale?"}
+                ]}
+            ]
+        })),
+        common::response("Yes", None),
+    )
+    .await;
+
+    let client = client.prompt_profile(retail_profile());
+    let request = QuestionRequest::new(
+        "isaac-test",
+        "Is this on sale?",
+        Image::url("https://example.com/img.jpg"),
+    );
+    let response = client.question(request).await.unwrap();
+    assert_eq!(response.content, Some("Yes".to_string()));
+}
+
+#[rstest]
+#[case::registered("acme-isaac-ft-3", "Describe the item for a product listing.")]
+#[case::fallback("isaac-test", "Provide a concise, human-friendly caption for the upcoming image.")]
+#[tokio::test]
+async fn registered_profile_by_model(#[case] model: &str, #[case] expected_text: &str) {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({
+            "model": model,
+            "messages": [
+                {"role": "system", "content": "<hint>BOX</hint>"},
+                {"role": "user", "content": [
+                    {"type": "image_url"},
+                    {"type": "text", "text": expected_text}
+                ]}
+            ]
+        })),
+        common::response("A red shoe", None),
+    )
+    .await;
+
+    let client = client.register_prompt_profile("acme-*-ft-*", retail_profile());
+    let request = CaptionRequest::new(model, Image::url("https://example.com/img.jpg"));
+    let response = client.caption(request).await.unwrap();
+    assert_eq!(response.content, Some("A red shoe".to_string()));
+}
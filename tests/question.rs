@@ -1,4 +1,4 @@
-use perceptron_ai::{Image, ImageFormat, OutputFormat, Perceptron, QuestionRequest};
+use perceptron_ai::{FewShotExample, Image, ImageFormat, OutputFormat, Perceptron, QuestionRequest};
 use rstest::rstest;
 use serde_json::json;
 use wiremock::matchers::body_partial_json;
@@ -117,3 +117,39 @@ async fn with_reasoning() {
     assert_eq!(response.content, Some("Three cats".to_string()));
     assert_eq!(response.reasoning, Some("I count the cats".to_string()));
 }
+
+#[tokio::test]
+async fn few_shot_text_answer() {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({
+            "messages": [
+                {"role": "user", "content": [
+                    {"type": "image_url", "image_url": {"url": "https://example.com/example.jpg"}},
+                    {"type": "text", "text": "What color is the car?"}
+                ]},
+                {"role": "assistant", "content": "Red."},
+                {"role": "user", "content": [
+                    {"type": "image_url", "image_url": {"url": "https://example.com/img.jpg"}},
+                    {"type": "text", "text": "What color is the cat?"}
+                ]}
+            ]
+        })),
+        common::response("Orange.", None),
+    )
+    .await;
+
+    let request = QuestionRequest::new(
+        "isaac-test",
+        "What color is the cat?",
+        Image::url("https://example.com/img.jpg"),
+    )
+    .few_shot(vec![FewShotExample::new(
+        Image::url("https://example.com/example.jpg"),
+        "What color is the car?",
+        "Red.",
+    )]);
+    let response = client.question(request).await.unwrap();
+    assert_eq!(response.content, Some("Orange.".to_string()));
+}
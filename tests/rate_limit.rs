@@ -0,0 +1,75 @@
+use std::time::{Duration, Instant};
+
+use perceptron_ai::{Image, Perceptron, PerceptronError, QuestionRequest, RateLimiter};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+mod common;
+
+fn request() -> QuestionRequest {
+    QuestionRequest::new("isaac-test", "What is this?", Image::url("https://example.com/img.jpg"))
+}
+
+#[tokio::test]
+async fn waits_for_reset_from_headers_across_clones() {
+    let (server, client) = common::setup().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(common::response("A cat.", None))
+                .insert_header("x-ratelimit-remaining-requests", "0")
+                .insert_header("x-ratelimit-reset-requests", "300ms"),
+        )
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let client = client.rate_limiter(RateLimiter::new());
+    let other = client.clone();
+    client.question(request()).await.unwrap();
+    let started = Instant::now();
+    other.question(request()).await.unwrap();
+    assert!(started.elapsed() >= Duration::from_millis(250));
+}
+
+#[tokio::test]
+async fn waits_out_too_many_requests() {
+    let (server, client) = common::setup().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "0.3"))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(common::response("A cat.", None)))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = client.rate_limiter(RateLimiter::new().requests_per_minute(600));
+    let started = Instant::now();
+    let response = client.question(request()).await.unwrap();
+    assert_eq!(response.content.as_deref(), Some("A cat."));
+    assert!(started.elapsed() >= Duration::from_millis(250));
+}
+
+#[tokio::test]
+async fn too_many_requests_fail_without_limiter() {
+    let (server, client) = common::setup().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "0.3"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let error = client.question(request()).await.unwrap_err();
+    assert!(
+        matches!(error, PerceptronError::ApiError { status: 429, .. }),
+        "{error:?}"
+    );
+}
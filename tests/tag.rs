@@ -0,0 +1,54 @@
+use perceptron_ai::{Image, Perceptron, TagRequest, Taxonomy, TaxonomyNode};
+use serde_json::json;
+use wiremock::matchers::body_partial_json;
+
+mod common;
+
+fn taxonomy() -> Taxonomy {
+    Taxonomy::new(vec![
+        TaxonomyNode::new("animal")
+            .child(TaxonomyNode::new("dog").alias("puppy"))
+            .child(TaxonomyNode::new("cat")),
+        TaxonomyNode::new("scene").child(TaxonomyNode::new("beach")),
+    ])
+}
+
+#[tokio::test]
+async fn maps_tags_onto_taxonomy() {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({
+            "messages": [
+                {"role": "system", "content": "<hint>BOX</hint>"},
+                {"role": "user", "content": [
+                    {"type": "image_url"},
+                    {"type": "text", "text": "Tag the image. Use only tags from this list: animal; animal > dog; animal > cat; scene; scene > beach. Write one line per tag that applies, choosing the most specific tag. If the tag can be located, write it as a <point_box> around the evidence whose mention is the tag; otherwise write the tag alone."}
+                ]}
+            ]
+        })),
+        common::response(
+            "<point_box mention=\"puppy\"> (10,20) (30,40) </point_box>\nbeach\nsunset",
+            None,
+        ),
+    )
+    .await;
+
+    let request = TagRequest::new("isaac-test", Image::url("https://example.com/beach.jpg"), taxonomy());
+    let response = client.tag(request).await.unwrap();
+    let tags: Vec<_> = response
+        .tags
+        .iter()
+        .map(|t| (t.path.join("/"), t.inferred, t.evidence.is_some()))
+        .collect();
+    assert_eq!(
+        tags,
+        vec![
+            ("animal".to_string(), true, false),
+            ("animal/dog".to_string(), false, true),
+            ("scene".to_string(), true, false),
+            ("scene/beach".to_string(), false, false),
+        ]
+    );
+    assert_eq!(response.rejected, ["sunset"]);
+}
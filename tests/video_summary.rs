@@ -0,0 +1,78 @@
+use perceptron_ai::{ClipTimestamp, Perceptron, Video, VideoSummaryRequest};
+use rstest::rstest;
+use serde_json::json;
+use wiremock::matchers::body_partial_json;
+
+mod common;
+
+const ANSWER: &str = r#"A cyclist rides through the city and stops at a cafe.
+<clip mention="Departure" t="0 20"/> The cyclist leaves home.
+<clip mention="Ride" t="15 70"/> Riding through traffic.
+<clip mention="Cafe" t="70 100"/> Coffee break at a cafe."#;
+
+#[tokio::test]
+async fn summary_and_chapters() {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({
+            "messages": [
+                {"role": "system", "content": "<hint>CLIP</hint>"},
+                {"role": "user", "content": [
+                    {"type": "video_url", "video_url": {"url": "https://example.com/ride.mp4"}},
+                    {"type": "text", "text": "Summarize the video in two or three sentences. Then split it into chapters in chronological order, writing one line per chapter: a <clip> tag whose mention is a short chapter title and whose t is the start and end time in seconds, followed by a one-sentence description of the chapter."}
+                ]}
+            ]
+        })),
+        common::response(ANSWER, None),
+    )
+    .await;
+
+    let request = VideoSummaryRequest::new("isaac-test", Video::url("https://example.com/ride.mp4"));
+    let response = client.summarize_video(request).await.unwrap();
+    assert_eq!(
+        response.summary.as_deref(),
+        Some("A cyclist rides through the city and stops at a cafe.")
+    );
+    let titles: Vec<_> = response.chapters.iter().map(|c| c.title.as_str()).collect();
+    assert_eq!(titles, ["Departure", "Ride", "Cafe"]);
+    assert_eq!(
+        response.chapters[0].range,
+        ClipTimestamp::Range { start: 0.0, end: 15.0 }
+    );
+    assert!(response.chapters[0].adjusted);
+    assert_eq!(
+        response.chapters[2].description.as_deref(),
+        Some("Coffee break at a cafe.")
+    );
+}
+
+#[rstest]
+#[case::within(120.0, 100.0, false)]
+#[case::clamped(90.0, 90.0, true)]
+#[tokio::test]
+async fn clamps_to_duration(#[case] duration: f32, #[case] last_end: f32, #[case] adjusted: bool) {
+    let (server, client) = common::setup().await;
+    common::mock_response(
+        &server,
+        body_partial_json(json!({
+            "messages": [
+                {"role": "system", "content": "<hint>CLIP</hint>"},
+                {"role": "user", "content": [
+                    {"type": "video_url"},
+                    {"type": "text", "text": format!(
+                        "Summarize the video in two or three sentences. Then split it into chapters in chronological order, writing one line per chapter: a <clip> tag whose mention is a short chapter title and whose t is the start and end time in seconds, followed by a one-sentence description of the chapter. The video is {duration} seconds long."
+                    )}
+                ]}
+            ]
+        })),
+        common::response(ANSWER, None),
+    )
+    .await;
+
+    let request = VideoSummaryRequest::new("isaac-test", Video::url("https://example.com/ride.mp4")).duration(duration);
+    let response = client.summarize_video(request).await.unwrap();
+    let last = response.chapters.last().unwrap();
+    assert!(matches!(last.range, ClipTimestamp::Range { end, .. } if end == last_end));
+    assert_eq!(last.adjusted, adjusted);
+}
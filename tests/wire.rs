@@ -1,6 +1,9 @@
 use perceptron_ai::{
-    AnalyzeRequest, CaptionRequest, CaptionStyle, DetectRequest, Image, ImageFormat, Modality, Model, OcrMode,
-    OcrRequest, OutputFormat, Point, Pointing, PointingResponse, QuestionRequest, SamplingParameter, TextResponse,
+    AnalyzeRequest, CaptionLength, CaptionRequest, CaptionStyle, ChatMessage, ChatRequest, ClassifyRequest,
+    CompareRequest, CountRequest, DetectRequest, DocumentRequest, GroundRequest, Image, ImageFormat, KeyFrameFormat,
+    LengthPolicy, LocateRequest, Modality, Model, OcrMode, OcrRequest, OutputFormat, PerceptronClient, Point, Pointing,
+    PointingResponse, QuestionRequest, SamplingParameter, TagRequest, Taxonomy, TaxonomyNode, TextResponse, Video,
+    VideoSummaryRequest,
 };
 use serde_json::json;
 
@@ -50,6 +53,10 @@ fn caption_request_all_fields() {
         &CaptionRequest::new("model-v1", Image::base64(ImageFormat::Jpeg, "data"))
             .style(CaptionStyle::Detailed)
             .output_format(OutputFormat::Box)
+            .text_only(false)
+            .length(CaptionLength::words(10, 20))
+            .length_policy(LengthPolicy::Retry { attempts: 2 })
+            .output_language("Japanese")
             .reasoning(true)
             .temperature(0.5)
             .top_p(0.25)
@@ -61,6 +68,10 @@ fn caption_request_all_fields() {
             "media": {"modality": "image", "type": "base64", "format": "jpeg", "data": "data"},
             "style": "detailed",
             "output_format": "box",
+            "text_only": false,
+            "length": {"unit": "words", "min": 10, "max": 20},
+            "length_policy": {"retry": {"attempts": 2}},
+            "output_language": "Japanese",
             "model": "model-v1",
             "reasoning": true,
             "temperature": 0.5,
@@ -105,6 +116,11 @@ fn detect_request_all_fields() {
     roundtrip(
         &DetectRequest::new("model-v1", Image::url("https://example.com/img.jpg"))
             .classes(vec!["cat".to_string(), "dog".to_string()])
+            .describe_class("cat", "domestic cat")
+            .excluded_classes(vec!["person".to_string()])
+            .max_instances_per_class(3)
+            .min_size(10)
+            .strict_classes(true)
             .reasoning(true)
             .temperature(0.5)
             .top_p(0.25)
@@ -115,6 +131,11 @@ fn detect_request_all_fields() {
         json!({
             "media": {"type": "url", "modality": "image", "src": "https://example.com/img.jpg"},
             "classes": ["cat", "dog"],
+            "class_descriptions": {"cat": "domestic cat"},
+            "excluded_classes": ["person"],
+            "max_instances_per_class": 3,
+            "min_size": 10,
+            "strict_classes": true,
             "model": "model-v1",
             "reasoning": true,
             "temperature": 0.5,
@@ -132,6 +153,8 @@ fn question_request_all_fields() {
     roundtrip(
         &QuestionRequest::new("model-v1", "What is this?", Image::url("https://example.com/img.jpg"))
             .output_format(OutputFormat::Point)
+            .system_prompt("Answer briefly.")
+            .extra_instructions("Use metric units.")
             .reasoning(true)
             .temperature(0.5)
             .top_p(0.25)
@@ -143,6 +166,8 @@ fn question_request_all_fields() {
             "question": "What is this?",
             "media": {"type": "url", "modality": "image", "src": "https://example.com/img.jpg"},
             "output_format": "point",
+            "system_prompt": "Answer briefly.",
+            "extra_instructions": "Use metric units.",
             "model": "model-v1",
             "reasoning": true,
             "temperature": 0.5,
@@ -183,6 +208,272 @@ fn model_all_fields() {
     );
 }
 
+#[test]
+fn chat_completion_request_preview() {
+    let client = PerceptronClient::new();
+    roundtrip(
+        &client.preview(
+            OcrRequest::new("isaac-test", Image::url("https://example.com/doc.jpg"))
+                .mode(OcrMode::Html)
+                .max_tokens(256),
+        ),
+        json!({
+            "messages": [
+                {"role": "system", "content": "You are an OCR (Optical Character Recognition) system. Accurately detect, extract, and transcribe all readable text from the image."},
+                {"role": "user", "content": [
+                    {"type": "image_url", "image_url": {"url": "https://example.com/doc.jpg"}},
+                    {"type": "text", "text": "Transcribe every readable word in the image using HTML markup."}
+                ]}
+            ],
+            "model": "isaac-test",
+            "max_completion_tokens": 256
+        }),
+    );
+}
+
+#[test]
+fn classify_request_all_fields() {
+    roundtrip(
+        &ClassifyRequest::new(
+            "model-v1",
+            Image::url("https://example.com/img.jpg"),
+            vec!["cat".to_string(), "dog".to_string()],
+        )
+        .multi_label(true)
+        .system_prompt("You are a pet classifier.")
+        .reasoning(true)
+        .temperature(0.5)
+        .max_tokens(100),
+        json!({
+            "media": {"type": "url", "modality": "image", "src": "https://example.com/img.jpg"},
+            "labels": ["cat", "dog"],
+            "multi_label": true,
+            "system_prompt": "You are a pet classifier.",
+            "model": "model-v1",
+            "reasoning": true,
+            "temperature": 0.5,
+            "top_p": null,
+            "top_k": null,
+            "frequency_penalty": null,
+            "presence_penalty": null,
+            "max_tokens": 100
+        }),
+    );
+}
+
+#[test]
+fn count_request_all_fields() {
+    roundtrip(
+        &CountRequest::new(
+            "model-v1",
+            Image::url("https://example.com/img.jpg"),
+            vec!["apple".to_string()],
+        )
+        .extra_instructions("Ignore apples in the background.")
+        .top_k(50)
+        .presence_penalty(0.125),
+        json!({
+            "media": {"type": "url", "modality": "image", "src": "https://example.com/img.jpg"},
+            "classes": ["apple"],
+            "extra_instructions": "Ignore apples in the background.",
+            "model": "model-v1",
+            "reasoning": null,
+            "temperature": null,
+            "top_p": null,
+            "top_k": 50,
+            "frequency_penalty": null,
+            "presence_penalty": 0.125,
+            "max_tokens": null
+        }),
+    );
+}
+
+#[test]
+fn ground_request_all_fields() {
+    roundtrip(
+        &GroundRequest::new("model-v1", Image::url("https://example.com/img.jpg"), "the red mug")
+            .polygon(true)
+            .all_matches(false)
+            .temperature(0.5),
+        json!({
+            "media": {"type": "url", "modality": "image", "src": "https://example.com/img.jpg"},
+            "expression": "the red mug",
+            "polygon": true,
+            "all_matches": false,
+            "model": "model-v1",
+            "reasoning": null,
+            "temperature": 0.5,
+            "top_p": null,
+            "top_k": null,
+            "frequency_penalty": null,
+            "presence_penalty": null,
+            "max_tokens": null
+        }),
+    );
+}
+
+#[test]
+fn compare_request_all_fields() {
+    roundtrip(
+        &CompareRequest::new(
+            "model-v1",
+            Image::url("https://example.com/a.jpg"),
+            Image::url("https://example.com/b.jpg"),
+        )
+        .max_tokens(256),
+        json!({
+            "before": {"type": "url", "modality": "image", "src": "https://example.com/a.jpg"},
+            "after": {"type": "url", "modality": "image", "src": "https://example.com/b.jpg"},
+            "model": "model-v1",
+            "reasoning": null,
+            "temperature": null,
+            "top_p": null,
+            "top_k": null,
+            "frequency_penalty": null,
+            "presence_penalty": null,
+            "max_tokens": 256
+        }),
+    );
+}
+
+#[test]
+fn document_request_all_fields() {
+    roundtrip(
+        &DocumentRequest::new(
+            "model-v1",
+            Image::url("https://example.com/receipt.jpg"),
+            vec!["total".to_string()],
+        )
+        .tables(true),
+        json!({
+            "image": {"type": "url", "src": "https://example.com/receipt.jpg"},
+            "fields": ["total"],
+            "tables": true,
+            "model": "model-v1",
+            "reasoning": null,
+            "temperature": null,
+            "top_p": null,
+            "top_k": null,
+            "frequency_penalty": null,
+            "presence_penalty": null,
+            "max_tokens": null
+        }),
+    );
+}
+
+#[test]
+fn video_summary_request_all_fields() {
+    roundtrip(
+        &VideoSummaryRequest::new("model-v1", Video::url("https://example.com/vid.mp4")).duration(95.5),
+        json!({
+            "video": {"type": "url", "src": "https://example.com/vid.mp4"},
+            "duration": 95.5,
+            "model": "model-v1",
+            "reasoning": null,
+            "temperature": null,
+            "top_p": null,
+            "top_k": null,
+            "frequency_penalty": null,
+            "presence_penalty": null,
+            "max_tokens": null
+        }),
+    );
+}
+
+#[test]
+fn locate_request_all_fields() {
+    roundtrip(
+        &LocateRequest::new("model-v1", Video::url("https://example.com/vid.mp4"), "a door opens")
+            .key_frames(KeyFrameFormat::Box)
+            .merge_gap(2.0),
+        json!({
+            "video": {"type": "url", "src": "https://example.com/vid.mp4"},
+            "query": "a door opens",
+            "key_frames": "box",
+            "merge_gap": 2.0,
+            "model": "model-v1",
+            "reasoning": null,
+            "temperature": null,
+            "top_p": null,
+            "top_k": null,
+            "frequency_penalty": null,
+            "presence_penalty": null,
+            "max_tokens": null
+        }),
+    );
+}
+
+#[test]
+fn tag_request_all_fields() {
+    roundtrip(
+        &TagRequest::new(
+            "model-v1",
+            Image::url("https://example.com/img.jpg"),
+            Taxonomy::new(vec![
+                TaxonomyNode::new("animal").child(TaxonomyNode::new("dog").alias("puppy")),
+            ]),
+        ),
+        json!({
+            "media": {"type": "url", "modality": "image", "src": "https://example.com/img.jpg"},
+            "taxonomy": [
+                {"label": "animal", "children": [{"label": "dog", "aliases": ["puppy"]}]}
+            ],
+            "model": "model-v1",
+            "reasoning": null,
+            "temperature": null,
+            "top_p": null,
+            "top_k": null,
+            "frequency_penalty": null,
+            "presence_penalty": null,
+            "max_tokens": null
+        }),
+    );
+}
+
+#[test]
+fn chat_request_all_fields() {
+    roundtrip(
+        &ChatRequest::new(
+            "model-v1",
+            vec![
+                ChatMessage::system("Be brief."),
+                ChatMessage::user(vec![
+                    Image::url("https://example.com/img.jpg").into(),
+                    "What is this?".into(),
+                ]),
+                ChatMessage::assistant("A cat."),
+            ],
+        )
+        .output_format(OutputFormat::Box)
+        .reasoning(true)
+        .temperature(0.5)
+        .top_p(0.25)
+        .top_k(50)
+        .frequency_penalty(0.5)
+        .presence_penalty(0.125)
+        .max_tokens(100),
+        json!({
+            "messages": [
+                {"role": "system", "content": "Be brief."},
+                {"role": "user", "content": [
+                    {"image": {"type": "url", "src": "https://example.com/img.jpg"}},
+                    {"text": "What is this?"}
+                ]},
+                {"role": "assistant", "content": "A cat."}
+            ],
+            "output_format": "box",
+            "model": "model-v1",
+            "reasoning": true,
+            "temperature": 0.5,
+            "top_p": 0.25,
+            "top_k": 50,
+            "frequency_penalty": 0.5,
+            "presence_penalty": 0.125,
+            "max_tokens": 100
+        }),
+    );
+}
+
 // --- Responses ---
 
 #[test]
@@ -191,6 +482,7 @@ fn text_response() {
         &TextResponse {
             content: Some("hello".to_string()),
             reasoning: Some("thinking".to_string()),
+            cached: false,
         },
         json!({"content": "hello", "reasoning": "thinking"}),
     );
@@ -202,6 +494,7 @@ fn pointing_response() {
         &PointingResponse {
             content: Some("a cat".to_string()),
             reasoning: Some("I see fur".to_string()),
+            cached: false,
             pointing: Some(Pointing {
                 points: vec![Point {
                     x: 50,
@@ -211,6 +504,7 @@ fn pointing_response() {
                 }],
                 ..Default::default()
             }),
+            ensemble: None,
         },
         json!({
             "content": "a cat",